@@ -0,0 +1,951 @@
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::json;
+use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
+
+use test_recorder_core::activity;
+use test_recorder_core::api::ApiEvent;
+use test_recorder_core::clips;
+use test_recorder_core::clock::SystemClock;
+use test_recorder_core::controller::{ControllerOutcome, RecordingController};
+use test_recorder_core::crypto::{self, Cipher};
+use test_recorder_core::integrity;
+use test_recorder_core::models::AppConfig;
+use test_recorder_core::order_detector::{self, OrderDetection, OrderDetector};
+use test_recorder_core::process_monitor::ProcessMonitor;
+use test_recorder_core::profiles::{self, BrokerProfile};
+use test_recorder_core::recorder::ScreenRecorder;
+use test_recorder_core::redaction::Redactor;
+use test_recorder_core::schedule::{self, MarketSchedule};
+use test_recorder_core::session;
+use test_recorder_core::state_machine::RecorderEvent;
+use test_recorder_core::session::SessionSummary;
+use test_recorder_core::thumbnails;
+use test_recorder_core::export::{self, ExportFilter, ExportFormat};
+use test_recorder_core::models::TradeEvent;
+use test_recorder_core::positions;
+use test_recorder_core::report;
+use test_recorder_core::trade_log;
+use test_recorder_core::trade_store::{TradeQuery, TradeStore};
+use test_recorder_core::webhooks::{self, Webhooks};
+use test_recorder_core::window_capture::WindowCapture;
+
+/// 웹뷰 없이 HTS 녹화를 수행하는 헤드리스 CLI
+#[derive(Parser)]
+#[command(name = "test-recorder-cli", version, about)]
+struct Cli {
+    /// JSON 설정 파일 경로 (없으면 기본 설정)
+    #[arg(long, global = true)]
+    config: Option<String>,
+
+    /// 녹화 출력 디렉토리 (설정 파일 값보다 우선)
+    #[arg(long, global = true)]
+    output_dir: Option<String>,
+
+    /// 결과를 JSON으로 출력 (스크립트용)
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// HTS 실행을 감시하면서 자동으로 녹화 시작/중지
+    Watch,
+    /// 지금 바로 녹화 (Ctrl+C 또는 --duration 후 종료)
+    Record {
+        /// 녹화 시간 (초)
+        #[arg(long)]
+        duration: Option<u64>,
+    },
+    /// 전체 화면 스크린샷 저장
+    Screenshot,
+    /// 열려 있는 윈도우 목록
+    ListWindows,
+    /// 녹화 세션 목록
+    ListSessions,
+    /// 세션을 다른 디렉토리로 내보내기
+    Export {
+        /// 세션 ID (예: recording_20240102_090000)
+        session: String,
+        /// 내보낼 디렉토리
+        #[arg(long)]
+        dest: String,
+    },
+    /// 세션 해시 체인 검증 (위변조 확인)
+    Verify {
+        /// 세션 ID
+        session: String,
+    },
+    /// 장 시간 스케줄 상태 (현재 시간대, 다음 개장)
+    Schedule,
+    /// 저장된 거래 내역 (최신순)
+    Trades {
+        /// 이 날짜부터 (YYYY-MM-DD, KST)
+        #[arg(long)]
+        from: Option<String>,
+        /// 이 날짜까지 (YYYY-MM-DD, KST, 그날 포함)
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+        #[arg(long)]
+        limit: Option<usize>,
+        /// 메모/태그/종목 검색어
+        #[arg(long)]
+        search: Option<String>,
+        /// 이 태그가 붙은 거래만 (여러 번 지정 가능)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// 실행 평가가 이 값 이상인 거래만 (1~5)
+        #[arg(long)]
+        min_rating: Option<u8>,
+    },
+    /// 왕복 매매별 실현 손익 (선입선출, 설정의 수수료/거래세 적용)
+    Positions {
+        /// 이 날짜부터 (YYYY-MM-DD, KST)
+        #[arg(long)]
+        from: Option<String>,
+        /// 이 날짜까지 (YYYY-MM-DD, KST, 그날 포함)
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// 일일 리포트 HTML 생성 (`reports/report_YYYYMMDD.html`)
+    Report {
+        /// 리포트 날짜 (YYYY-MM-DD, KST, 없으면 오늘)
+        #[arg(long)]
+        date: Option<String>,
+    },
+    /// 거래, 왕복 매매, 세션, 프레임 트랙을 분석용 파일로 내보내기
+    ExportData {
+        /// 내보낼 디렉토리
+        #[arg(long)]
+        out: String,
+        /// csv 또는 parquet
+        #[arg(long, value_enum, default_value_t = DataFormat::Csv)]
+        format: DataFormat,
+        /// 이 날짜부터 (YYYY-MM-DD, KST)
+        #[arg(long)]
+        from: Option<String>,
+        /// 이 날짜까지 (YYYY-MM-DD, KST, 그날 포함)
+        #[arg(long)]
+        to: Option<String>,
+        /// 이 종목만 (여러 번 지정 가능)
+        #[arg(long = "ticker")]
+        tickers: Vec<String>,
+    },
+    /// 세션의 타임라인 썸네일, 스프라이트 시트, WebVTT 생성 (`thumbs/`)
+    Thumbnails {
+        /// 세션 ID
+        session: String,
+    },
+    /// 세션 구간의 프레임을 디렉토리로 내보내기 (커서/클릭 기록이 있으면 프레임에 표시)
+    Clip {
+        /// 세션 ID
+        session: String,
+        /// 구간 시작 (세션 시작부터 ms)
+        #[arg(long)]
+        start_ms: u64,
+        /// 구간 끝 (ms, 포함)
+        #[arg(long)]
+        end_ms: u64,
+        /// 내보낼 디렉토리
+        #[arg(long)]
+        dest: String,
+        /// 커서/클릭을 그리지 않음
+        #[arg(long)]
+        no_cursor: bool,
+    },
+    /// 세션에서 화면이 많이 바뀐 구간 (활동 곡선은 --json)
+    Activity {
+        /// 세션 ID
+        session: String,
+        /// 곡선 점 개수
+        #[arg(long, default_value_t = activity::DEFAULT_POINTS)]
+        points: usize,
+    },
+    /// 설정된 주문/체결 로그를 읽어서 녹화 세션 위치와 함께 출력
+    ImportTrades {
+        /// 로그 ID (설정의 trade_logs)
+        source: String,
+        /// 시각만 적힌 로그의 날짜 (YYYY-MM-DD, 없으면 파일 수정 날짜)
+        #[arg(long)]
+        date: Option<String>,
+    },
+    /// 증권사 HTS 프로필 관리
+    Profiles {
+        #[command(subcommand)]
+        action: ProfilesCommand,
+    },
+    /// 웹훅 시험 전송, 보내지 못한 대기열 확인
+    Webhooks {
+        #[command(subcommand)]
+        action: WebhooksCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfilesCommand {
+    /// 프로필 목록
+    List,
+    /// 프로필을 파일로 내보내기
+    Export {
+        /// 내보낼 파일 경로
+        path: String,
+        /// 내보낼 프로필 ID (없으면 전체, 여러 번 지정 가능)
+        #[arg(long = "id")]
+        ids: Vec<String>,
+    },
+    /// 프로필 파일을 가져와서 --config 설정 파일에 저장
+    Import {
+        /// 가져올 파일 경로
+        path: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum WebhooksCommand {
+    /// 엔드포인트에 시험 이벤트를 바로 보냄 (대기열/재시도 없음)
+    Test {
+        /// 엔드포인트 ID (설정의 webhooks.endpoints)
+        id: String,
+    },
+    /// 아직 보내지 못한 전송 목록
+    Pending,
+    /// 시도할 때가 된 전송을 지금 보냄
+    Flush,
+}
+
+/// `export-data` 파일 형식
+#[derive(Clone, Copy, ValueEnum)]
+enum DataFormat {
+    Csv,
+    Parquet,
+}
+
+/// JSON 모드면 JSON 한 줄, 아니면 사람이 읽는 텍스트 출력
+fn emit<T: Serialize>(json_mode: bool, value: &T, human: impl FnOnce()) {
+    if json_mode {
+        match serde_json::to_string(value) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("Failed to serialize output: {}", e),
+        }
+    } else {
+        human();
+    }
+}
+
+/// 앱과 같은 이벤트를 웹훅 대기열에 추가 (받는 엔드포인트가 없으면 아무것도 안 함)
+fn notify<T: Serialize>(webhooks: &Webhooks, event: &str, payload: &T) {
+    let result = ApiEvent::new(event, payload).and_then(|event| webhooks.enqueue(&event));
+    if let Err(e) = result {
+        eprintln!("{}", e);
+    }
+}
+
+/// KST 날짜의 00:00 (UTC)
+fn kst_day_start(date: &str) -> Result<chrono::DateTime<chrono::Utc>, String> {
+    use chrono::TimeZone;
+
+    let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {}", e))?;
+    schedule::kst()
+        .from_local_datetime(&day.and_time(chrono::NaiveTime::MIN))
+        .single()
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .ok_or_else(|| format!("Invalid date: {}", date))
+}
+
+/// `--from`/`--to` 날짜를 `[from 00:00, to 다음날 00:00)` 구간 조회 조건으로
+fn kst_date_query(from: Option<&str>, to: Option<&str>) -> Result<TradeQuery, String> {
+    Ok(TradeQuery {
+        from: from.map(kst_day_start).transpose()?,
+        to: to.map(kst_day_start).transpose()?.map(|t| t + chrono::Duration::days(1)),
+        ..Default::default()
+    })
+}
+
+fn load_config(cli: &Cli) -> Result<AppConfig, String> {
+    let mut config = match &cli.config {
+        Some(path) => AppConfig::load_from_file(path)?,
+        None => AppConfig::default(),
+    };
+    if let Some(output_dir) = &cli.output_dir {
+        config.output_dir = output_dir.clone();
+    }
+    Ok(config)
+}
+
+/// 암호화가 켜져 있으면 `TEST_RECORDER_PASSPHRASE` 또는 키체인으로 키 열기
+fn open_cipher(config: &AppConfig) -> Result<Option<Cipher>, String> {
+    crypto::open_cipher(&config.encryption, &config.output_dir, None)
+}
+
+fn new_recorder(config: &AppConfig) -> Result<ScreenRecorder, String> {
+    let recorder = ScreenRecorder::new();
+    recorder.set_cipher(open_cipher(config)?);
+    Ok(recorder)
+}
+
+async fn watch(config: &AppConfig, json_mode: bool) -> Result<(), String> {
+    let mut controller = RecordingController::new(new_recorder(config)?, true);
+    let monitor = ProcessMonitor::new(config.hts.profiles.clone());
+    let schedule = MarketSchedule::load(&config.schedule, Arc::new(SystemClock))?;
+    let cipher = open_cipher(config)?;
+    let mut tailers = trade_log::open_tailers(&config.trade_logs);
+    let mut store = TradeStore::open(&config.output_dir, cipher.as_ref())?;
+    let mut check_interval = interval(Duration::from_millis(config.hts.check_interval_ms));
+    // 자동 리포트를 이미 처리한 날짜 (KST)
+    let mut report_checked: Option<chrono::NaiveDate> = None;
+    let mut was_hts_running = false;
+
+    // 녹화 상태, HTS 감지, 거래를 설정된 웹훅으로도 보냄
+    webhooks::validate_config(&config.webhooks)?;
+    let webhooks = Arc::new(Webhooks::open(config.webhooks.clone(), &config.output_dir)?);
+    webhooks.spawn_sender()?;
+
+    // 주문창 감지는 별도 스레드에서 돌리고 감지 결과만 받아서 기록
+    let detector = OrderDetector::new(config.order_detection.clone(), &config.hts.profiles)?;
+    let cancel = CancellationToken::new();
+    let (order_tx, order_rx) = mpsc::channel::<OrderDetection>();
+    let detector_thread = {
+        let detector = Arc::new(Mutex::new(detector));
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            OrderDetector::run(detector, cancel, move |detection| {
+                let _ = order_tx.send(detection);
+            })
+        })
+    };
+
+    emit(json_mode, &json!({ "event": "watching" }), || {
+        println!("Watching for HTS processes... (Ctrl+C to quit)");
+    });
+
+    loop {
+        tokio::select! {
+            _ = check_interval.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+
+        let detected = monitor.detect();
+        let hts_name = detected.as_ref().map(|d| d.process_name.clone());
+        controller.set_detected_profile(detected);
+        if hts_name.is_some() != was_hts_running {
+            was_hts_running = hts_name.is_some();
+            notify(&webhooks, "hts-detected", &was_hts_running);
+        }
+        let schedule_event = if schedule.is_open() {
+            RecorderEvent::ScheduleOpened
+        } else {
+            RecorderEvent::ScheduleClosed
+        };
+        let event = if hts_name.is_some() {
+            RecorderEvent::HtsDetected
+        } else {
+            RecorderEvent::HtsExited
+        };
+        let disk_full = controller.recorder().is_disk_full();
+
+        let result = tokio::task::block_in_place(|| {
+            let scheduled = controller.handle(schedule_event, config)?;
+            let outcome = controller.handle(event, config)?;
+            let outcome = ControllerOutcome {
+                started: outcome.started.or(scheduled.started),
+                summary: outcome.summary.or(scheduled.summary),
+                stopping: None,
+            };
+            if disk_full {
+                controller.handle(RecorderEvent::DiskFull, config)
+            } else {
+                Ok(outcome)
+            }
+        });
+        report_transitions(&mut controller, &webhooks, json_mode);
+
+        match result {
+            Ok(outcome) => report_outcome(&outcome, hts_name.as_deref(), config, cipher.as_ref(), &webhooks, json_mode),
+            Err(e) => eprintln!("{}", e),
+        }
+
+        for detection in order_rx.try_iter() {
+            record_order(&controller, &mut store, config, cipher.as_ref(), &webhooks, &detection, json_mode);
+        }
+        for tailer in &mut tailers {
+            let events = match tailer.poll() {
+                Ok(events) => events,
+                Err(e) => {
+                    eprintln!("Trade log {}: {}", tailer.source().id, e);
+                    continue;
+                }
+            };
+            for event in events {
+                let event = record_trade(&controller, &mut store, event);
+                notify(&webhooks, "trade-detected", &event);
+                emit(json_mode, &json!({ "event": "trade-detected", "trade": event }), || {
+                    println!(
+                        "Trade: {:?} {} x{} @{}",
+                        event.action,
+                        event.ticker.as_deref().unwrap_or("-"),
+                        event.quantity.unwrap_or(0),
+                        event.price.unwrap_or(0.0)
+                    );
+                });
+            }
+        }
+
+        // 그날 마지막 장 시간대가 끝나고 녹화가 멈추면 일일 리포트
+        let now = chrono::Utc::now();
+        let today = now.with_timezone(&schedule::kst()).date_naive();
+        let recording = controller.recorder().is_recording();
+        if config.report.auto_generate
+            && report_checked != Some(today)
+            && report::report_due(&config.output_dir, today, schedule.day_close(today), now, recording)
+        {
+            report_checked = Some(today);
+            let written = tokio::task::block_in_place(|| {
+                let daily = report::collect(&config.output_dir, today, cipher.as_ref(), &config.fees)?;
+                if daily.is_empty() {
+                    return Ok(None);
+                }
+                report::write_report(&config.output_dir, &daily, cipher.as_ref()).map(Some)
+            });
+            match written {
+                Ok(Some(path)) => emit(json_mode, &json!({ "event": "report-generated", "path": path }), || {
+                    println!("Daily report written: {}", path.display());
+                }),
+                Ok(None) => {}
+                Err(e) => eprintln!("Failed to generate daily report: {}", e),
+            }
+        }
+    }
+
+    cancel.cancel();
+    let _ = detector_thread.join();
+
+    if controller.recorder().is_recording() {
+        let outcome = tokio::task::block_in_place(|| controller.handle(RecorderEvent::ManualStop, config))?;
+        report_transitions(&mut controller, &webhooks, json_mode);
+        report_outcome(&outcome, None, config, cipher.as_ref(), &webhooks, json_mode);
+    }
+
+    Ok(())
+}
+
+fn report_transitions(controller: &mut RecordingController, webhooks: &Webhooks, json_mode: bool) {
+    for transition in controller.take_transitions() {
+        notify(webhooks, "recording-state", &transition);
+        emit(json_mode, &json!({ "event": "recording-state", "transition": transition }), || {
+            println!("State: {:?} -> {:?} ({:?})", transition.from, transition.to, transition.reason);
+        });
+    }
+}
+
+/// 거래를 녹화 중인 세션 위치/해시 체인과 거래 내역에 기록
+fn record_trade(controller: &RecordingController, store: &mut TradeStore, mut event: TradeEvent) -> TradeEvent {
+    let recorder = controller.recorder();
+    event.session = recorder.session_position(event.timestamp);
+    if let Err(e) = recorder.append_trade_event(&event) {
+        eprintln!("{}", e);
+    }
+    if let Err(e) = store.record(event.clone()) {
+        eprintln!("Failed to save trade: {}", e);
+    }
+    event
+}
+
+/// 주문창 이미지를 세션(녹화 중이 아니면 출력 디렉토리)에 저장하고 해시 체인에 추가
+fn record_order(
+    controller: &RecordingController,
+    store: &mut TradeStore,
+    config: &AppConfig,
+    cipher: Option<&Cipher>,
+    webhooks: &Webhooks,
+    detection: &OrderDetection,
+    json_mode: bool,
+) {
+    let recorder = controller.recorder();
+    let dir = recorder.get_output_path().unwrap_or_else(|| config.output_dir.clone());
+
+    // 주문창에도 계좌번호가 보이므로 녹화 프레임과 같은 가림 적용
+    let detected = controller.detected_profile();
+    let redactor = match Redactor::new(config.redaction_for(detected)) {
+        Ok(redactor) => redactor,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let hts_window = WindowCapture::find_window_rect(&config.hts.window_matcher_for(detected));
+    let event = match order_detector::save_detection(detection, Path::new(&dir), &redactor, hts_window, cipher) {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let event = record_trade(controller, store, event);
+    notify(webhooks, "trade-detected", &event);
+
+    emit(json_mode, &json!({ "event": "trade-detected", "trade": event }), || {
+        println!(
+            "Order dialog: {:?} ({:.0}%) {}",
+            event.action,
+            detection.confidence * 100.0,
+            detection.window_title
+        );
+    });
+}
+
+fn report_outcome(
+    outcome: &ControllerOutcome,
+    hts_name: Option<&str>,
+    config: &AppConfig,
+    cipher: Option<&Cipher>,
+    webhooks: &Webhooks,
+    json_mode: bool,
+) {
+    if let Some(path) = &outcome.started {
+        let session_id = Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        notify(webhooks, "recording-started", &json!({ "session_id": session_id, "path": path }));
+        emit(json_mode, &json!({ "event": "recording-started", "hts_name": hts_name, "path": path }), || {
+            println!("Recording to: {}", path);
+        });
+    }
+    if let Some(summary) = &outcome.summary {
+        notify(webhooks, "recording-stopped", summary);
+        emit(json_mode, &json!({ "event": "recording-stopped", "summary": summary }), || {
+            println!("Recording saved to: {} ({} frames)", summary.path, summary.frame_count);
+        });
+        generate_thumbnails(config, cipher, summary, json_mode);
+    }
+}
+
+/// 녹화가 끝난 세션의 스크러빙용 썸네일 생성 (설정에서 끄지 않았으면)
+fn generate_thumbnails(config: &AppConfig, cipher: Option<&Cipher>, summary: &SessionSummary, json_mode: bool) {
+    if !config.thumbnails.enabled {
+        return;
+    }
+    let result = tokio::task::block_in_place(|| {
+        thumbnails::generate_session(Path::new(&summary.path), &config.thumbnails, cipher)
+    });
+    match result {
+        Ok(tracks) => emit(
+            json_mode,
+            &json!({ "event": "thumbnails-ready", "session_id": summary.session_id, "tracks": tracks }),
+            || println!("Thumbnails generated: {} tracks", tracks.len()),
+        ),
+        Err(e) => eprintln!("Failed to generate thumbnails for {}: {}", summary.session_id, e),
+    }
+}
+
+async fn record(config: &AppConfig, duration: Option<u64>, json_mode: bool) -> Result<(), String> {
+    let recorder = new_recorder(config)?;
+    let detected = ProcessMonitor::new(config.hts.profiles.clone()).detect();
+    let path = recorder.start_recording(config, detected.as_ref())?;
+
+    emit(json_mode, &json!({ "event": "recording-started", "path": path }), || {
+        println!("Recording to: {} (Ctrl+C to stop)", path);
+    });
+
+    match duration {
+        Some(secs) => {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(secs)) => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        None => {
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+
+    let summary = tokio::task::block_in_place(|| recorder.stop_recording())?;
+    emit(json_mode, &json!({ "event": "recording-stopped", "summary": summary }), || {
+        println!(
+            "Recording saved to: {} ({} frames, {}s, {} dropped)",
+            summary.path, summary.frame_count, summary.duration_secs, summary.dropped_frames
+        );
+    });
+    generate_thumbnails(config, open_cipher(config)?.as_ref(), &summary, json_mode);
+
+    Ok(())
+}
+
+fn profiles_command(
+    config_path: &Option<String>,
+    config: AppConfig,
+    action: ProfilesCommand,
+    json_mode: bool,
+) -> Result<(), String> {
+    match action {
+        ProfilesCommand::List => {
+            let profiles = &config.hts.profiles;
+            emit(json_mode, profiles, || {
+                for p in profiles {
+                    let processes: Vec<&str> = p.process_names.iter().map(|n| n.pattern.as_str()).collect();
+                    println!("{}\t{}\t{}", p.id, p.name, processes.join(", "));
+                }
+            });
+        }
+        ProfilesCommand::Export { path, ids } => {
+            let selected: Vec<BrokerProfile> = config
+                .hts
+                .profiles
+                .into_iter()
+                .filter(|p| ids.is_empty() || ids.contains(&p.id))
+                .collect();
+            profiles::export_profiles(&path, &selected)?;
+            emit(json_mode, &json!({ "path": path, "count": selected.len() }), || {
+                println!("Exported {} profiles to {}", selected.len(), path);
+            });
+        }
+        ProfilesCommand::Import { path } => {
+            let config_path = config_path
+                .as_deref()
+                .ok_or_else(|| "--config is required to save imported profiles".to_string())?;
+            let imported = profiles::import_profiles(&path)?;
+            let count = imported.len();
+
+            // --output-dir 등 명령행 값이 설정 파일에 섞이지 않도록 파일에서 다시 읽음
+            let mut file_config = AppConfig::load_from_file(config_path)?;
+            let replaced = profiles::merge_profiles(&mut file_config.hts.profiles, imported);
+            file_config.save_to_file(config_path)?;
+            emit(json_mode, &json!({ "imported": count, "replaced": replaced }), || {
+                println!("Imported {} profiles ({} replaced) into {}", count, replaced, config_path);
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn webhooks_command(config: &AppConfig, action: WebhooksCommand, json_mode: bool) -> Result<(), String> {
+    webhooks::validate_config(&config.webhooks)?;
+    let webhooks = Webhooks::open(config.webhooks.clone(), &config.output_dir)?;
+
+    match action {
+        WebhooksCommand::Test { id } => {
+            tokio::task::block_in_place(|| webhooks.send_test(&id))?;
+            emit(json_mode, &json!({ "sent": id }), || println!("Test event sent to {}", id));
+        }
+        WebhooksCommand::Pending => {
+            let pending = webhooks.pending()?;
+            emit(json_mode, &pending, || {
+                for d in &pending {
+                    println!(
+                        "{}  {:<12} {:<18} attempts={} next={} {}",
+                        d.created_at.format("%Y-%m-%d %H:%M:%S"),
+                        d.endpoint_id,
+                        d.event,
+                        d.attempts,
+                        d.next_attempt_at.format("%H:%M:%S"),
+                        d.last_error.as_deref().unwrap_or("")
+                    );
+                }
+                println!("{} pending", pending.len());
+            });
+        }
+        WebhooksCommand::Flush => {
+            let stats = tokio::task::block_in_place(|| webhooks.process_due(chrono::Utc::now()))?;
+            emit(json_mode, &stats, || {
+                println!("Sent {}, retrying {}, dropped {}", stats.sent, stats.retrying, stats.dropped);
+            });
+        }
+    }
+
+    Ok(())
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let config = load_config(&cli)?;
+    let cli_config_path = cli.config.clone();
+    let json_mode = cli.json;
+
+    match cli.command {
+        Command::Watch => watch(&config, json_mode).await?,
+        Command::Trades {
+            from,
+            to,
+            offset,
+            limit,
+            search,
+            tags,
+            min_rating,
+        } => {
+            let query = TradeQuery {
+                offset,
+                limit,
+                text: search,
+                tags,
+                min_rating,
+                ..kst_date_query(from.as_deref(), to.as_deref())?
+            };
+            let page = TradeStore::open(&config.output_dir, open_cipher(&config)?.as_ref())?.query(&query)?;
+            emit(json_mode, &page, || {
+                for trade in &page.events {
+                    println!(
+                        "{}\t{:?}\t{:?}\t{}\t{}\t{}",
+                        trade.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                        trade.source,
+                        trade.action,
+                        trade.ticker.as_deref().unwrap_or("-"),
+                        trade.quantity.unwrap_or(0),
+                        trade.price.unwrap_or(0.0)
+                    );
+                }
+                println!("{}-{} of {}", page.offset + 1, page.offset + page.events.len(), page.total);
+            });
+        }
+        Command::Positions { from, to } => {
+            let query = kst_date_query(from.as_deref(), to.as_deref())?;
+            let events = TradeStore::open(&config.output_dir, open_cipher(&config)?.as_ref())?.matching(&query)?;
+            let report = positions::compute_positions(&events, &config.fees);
+            emit(json_mode, &report, || {
+                for trip in &report.round_trips {
+                    println!(
+                        "{}\t{}\t{}주\t{:.0} -> {:.0}\t{:+.0}원 ({:+.2}%)\t{}초",
+                        trip.closed_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                        trip.instrument_name.as_deref().unwrap_or(&trip.ticker),
+                        trip.quantity,
+                        trip.avg_entry_price,
+                        trip.avg_exit_price,
+                        trip.net_pnl,
+                        trip.return_pct,
+                        trip.holding_secs
+                    );
+                }
+                for position in &report.open_positions {
+                    println!("보유\t{}\t{}주 @{:.0}", position.ticker, position.quantity, position.avg_price);
+                }
+                let summary = &report.summary;
+                println!(
+                    "{} round trips ({} wins, {} losses), net {:+.0} (fees {:.0}, taxes {:.0})",
+                    summary.round_trips, summary.wins, summary.losses, summary.net_pnl, summary.fees, summary.taxes
+                );
+                if !report.unmatched_sells.is_empty() {
+                    println!("{} sells without a matching buy", report.unmatched_sells.len());
+                }
+            });
+        }
+        Command::Report { date } => {
+            let date = match date {
+                Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|e| format!("Invalid date: {}", e))?,
+                None => chrono::Utc::now().with_timezone(&schedule::kst()).date_naive(),
+            };
+            let path = report::write_daily_report(&config.output_dir, date, open_cipher(&config)?.as_ref(), &config.fees)?;
+            emit(json_mode, &json!({ "path": path }), || println!("{}", path.display()));
+        }
+        Command::ExportData {
+            out,
+            format,
+            from,
+            to,
+            tickers,
+        } => {
+            let range = kst_date_query(from.as_deref(), to.as_deref())?;
+            let filter = ExportFilter {
+                from: range.from,
+                to: range.to,
+                tickers,
+            };
+            let format = match format {
+                DataFormat::Csv => ExportFormat::Csv,
+                DataFormat::Parquet => ExportFormat::Parquet,
+            };
+            let files = tokio::task::block_in_place(|| {
+                export::export_data(&config.output_dir, &out, format, &filter, open_cipher(&config)?.as_ref(), &config.fees)
+            })?;
+            emit(json_mode, &files, || {
+                for file in &files {
+                    println!("{}\t{} rows\t{}", file.dataset, file.rows, file.path);
+                }
+            });
+        }
+        Command::Thumbnails { session } => {
+            let session_dir = session::session_path(&config.output_dir, &session)?;
+            let tracks = tokio::task::block_in_place(|| {
+                thumbnails::generate_session(&session_dir, &config.thumbnails, open_cipher(&config)?.as_ref())
+            })?;
+            emit(json_mode, &tracks, || {
+                for track in &tracks {
+                    println!("{}\t{} thumbnails\t{}", track.track, track.count, track.vtt);
+                }
+            });
+        }
+        Command::Clip {
+            session,
+            start_ms,
+            end_ms,
+            dest,
+            no_cursor,
+        } => {
+            let render_input = config.input_tracking.render_in_clips && !no_cursor;
+            let clip = tokio::task::block_in_place(|| {
+                let cipher = open_cipher(&config)?;
+                clips::export_clip(&config.output_dir, &session, start_ms, end_ms, &dest, cipher.as_ref(), render_input)
+            })?;
+            emit(json_mode, &clip, || println!("{} ({} frames)", clip.path, clip.frames));
+        }
+        Command::Activity { session, points } => {
+            let session_dir = session::session_path(&config.output_dir, &session)?;
+            let timeline = activity::load_timeline(&session_dir, points)?;
+            emit(json_mode, &timeline, || {
+                for burst in &timeline.bursts {
+                    println!(
+                        "{}s-{}s\tpeak {:.0}% at {}s (frame {})",
+                        burst.start_ms / 1000,
+                        burst.end_ms / 1000,
+                        burst.peak_score * 100.0,
+                        burst.peak_offset_ms / 1000,
+                        burst.peak_frame
+                    );
+                }
+                println!("{} bursts (threshold {:.0}%)", timeline.bursts.len(), timeline.burst_threshold * 100.0);
+            });
+        }
+        Command::ImportTrades { source, date } => {
+            let source = config
+                .trade_logs
+                .iter()
+                .find(|s| s.id == source)
+                .ok_or_else(|| format!("Trade log '{}' not found", source))?;
+            let date = date
+                .map(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d"))
+                .transpose()
+                .map_err(|e| format!("Invalid date: {}", e))?;
+
+            let mut events = trade_log::import_file(source, date)?;
+            let cipher = open_cipher(&config)?;
+            let timelines = session::load_timelines(&config.output_dir, cipher.as_ref())?;
+            trade_log::align_trades(&mut events, &timelines);
+
+            let mut store = TradeStore::open(&config.output_dir, cipher.as_ref())?;
+            for event in &events {
+                if !store.contains_order(event)? {
+                    store.record(event.clone())?;
+                }
+            }
+            emit(json_mode, &events, || {
+                for trade in &events {
+                    let position = trade
+                        .session
+                        .as_ref()
+                        .map(|s| format!("{} +{}ms", s.session_id, s.offset_ms))
+                        .unwrap_or_else(|| "-".to_string());
+                    println!(
+                        "{}\t{:?}\t{}\t{}\t{}\t{}",
+                        trade.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S"),
+                        trade.action,
+                        trade.ticker.as_deref().unwrap_or("-"),
+                        trade.quantity.unwrap_or(0),
+                        trade.price.unwrap_or(0.0),
+                        position
+                    );
+                }
+            });
+        }
+        Command::Record { duration } => record(&config, duration, json_mode).await?,
+        Command::Screenshot => {
+            let redactor = Redactor::new(config.redaction.clone())?;
+            let path = WindowCapture::capture_screen_and_save(
+                &config.output_dir,
+                "screenshot",
+                Some((&redactor, &config.hts.window_matcher())),
+                open_cipher(&config)?.as_ref(),
+            )?;
+            emit(json_mode, &json!({ "path": path }), || println!("{}", path));
+        }
+        Command::ListWindows => {
+            let windows = WindowCapture::list_all_windows()?;
+            emit(json_mode, &windows, || {
+                for window in &windows {
+                    println!("{}", window);
+                }
+            });
+        }
+        Command::ListSessions => {
+            let sessions = session::list_sessions(&config.output_dir)?;
+            emit(json_mode, &sessions, || {
+                for s in &sessions {
+                    println!("{}\t{} frames\t{} bytes", s.id, s.frame_count, s.total_bytes);
+                }
+            });
+        }
+        Command::Export { session, dest } => {
+            let cipher = open_cipher(&config)?;
+            let path = session::export_session(&config.output_dir, &session, &dest, cipher.as_ref())?;
+            emit(json_mode, &json!({ "session": session, "path": path }), || println!("{}", path));
+        }
+        Command::Schedule => {
+            let status = MarketSchedule::load(&config.schedule, Arc::new(SystemClock))?.status();
+            emit(json_mode, &status, || {
+                if !status.enabled {
+                    println!("Schedule disabled (recording follows HTS detection only)");
+                    return;
+                }
+                match status.session {
+                    Some(session) => println!("Open: {:?}", session),
+                    None => println!("Closed"),
+                }
+                if let Some(next) = status.next_open {
+                    println!("Next open: {}", next.with_timezone(&chrono::Local));
+                }
+            });
+        }
+        Command::Profiles { action } => profiles_command(&cli_config_path, config, action, json_mode)?,
+        Command::Webhooks { action } => webhooks_command(&config, action, json_mode)?,
+        Command::Verify { session } => {
+            let session_dir = session::session_path(&config.output_dir, &session)?;
+            let trusted = integrity::trusted_key(&config.integrity, &config.output_dir)?;
+            let report = integrity::verify_session(&session_dir, open_cipher(&config)?.as_ref(), trusted.as_ref())?;
+            emit(json_mode, &report, || {
+                match &report.first_broken {
+                    None if report.valid => println!("OK: {} entries verified", report.entries_checked),
+                    None => {
+                        if let Some(error) = &report.manifest_error {
+                            println!("FAILED: {}", error);
+                        } else if report.root_matches_manifest != Some(true) {
+                            println!("FAILED: chain does not match manifest root/length");
+                        } else if let Some(status) = report.signature.filter(|s| *s != integrity::SignatureStatus::Valid) {
+                            println!("FAILED: signature {:?}", status);
+                        }
+                        for file in &report.unreferenced_files {
+                            println!("FAILED: frame not in chain: {}", file);
+                        }
+                    }
+                    Some(broken) => println!("FAILED at entry {}: {}", broken.index, broken.reason),
+                }
+            });
+            if !report.valid {
+                std::process::exit(2);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    let json_mode = cli.json;
+
+    if let Err(e) = run(cli).await {
+        if json_mode {
+            println!("{}", json!({ "error": e }));
+        } else {
+            eprintln!("Error: {}", e);
+        }
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,274 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::tracks;
+
+/// 프레임별 화면 변화량 트랙 파일
+pub const ACTIVITY_TRACK: &str = "activity.jsonl";
+
+/// 변화량을 비교하는 블록 격자 (16:9 화면 기준 정사각형에 가깝게)
+const GRID_COLS: u32 = 32;
+const GRID_ROWS: u32 = 18;
+
+/// 블록 평균 밝기를 낼 때 가로/세로 몇 픽셀마다 하나씩 볼지
+const SAMPLE_STEP: u32 = 4;
+
+/// 블록 평균 밝기(0~255)가 이보다 많이 바뀌면 변한 블록 (커서 깜빡임, 압축 노이즈 무시)
+const BLOCK_THRESHOLD: u8 = 6;
+
+/// 타임라인 곡선 기본 점 개수
+pub const DEFAULT_POINTS: usize = 300;
+
+/// 이 점수 미만은 활동 구간으로 보지 않음 (시세 숫자만 바뀌는 정도)
+const MIN_BURST_SCORE: f32 = 0.05;
+
+/// 활동 구간 사이가 이보다 짧으면 하나로 합침
+const BURST_MERGE_GAP_MS: u64 = 3_000;
+
+/// 프레임의 블록별 평균 밝기 (이전 프레임과 비교용)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSignature {
+    width: u32,
+    height: u32,
+    blocks: Vec<u8>,
+}
+
+impl BlockSignature {
+    /// RGBA 버퍼에서 계산 (인코더 스레드에서 호출)
+    pub fn from_rgba(width: u32, height: u32, rgba: &[u8]) -> Self {
+        let mut sums = vec![0u32; (GRID_COLS * GRID_ROWS) as usize];
+        let mut counts = vec![0u32; sums.len()];
+
+        if width > 0 && height > 0 && rgba.len() >= (width * height * 4) as usize {
+            for y in (0..height).step_by(SAMPLE_STEP as usize) {
+                let row = (y * GRID_ROWS / height) * GRID_COLS;
+                for x in (0..width).step_by(SAMPLE_STEP as usize) {
+                    let i = ((y * width + x) * 4) as usize;
+                    // 정수 근사 휘도 (0.299R + 0.587G + 0.114B)
+                    let luma = (rgba[i] as u32 * 77 + rgba[i + 1] as u32 * 150 + rgba[i + 2] as u32 * 29) >> 8;
+                    let block = (row + x * GRID_COLS / width) as usize;
+                    sums[block] += luma;
+                    counts[block] += 1;
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            blocks: sums
+                .iter()
+                .zip(&counts)
+                .map(|(&sum, &count)| sum.checked_div(count).unwrap_or(0) as u8)
+                .collect(),
+        }
+    }
+
+    /// 이전 프레임 대비 바뀐 블록 비율 (0~1, 해상도가 바뀌면 1)
+    pub fn change_score(&self, previous: &BlockSignature) -> f32 {
+        if self.width != previous.width || self.height != previous.height {
+            return 1.0;
+        }
+        let changed = self
+            .blocks
+            .iter()
+            .zip(&previous.blocks)
+            .filter(|(a, b)| a.abs_diff(**b) > BLOCK_THRESHOLD)
+            .count();
+        changed as f32 / self.blocks.len() as f32
+    }
+}
+
+/// 프레임 하나의 변화량
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityRecord {
+    pub index: usize,
+    pub offset_ms: u64,
+    /// 이전 프레임 대비 바뀐 블록 비율 (세션 첫 프레임은 0)
+    pub score: f32,
+}
+
+/// 줄인 곡선의 점 하나 (구간 안 최댓값과 평균)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityPoint {
+    pub offset_ms: u64,
+    pub max: f32,
+    pub mean: f32,
+}
+
+/// 화면이 많이 바뀐 구간
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityBurst {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub peak_score: f32,
+    pub peak_offset_ms: u64,
+    /// 가장 많이 바뀐 프레임 (바로 이동용)
+    pub peak_frame: usize,
+    /// 구간 점수 합 (구간끼리 비교용)
+    pub total: f32,
+}
+
+/// 세션 화면 활동 타임라인
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityTimeline {
+    pub session_id: String,
+    pub duration_ms: u64,
+    /// 곡선 점 하나가 덮는 시간
+    pub bucket_ms: u64,
+    pub points: Vec<ActivityPoint>,
+    /// 시간순
+    pub bursts: Vec<ActivityBurst>,
+    /// 활동 구간 기준 점수
+    pub burst_threshold: f32,
+}
+
+/// 변화량 기록을 `max_points`개 이하 점으로 줄이고 활동 구간 찾기
+pub fn build_timeline(session_id: &str, records: &[ActivityRecord], max_points: usize) -> ActivityTimeline {
+    let mut records: Vec<&ActivityRecord> = records.iter().collect();
+    records.sort_by_key(|r| r.offset_ms);
+
+    let duration_ms = records.last().map(|r| r.offset_ms).unwrap_or(0);
+    // 1초 이상이면 초 단위로 맞춤 (축 눈금 읽기 쉽게)
+    let bucket_ms = (duration_ms + 1).div_ceil(max_points.max(1) as u64);
+    let bucket_ms = if bucket_ms >= 1000 { bucket_ms.div_ceil(1000) * 1000 } else { bucket_ms };
+
+    let mut points: Vec<ActivityPoint> = Vec::new();
+    let mut count = 0;
+    for record in &records {
+        let offset_ms = record.offset_ms / bucket_ms * bucket_ms;
+        match points.last_mut() {
+            Some(point) if point.offset_ms == offset_ms => {
+                point.max = point.max.max(record.score);
+                point.mean += record.score;
+                count += 1;
+            }
+            _ => {
+                if let Some(point) = points.last_mut() {
+                    point.mean /= count as f32;
+                }
+                points.push(ActivityPoint {
+                    offset_ms,
+                    max: record.score,
+                    mean: record.score,
+                });
+                count = 1;
+            }
+        }
+    }
+    if let Some(point) = points.last_mut() {
+        point.mean /= count as f32;
+    }
+
+    let burst_threshold = burst_threshold(&records);
+    let mut bursts: Vec<ActivityBurst> = Vec::new();
+    for record in records.iter().filter(|r| r.score >= burst_threshold) {
+        match bursts.last_mut() {
+            Some(burst) if record.offset_ms <= burst.end_ms + BURST_MERGE_GAP_MS => {
+                burst.end_ms = record.offset_ms;
+                burst.total += record.score;
+                if record.score > burst.peak_score {
+                    burst.peak_score = record.score;
+                    burst.peak_offset_ms = record.offset_ms;
+                    burst.peak_frame = record.index;
+                }
+            }
+            _ => bursts.push(ActivityBurst {
+                start_ms: record.offset_ms,
+                end_ms: record.offset_ms,
+                peak_score: record.score,
+                peak_offset_ms: record.offset_ms,
+                peak_frame: record.index,
+                total: record.score,
+            }),
+        }
+    }
+
+    ActivityTimeline {
+        session_id: session_id.to_string(),
+        duration_ms,
+        bucket_ms,
+        points,
+        bursts,
+        burst_threshold,
+    }
+}
+
+/// 평균 + 표준편차 2배 (대부분 정지 화면인 세션에서 튀는 프레임만), 최소 [`MIN_BURST_SCORE`]
+fn burst_threshold(records: &[&ActivityRecord]) -> f32 {
+    if records.is_empty() {
+        return MIN_BURST_SCORE;
+    }
+    let n = records.len() as f32;
+    let mean = records.iter().map(|r| r.score).sum::<f32>() / n;
+    let variance = records.iter().map(|r| (r.score - mean).powi(2)).sum::<f32>() / n;
+    (mean + 2.0 * variance.sqrt()).max(MIN_BURST_SCORE)
+}
+
+/// 세션 디렉토리의 활동 트랙으로 타임라인 만들기 (트랙이 없는 예전 세션은 빈 타임라인)
+pub fn load_timeline(session_dir: &Path, max_points: usize) -> Result<ActivityTimeline, String> {
+    let session_id = session_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid session path: {}", session_dir.display()))?;
+    let records: Vec<ActivityRecord> = tracks::read_track(&session_dir.join(ACTIVITY_TRACK), None)?;
+    Ok(build_timeline(&session_id, &records, max_points))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, value: u8) -> Vec<u8> {
+        [value, value, value, 255].repeat((width * height) as usize)
+    }
+
+    #[test]
+    fn test_change_score() {
+        let (w, h) = (320, 180);
+        let base = BlockSignature::from_rgba(w, h, &solid(w, h, 100));
+        assert_eq!(base.change_score(&base), 0.0);
+
+        // 왼쪽 절반만 밝게
+        let mut half = solid(w, h, 100);
+        for y in 0..h {
+            for x in 0..w / 2 {
+                let i = ((y * w + x) * 4) as usize;
+                half[i..i + 3].copy_from_slice(&[200, 200, 200]);
+            }
+        }
+        let changed = BlockSignature::from_rgba(w, h, &half);
+        assert!((changed.change_score(&base) - 0.5).abs() < 0.01);
+
+        // 노이즈 수준 변화는 무시, 해상도가 바뀌면 전부 바뀐 것
+        assert_eq!(BlockSignature::from_rgba(w, h, &solid(w, h, 103)).change_score(&base), 0.0);
+        assert_eq!(BlockSignature::from_rgba(160, 90, &solid(160, 90, 100)).change_score(&base), 1.0);
+    }
+
+    #[test]
+    fn test_timeline_downsample_and_bursts() {
+        // 1초 간격 10분, 120~125초와 127초, 400초에 큰 변화
+        let records: Vec<ActivityRecord> = (0..600)
+            .map(|i| ActivityRecord {
+                index: i + 1,
+                offset_ms: i as u64 * 1000,
+                score: match i {
+                    120..=125 | 127 => 0.4 + (i % 3) as f32 * 0.1,
+                    400 => 0.9,
+                    _ => 0.01,
+                },
+            })
+            .collect();
+
+        let timeline = build_timeline("s", &records, 60);
+        assert!(timeline.points.len() <= 60);
+        assert_eq!(timeline.bucket_ms, 10_000);
+        assert!((timeline.points[12].max - 0.6).abs() < 1e-6);
+
+        assert_eq!(timeline.bursts.len(), 2);
+        let first = &timeline.bursts[0];
+        assert_eq!((first.start_ms, first.end_ms), (120_000, 127_000));
+        assert_eq!(first.peak_frame, 123);
+        assert_eq!(timeline.bursts[1].peak_offset_ms, 400_000);
+    }
+}
@@ -0,0 +1,519 @@
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{DateTime, Utc};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::journal::{self, JournalEdit};
+use crate::models::{TradeAction, TradeEvent, TradeSource};
+
+/// 토큰 최소 길이 (추측하기 어렵게)
+const MIN_TOKEN_LEN: usize = 16;
+
+/// 구독자가 늦을 때 쌓아 두는 이벤트 수 (넘치면 오래된 것부터 버림)
+const EVENT_BUFFER: usize = 256;
+
+/// 로컬 제어 API 설정 (기본 꺼짐, 127.0.0.1에서만 열림)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// `Authorization: Bearer <token>` 또는 `?token=` (WebSocket)
+    #[serde(default)]
+    pub token: String,
+}
+
+fn default_port() -> u16 {
+    47615
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+            token: String::new(),
+        }
+    }
+}
+
+/// 켠 경우 토큰이 충분히 긴지 검사
+pub fn validate_config(config: &ApiConfig) -> Result<(), String> {
+    if !config.enabled {
+        return Ok(());
+    }
+    if config.token.len() < MIN_TOKEN_LEN {
+        return Err(format!("API token must be at least {} characters", MIN_TOKEN_LEN));
+    }
+    if !config.token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err("API token may only contain letters, digits, '-' and '_'".to_string());
+    }
+    Ok(())
+}
+
+/// 새 API 토큰 (48자리 hex)
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// 앱 이벤트 하나 (프론트엔드로 보내는 이벤트와 이름/내용이 같음)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiEvent {
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ApiEvent {
+    pub fn new<T: Serialize>(event: &str, payload: &T) -> Result<Self, String> {
+        Ok(Self {
+            event: event.to_string(),
+            payload: serde_json::to_value(payload).map_err(|e| format!("Failed to serialize {} event: {}", event, e))?,
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+/// 앱 이벤트 방송 (WebSocket 구독자마다 하나씩 받음)
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ApiEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    /// 구독자가 없으면 버림
+    pub fn send(&self, event: ApiEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn publish<T: Serialize>(&self, event: &str, payload: &T) {
+        match ApiEvent::new(event, payload) {
+            Ok(event) => self.send(event),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ApiEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 외부 도구가 남기는 거래 마커 (수동 입력 거래로 기록)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkerRequest {
+    /// 없으면 `unknown`
+    #[serde(default)]
+    pub action: Option<TradeAction>,
+    #[serde(default)]
+    pub ticker: Option<String>,
+    #[serde(default)]
+    pub instrument_name: Option<String>,
+    #[serde(default)]
+    pub quantity: Option<u64>,
+    #[serde(default)]
+    pub price: Option<f64>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl MarkerRequest {
+    /// 거래 이벤트로 변환 (메모/태그는 저널과 같은 규칙으로 검사)
+    pub fn into_event(self, now: DateTime<Utc>) -> Result<TradeEvent, String> {
+        let mut event = TradeEvent::new(self.action.unwrap_or(TradeAction::Unknown), now, TradeSource::Manual);
+        event.ticker = self.ticker;
+        event.instrument_name = self.instrument_name;
+        event.quantity = self.quantity;
+        event.price = self.price;
+        if let Some(text) = self.note {
+            journal::apply(&mut event, &JournalEdit::AddNote { text }, now)?;
+        }
+        for tag in self.tags {
+            journal::apply(&mut event, &JournalEdit::AddTag { tag }, now)?;
+        }
+        Ok(event)
+    }
+}
+
+/// API로 들어온 명령 (앱이 UI 명령과 같은 경로로 처리)
+#[derive(Debug, Clone)]
+pub enum ApiCommand {
+    /// `RecordingStatus`
+    Status,
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    /// 기록된 `TradeEvent`
+    AddMarker(MarkerRequest),
+    /// `Vec<SessionInfo>`
+    ListSessions,
+}
+
+/// 명령 결과 회신
+pub struct ApiReply(oneshot::Sender<Result<serde_json::Value, String>>);
+
+impl ApiReply {
+    pub fn send<T: Serialize>(self, result: Result<T, String>) {
+        let result = result.and_then(|value| {
+            serde_json::to_value(value).map_err(|e| format!("Failed to serialize API response: {}", e))
+        });
+        let _ = self.0.send(result);
+    }
+}
+
+pub struct ApiRequest {
+    pub command: ApiCommand,
+    pub reply: ApiReply,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    token: Arc<str>,
+    requests: mpsc::Sender<ApiRequest>,
+    events: EventBus,
+    cancel: CancellationToken,
+}
+
+/// 실행 중인 로컬 API 서버
+pub struct ApiServer {
+    config: ApiConfig,
+    addr: SocketAddr,
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+impl ApiServer {
+    /// `127.0.0.1:<port>`에서 서버 시작 (포트가 사용 중이면 에러)
+    ///
+    /// 명령은 `requests`로 넘기고 `events`에 들어온 이벤트는 `/api/events` 구독자에게 보낸다.
+    pub async fn start(
+        config: &ApiConfig,
+        requests: mpsc::Sender<ApiRequest>,
+        events: EventBus,
+    ) -> Result<Self, String> {
+        validate_config(config)?;
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, config.port))
+            .await
+            .map_err(|e| format!("Failed to bind API port {}: {}", config.port, e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to read API address: {}", e))?;
+
+        let cancel = CancellationToken::new();
+        let router = router(ServerState {
+            token: Arc::from(config.token.as_str()),
+            requests,
+            events,
+            cancel: cancel.clone(),
+        });
+        let shutdown = cancel.clone();
+        let task = tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown.cancelled_owned())
+                .await
+            {
+                eprintln!("API server error: {}", e);
+            }
+        });
+
+        println!("API server listening on http://{}", addr);
+        Ok(Self {
+            config: config.clone(),
+            addr,
+            cancel,
+            task,
+        })
+    }
+
+    pub fn config(&self) -> &ApiConfig {
+        &self.config
+    }
+
+    /// 실제로 열린 주소 (포트 0이면 OS가 고른 포트)
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// 서버를 멈추고 포트가 풀릴 때까지 대기
+    pub async fn stop(self) {
+        self.cancel.cancel();
+        let _ = self.task.await;
+        println!("API server stopped");
+    }
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/api/status", get(status))
+        .route("/api/recording/start", post(start_recording))
+        .route("/api/recording/stop", post(stop_recording))
+        .route("/api/recording/pause", post(pause_recording))
+        .route("/api/recording/resume", post(resume_recording))
+        .route("/api/markers", post(add_marker))
+        .route("/api/sessions", get(list_sessions))
+        .route("/api/events", get(events))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state)
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// 길이가 같으면 끝까지 비교 (응답 시간으로 토큰을 추측하지 못하게)
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn authorize(State(state): State<ServerState>, request: Request, next: Next) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    // 브라우저 WebSocket은 헤더를 못 넣으므로 쿼리도 허용
+    let query = request
+        .uri()
+        .query()
+        .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("token=")));
+    let authorized = bearer.or(query).is_some_and(|token| tokens_match(token, &state.token));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        error_response(StatusCode::UNAUTHORIZED, "Invalid or missing API token")
+    }
+}
+
+/// 앱에 명령을 넘기고 결과를 JSON으로 응답 (앱이 거절하면 409)
+async fn call(state: &ServerState, command: ApiCommand) -> Response {
+    let (reply, result) = oneshot::channel();
+    let request = ApiRequest {
+        command,
+        reply: ApiReply(reply),
+    };
+    if state.requests.send(request).await.is_err() {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "Recorder is not running");
+    }
+    match result.await {
+        Ok(Ok(value)) => Json(value).into_response(),
+        Ok(Err(e)) => error_response(StatusCode::CONFLICT, &e),
+        Err(_) => error_response(StatusCode::SERVICE_UNAVAILABLE, "Recorder dropped the request"),
+    }
+}
+
+async fn status(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::Status).await
+}
+
+async fn start_recording(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::Start).await
+}
+
+async fn stop_recording(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::Stop).await
+}
+
+async fn pause_recording(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::Pause).await
+}
+
+async fn resume_recording(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::Resume).await
+}
+
+async fn add_marker(State(state): State<ServerState>, Json(marker): Json<MarkerRequest>) -> Response {
+    call(&state, ApiCommand::AddMarker(marker)).await
+}
+
+async fn list_sessions(State(state): State<ServerState>) -> Response {
+    call(&state, ApiCommand::ListSessions).await
+}
+
+async fn events(State(state): State<ServerState>, upgrade: WebSocketUpgrade) -> Response {
+    upgrade.on_upgrade(move |socket| stream_events(socket, state))
+}
+
+/// 연결이 끊기거나 서버가 멈출 때까지 이벤트를 JSON 텍스트 메시지로 전송
+async fn stream_events(mut socket: WebSocket, state: ServerState) {
+    let mut events = state.events.subscribe();
+    loop {
+        tokio::select! {
+            _ = state.cancel.cancelled() => break,
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let text = match serde_json::to_string(&event) {
+                        Ok(text) => text,
+                        Err(_) => continue,
+                    };
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => eprintln!("API event stream lagged: {} events skipped", skipped),
+                Err(RecvError::Closed) => break,
+            },
+            // 클라이언트 메시지는 무시 (닫기만 처리)
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    let _ = socket.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::time::{timeout, Duration};
+
+    const TOKEN: &str = "0123456789abcdef0123";
+
+    async fn http(addr: SocketAddr, method: &str, path: &str, token: Option<&str>, body: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let auth = token.map(|t| format!("Authorization: Bearer {}\r\n", t)).unwrap_or_default();
+        let request = format!(
+            "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            path,
+            auth,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// 상태는 고정값, 마커는 이벤트로 바꿔 돌려주는 가짜 앱
+    fn fake_app(mut requests: mpsc::Receiver<ApiRequest>) {
+        tokio::spawn(async move {
+            while let Some(request) = requests.recv().await {
+                match request.command {
+                    ApiCommand::Status => request.reply.send(Ok(serde_json::json!({ "is_recording": true }))),
+                    ApiCommand::AddMarker(marker) => request.reply.send(marker.into_event(Utc::now())),
+                    ApiCommand::Stop => request.reply.send::<()>(Err("Not recording".to_string())),
+                    _ => request.reply.send(Ok(())),
+                }
+            }
+        });
+    }
+
+    async fn start_server(events: EventBus) -> ApiServer {
+        let (sender, receiver) = mpsc::channel(8);
+        fake_app(receiver);
+        let config = ApiConfig {
+            enabled: true,
+            port: 0,
+            token: TOKEN.to_string(),
+        };
+        ApiServer::start(&config, sender, events).await.unwrap()
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let mut config = ApiConfig::default();
+        assert!(validate_config(&config).is_ok());
+        config.enabled = true;
+        assert!(validate_config(&config).is_err());
+        config.token = "short".to_string();
+        assert!(validate_config(&config).is_err());
+        config.token = "contains spaces and more".to_string();
+        assert!(validate_config(&config).is_err());
+        config.token = generate_token();
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rest_requires_token() {
+        let server = start_server(EventBus::new()).await;
+        let addr = server.addr();
+
+        assert!(http(addr, "GET", "/api/status", None, "").await.starts_with("HTTP/1.1 401"));
+        assert!(http(addr, "GET", "/api/status", Some("0123456789abcdef0124"), "").await.starts_with("HTTP/1.1 401"));
+
+        let response = http(addr, "GET", "/api/status", Some(TOKEN), "").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.ends_with(r#"{"is_recording":true}"#));
+
+        let response = http(addr, "POST", "/api/markers", Some(TOKEN), r#"{"action":"buy","ticker":"005930","note":"돌파 매수","tags":["스캘핑"]}"#).await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let event: TradeEvent = serde_json::from_str(body).unwrap();
+        assert_eq!(event.source, TradeSource::Manual);
+        assert_eq!(event.ticker.as_deref(), Some("005930"));
+        assert_eq!(event.notes[0].text, "돌파 매수");
+
+        let response = http(addr, "POST", "/api/markers", Some(TOKEN), r#"{"tags":[" "]}"#).await;
+        assert!(response.starts_with("HTTP/1.1 409"));
+        assert!(http(addr, "POST", "/api/recording/stop", Some(TOKEN), "").await.starts_with("HTTP/1.1 409"));
+
+        server.stop().await;
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_event_stream() {
+        let events = EventBus::new();
+        let server = start_server(events.clone()).await;
+        let url = format!("ws://{}/api/events?token={}", server.addr(), TOKEN);
+        let (mut socket, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+
+        // 구독이 연결 직후 시작되므로 받을 때까지 다시 보냄
+        let message = timeout(Duration::from_secs(5), async {
+            loop {
+                events.publish("hts-detected", &true);
+                if let Ok(Some(message)) = timeout(Duration::from_millis(100), socket.next()).await {
+                    return message.unwrap();
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        let event: ApiEvent = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(event.event, "hts-detected");
+        assert_eq!(event.payload, serde_json::json!(true));
+
+        let denied = format!("ws://{}/api/events?token=wrong", server.addr());
+        assert!(tokio_tungstenite::connect_async(denied).await.is_err());
+        server.stop().await;
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use xcap::image;
+
+use crate::crypto::Cipher;
+use crate::input::{self, InputRecord};
+use crate::session;
+use crate::storage;
+use crate::tracks::{self, FrameRecord, TrackWriter};
+use crate::window_capture;
+
+/// 내보낸 클립
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipExport {
+    pub path: String,
+    pub frames: usize,
+    /// 커서/클릭을 프레임에 그렸는지 (입력 기록이 없는 세션이면 false)
+    pub input_rendered: bool,
+}
+
+/// 세션의 `[start_ms, end_ms]` 구간 전체 화면 프레임을 `<dest_dir>/<session_id>_<start>-<end>/`로 내보내기
+///
+/// 프레임은 복호화해서 원래 이름으로 쓰고, 구간의 프레임/커서 트랙도 함께 남긴다.
+/// `render_input`이면 커서 위치와 클릭 표시를 프레임에 그린다.
+pub fn export_clip(
+    output_dir: &str,
+    session_id: &str,
+    start_ms: u64,
+    end_ms: u64,
+    dest_dir: &str,
+    cipher: Option<&Cipher>,
+    render_input: bool,
+) -> Result<ClipExport, String> {
+    if end_ms < start_ms {
+        return Err(format!("Invalid clip range: {}-{}ms", start_ms, end_ms));
+    }
+    let session_dir = session::session_path(output_dir, session_id)?;
+
+    let frames: Vec<FrameRecord> = tracks::read_track(&session_dir.join(tracks::FRAMES_TRACK), cipher)?
+        .into_iter()
+        .filter(|f: &FrameRecord| f.offset_ms >= start_ms && f.offset_ms <= end_ms)
+        .collect();
+    if frames.is_empty() {
+        return Err(format!("No frames in {} between {}ms and {}ms", session_id, start_ms, end_ms));
+    }
+
+    let mut inputs: Vec<InputRecord> = tracks::read_track(&session_dir.join(input::INPUT_TRACK), cipher)?;
+    inputs.sort_by_key(|r| r.offset_ms);
+    let render = render_input && !inputs.is_empty();
+
+    // 같은 구간을 다시 내보내면 트랙이 이어 붙지 않도록 새로 만듦
+    let target = Path::new(dest_dir).join(format!("{}_{}-{}", session_id, start_ms, end_ms));
+    if target.exists() {
+        std::fs::remove_dir_all(&target).map_err(|e| format!("Failed to replace clip directory: {}", e))?;
+    }
+    std::fs::create_dir_all(&target).map_err(|e| format!("Failed to create clip directory: {}", e))?;
+
+    let mut frame_track = TrackWriter::<FrameRecord>::open(&target.join(tracks::FRAMES_TRACK))?;
+    for frame in &frames {
+        let data = storage::read_file(&session_dir.join(&frame.file), cipher)?;
+        let data = if render {
+            let mut image = image::load_from_memory(&data)
+                .map_err(|e| format!("Failed to decode {}: {}", frame.file, e))?
+                .to_rgba8();
+            input::render_overlay(&mut image, &inputs, frame.offset_ms);
+            window_capture::encode_png(&image)?
+        } else {
+            data
+        };
+
+        let file = storage::plain_file_name(&frame.file).to_string();
+        std::fs::write(target.join(&file), data).map_err(|e| format!("Failed to write clip frame: {}", e))?;
+        frame_track.append(&FrameRecord { file, ..frame.clone() })?;
+    }
+
+    // 다른 도구에서 다시 그릴 수 있게 구간의 커서 기록도 남김
+    let clip_inputs: Vec<&InputRecord> = inputs
+        .iter()
+        .filter(|r| r.offset_ms >= start_ms && r.offset_ms <= end_ms)
+        .collect();
+    if !clip_inputs.is_empty() {
+        let mut input_track = TrackWriter::<InputRecord>::open(&target.join(input::INPUT_TRACK))?;
+        for record in clip_inputs {
+            input_track.append(record)?;
+        }
+    }
+
+    Ok(ClipExport {
+        path: target.to_string_lossy().to_string(),
+        frames: frames.len(),
+        input_rendered: render,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{InputKind, MouseButton};
+    use chrono::Utc;
+    use image::{Rgba, RgbaImage};
+
+    /// 흰 프레임 5장(1초 간격)과 2.9초 클릭 하나가 있는 세션
+    fn write_session(session_dir: &Path, cipher: Option<&Cipher>) -> RgbaImage {
+        std::fs::create_dir_all(session_dir).unwrap();
+
+        let blank = RgbaImage::from_pixel(64, 64, Rgba([255, 255, 255, 255]));
+        let mut frames = TrackWriter::open_sealed(&session_dir.join(tracks::FRAMES_TRACK), cipher).unwrap();
+        for i in 1..=5 {
+            let record = FrameRecord {
+                index: i,
+                file: format!("frame_{:06}.png", i),
+                offset_ms: i as u64 * 1000,
+                captured_at: Utc::now(),
+                focused_window: None,
+            };
+            let png = window_capture::encode_png(&blank).unwrap();
+            storage::write_file(&session_dir.join(&record.file), &png, cipher).unwrap();
+            frames.append(&record).unwrap();
+        }
+        let mut inputs = TrackWriter::open_sealed(&session_dir.join(input::INPUT_TRACK), cipher).unwrap();
+        inputs
+            .append(&InputRecord {
+                offset_ms: 2_900,
+                x: 30,
+                y: 30,
+                kind: InputKind::Press,
+                button: Some(MouseButton::Left),
+            })
+            .unwrap();
+        blank
+    }
+
+    #[test]
+    fn test_export_clip_with_cursor() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let session_id = "recording_20260309_090000";
+        let blank = write_session(&dir.join(session_id), None);
+
+        let output_dir = dir.to_str().unwrap();
+        let dest = dir.join("clips");
+        let clip = export_clip(output_dir, session_id, 2_000, 4_000, dest.to_str().unwrap(), None, true).unwrap();
+        assert_eq!(clip.frames, 3);
+        assert!(clip.input_rendered);
+
+        let path = Path::new(&clip.path);
+        let before = image::open(path.join("frame_000002.png")).unwrap().to_rgba8();
+        let clicked = image::open(path.join("frame_000003.png")).unwrap().to_rgba8();
+        assert_eq!(before, blank);
+        assert_ne!(clicked.get_pixel(30, 30), blank.get_pixel(30, 30));
+        assert!(path.join(input::INPUT_TRACK).exists());
+
+        assert!(export_clip(output_dir, session_id, 10_000, 20_000, dest.to_str().unwrap(), None, true).is_err());
+    }
+    #[test]
+    fn test_export_clip_from_encrypted_session() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let session_id = "recording_20260309_100000";
+        let session_dir = dir.join(session_id);
+        let cipher = Cipher::from_key(&[9; 32]);
+        let blank = write_session(&session_dir, Some(&cipher));
+
+        let raw = std::fs::read_to_string(session_dir.join(input::INPUT_TRACK)).unwrap();
+        assert!(raw.contains("sealed") && !raw.contains("offset_ms"));
+
+        let output_dir = dir.to_str().unwrap();
+        let dest = dir.join("clips");
+        assert!(export_clip(output_dir, session_id, 2_000, 4_000, dest.to_str().unwrap(), None, true).is_err());
+
+        let clip = export_clip(output_dir, session_id, 2_000, 4_000, dest.to_str().unwrap(), Some(&cipher), true).unwrap();
+        assert_eq!(clip.frames, 3);
+        assert!(clip.input_rendered);
+        let clicked = image::open(Path::new(&clip.path).join("frame_000003.png")).unwrap().to_rgba8();
+        assert_ne!(clicked.get_pixel(30, 30), blank.get_pixel(30, 30));
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+
+/// 기준점 재확인 주기
+const RECHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 이 이상 벗어나면 기준점을 다시 잡음 (NTP 보정, 절전 복귀 등)
+const DRIFT_THRESHOLD_MS: i64 = 250;
+
+/// 현재 시각 공급자 (테스트에서 시간을 고정할 수 있도록 주입)
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 시스템 시계
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 캡처 시점 타임스탬프
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FrameTimestamp {
+    /// 세션 시작부터의 단조 시계 오프셋 (ms)
+    pub offset_ms: u64,
+    /// 기준점에서 계산한 UTC 시각
+    pub utc: DateTime<Utc>,
+}
+
+/// 기준점 재설정 기록
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockAdjustment {
+    pub offset_ms: u64,
+    /// 시스템 시계 - 기준점 계산값 (ms)
+    pub drift_ms: i64,
+    pub new_anchor_utc: DateTime<Utc>,
+}
+
+/// 매니페스트에 남기는 시계 정보
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockInfo {
+    pub anchor_utc: DateTime<Utc>,
+    pub adjustments: Vec<ClockAdjustment>,
+}
+
+/// 단조 시계(`Instant`)를 UTC 기준점에 묶어 둔 세션 시계
+///
+/// 프레임 간격이 밀려도 각 프레임은 실제 캡처 시각을 갖는다. 시스템 시계가
+/// 바뀌어도 오프셋은 계속 단조 증가하고, UTC 기준점만 주기적으로 다시 맞춘다.
+pub struct SessionClock {
+    start: Instant,
+    anchor_instant: Instant,
+    anchor_utc: DateTime<Utc>,
+    last_check: Instant,
+    info: ClockInfo,
+}
+
+impl SessionClock {
+    pub fn start() -> Self {
+        let now = Instant::now();
+        let anchor_utc = Utc::now();
+
+        Self {
+            start: now,
+            anchor_instant: now,
+            anchor_utc,
+            last_check: now,
+            info: ClockInfo {
+                anchor_utc,
+                adjustments: Vec::new(),
+            },
+        }
+    }
+
+    /// 지금 시점 타임스탬프
+    pub fn stamp(&mut self) -> FrameTimestamp {
+        let now = Instant::now();
+        if now.duration_since(self.last_check) >= RECHECK_INTERVAL {
+            self.recheck(now);
+        }
+        self.timestamp_at(now)
+    }
+
+    fn timestamp_at(&self, at: Instant) -> FrameTimestamp {
+        let since_anchor = at.duration_since(self.anchor_instant);
+        FrameTimestamp {
+            offset_ms: at.duration_since(self.start).as_millis() as u64,
+            utc: self.anchor_utc
+                + chrono::Duration::from_std(since_anchor).unwrap_or_else(|_| chrono::Duration::zero()),
+        }
+    }
+
+    /// 시스템 UTC와 비교해서 많이 벗어났으면 기준점 재설정
+    fn recheck(&mut self, now: Instant) {
+        self.last_check = now;
+
+        let expected = self.timestamp_at(now);
+        let system_utc = Utc::now();
+        let drift_ms = (system_utc - expected.utc).num_milliseconds();
+
+        if drift_ms.abs() > DRIFT_THRESHOLD_MS {
+            println!("Clock drift {}ms detected, re-anchoring", drift_ms);
+            self.anchor_instant = now;
+            self.anchor_utc = system_utc;
+            self.info.adjustments.push(ClockAdjustment {
+                offset_ms: expected.offset_ms,
+                drift_ms,
+                new_anchor_utc: system_utc,
+            });
+        }
+    }
+
+    pub fn info(&self) -> ClockInfo {
+        self.info.clone()
+    }
+}
@@ -0,0 +1,117 @@
+use crate::models::AppConfig;
+use crate::profiles::DetectedProfile;
+use crate::recorder::{ScreenRecorder, StoppingSession};
+use crate::session::SessionSummary;
+use crate::state_machine::{
+    RecorderAction, RecorderEvent, RecorderState, RecordingOrigin, RecordingStateMachine, StateTransition,
+};
+
+/// 이벤트 처리 결과
+#[derive(Debug, Default)]
+pub struct ControllerOutcome {
+    /// 새로 시작한 세션 경로
+    pub started: Option<String>,
+    /// 방금 끝난 세션 요약
+    pub summary: Option<SessionSummary>,
+    /// 마무리해야 하는 세션 ([`RecordingController::begin`]을 쓴 경우만)
+    pub stopping: Option<StoppingSession>,
+}
+
+/// 상태 머신 + 레코더
+///
+/// 수동 명령과 자동 감시 루프 모두 이 타입을 거쳐서 녹화를 제어한다.
+/// `handle`은 녹화 중지 시 파이프라인이 끝날 때까지 블로킹한다. 컨트롤러를 락으로 공유하면
+/// `begin`으로 세션만 꺼내고 락을 푼 다음 마무리한 뒤 `finish_stop`으로 결과를 알린다.
+pub struct RecordingController {
+    machine: RecordingStateMachine,
+    recorder: ScreenRecorder,
+    /// 마지막으로 감지된 HTS 프로필 (다음 녹화 매니페스트에 기록)
+    detected: Option<DetectedProfile>,
+}
+
+impl RecordingController {
+    pub fn new(recorder: ScreenRecorder, auto_mode: bool) -> Self {
+        Self {
+            machine: RecordingStateMachine::new(auto_mode),
+            recorder,
+            detected: None,
+        }
+    }
+
+    /// 감시 루프가 HTS 이벤트를 넣기 전에 호출
+    pub fn set_detected_profile(&mut self, detected: Option<DetectedProfile>) {
+        self.detected = detected;
+    }
+
+    pub fn detected_profile(&self) -> Option<&DetectedProfile> {
+        self.detected.as_ref()
+    }
+
+    pub fn recorder(&self) -> &ScreenRecorder {
+        &self.recorder
+    }
+
+    pub fn state(&self) -> &RecorderState {
+        self.machine.state()
+    }
+
+    pub fn origin(&self) -> Option<RecordingOrigin> {
+        self.machine.origin()
+    }
+
+    pub fn auto_mode(&self) -> bool {
+        self.machine.auto_mode()
+    }
+
+    pub fn history(&self) -> Vec<StateTransition> {
+        self.machine.history().cloned().collect()
+    }
+
+    /// UI로 아직 보내지 않은 전이 꺼내기 (`handle`이 실패해도 전이는 남아 있음)
+    pub fn take_transitions(&mut self) -> Vec<StateTransition> {
+        self.machine.take_transitions()
+    }
+
+    /// 이벤트를 상태 머신에 넣고, 요청된 동작을 레코더에서 실행
+    pub fn handle(&mut self, event: RecorderEvent, config: &AppConfig) -> Result<ControllerOutcome, String> {
+        let mut outcome = self.begin(event, config)?;
+        if let Some(stopping) = outcome.stopping.take() {
+            outcome.summary = Some(self.finish_stop(stopping.finish())?);
+        }
+        Ok(outcome)
+    }
+
+    /// 중지한 세션의 마무리 결과 보고 (상태 머신을 Finalizing에서 다음 상태로)
+    pub fn finish_stop(&mut self, result: Result<SessionSummary, String>) -> Result<SessionSummary, String> {
+        // 마무리가 실패해도 세션은 이미 정리된 상태
+        self.machine.stop_completed();
+        result
+    }
+
+    /// `handle`과 같지만 녹화 중지는 세션을 꺼내기만 하고 `stopping`으로 돌려줌
+    pub fn begin(&mut self, event: RecorderEvent, config: &AppConfig) -> Result<ControllerOutcome, String> {
+        let mut outcome = ControllerOutcome::default();
+
+        match self.machine.handle(event)? {
+            Some(RecorderAction::Start) => match self.recorder.start_recording(config, self.detected.as_ref()) {
+                Ok(path) => outcome.started = Some(path),
+                Err(e) => {
+                    self.machine.start_failed(&e);
+                    return Err(e);
+                }
+            },
+            Some(RecorderAction::Stop) => match self.recorder.begin_stop() {
+                Ok(stopping) => outcome.stopping = Some(stopping),
+                Err(e) => {
+                    self.machine.stop_completed();
+                    return Err(e);
+                }
+            },
+            Some(RecorderAction::Pause) => self.recorder.pause()?,
+            Some(RecorderAction::Resume) => self.recorder.resume()?,
+            None => {}
+        }
+
+        Ok(outcome)
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+
+/// 암호화 파일 헤더 (매직 4바이트 + 포맷 버전)
+const MAGIC: &[u8; 4] = b"TREC";
+const FORMAT_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + NONCE_LEN;
+
+/// 출력 디렉토리에 두는 키 유도 파라미터 파일
+pub const KEY_FILE: &str = "encryption.json";
+
+/// 패스프레이즈를 넘겨주는 환경 변수 (CLI용)
+pub const PASSPHRASE_ENV: &str = "TEST_RECORDER_PASSPHRASE";
+
+const KEYRING_SERVICE: &str = "test-recorder";
+const KEYRING_USER: &str = "recordings";
+
+/// 패스프레이즈 검증용 평문
+const CHECK_PLAINTEXT: &[u8] = b"test-recorder";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// 사용자 패스프레이즈에서 Argon2id로 유도
+    Passphrase,
+    /// OS 키체인(Windows 자격 증명 관리자, macOS 키체인)에 저장된 랜덤 키
+    Keyring,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    pub enabled: bool,
+    pub key_source: KeySource,
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_source: KeySource::Passphrase,
+        }
+    }
+}
+
+/// `encryption.json` 내용 (비밀 아님)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeyFile {
+    kdf: String,
+    salt: String,
+    /// 패스프레이즈가 맞는지 확인하기 위한 암호문
+    check: String,
+}
+
+/// 녹화 파일 암호화/복호화 (AES-256-GCM)
+#[derive(Clone)]
+pub struct Cipher {
+    inner: Aes256Gcm,
+}
+
+impl Cipher {
+    pub fn from_key(key: &[u8; 32]) -> Self {
+        Self {
+            inner: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .inner
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| format!("Failed to encrypt: {}", e))?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + ciphertext.len());
+        out.extend_from_slice(MAGIC);
+        out.push(FORMAT_VERSION);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        if !is_encrypted(data) {
+            return Err("Not an encrypted recording file".to_string());
+        }
+        if data[MAGIC.len()] != FORMAT_VERSION {
+            return Err(format!("Unsupported encryption format: {}", data[MAGIC.len()]));
+        }
+
+        let nonce = Nonce::from_slice(&data[MAGIC.len() + 1..HEADER_LEN]);
+        self.inner
+            .decrypt(nonce, &data[HEADER_LEN..])
+            .map_err(|_| "Failed to decrypt (wrong key or tampered file)".to_string())
+    }
+}
+
+/// 암호화 헤더로 시작하는지 확인
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.len() > HEADER_LEN && data.starts_with(MAGIC)
+}
+
+/// 설정에 따라 녹화 암호화 키 열기
+///
+/// 암호화가 꺼져 있으면 `None`. 패스프레이즈 방식은 `passphrase` 인자 또는
+/// `TEST_RECORDER_PASSPHRASE` 환경 변수를 사용하고, 출력 디렉토리에 처음
+/// 사용할 때 솔트와 검증값을 만든다.
+pub fn open_cipher(
+    config: &EncryptionConfig,
+    output_dir: &str,
+    passphrase: Option<&str>,
+) -> Result<Option<Cipher>, String> {
+    if !config.enabled {
+        return Ok(None);
+    }
+
+    let cipher = match config.key_source {
+        KeySource::Passphrase => {
+            let passphrase = match passphrase {
+                Some(p) => p.to_string(),
+                None => std::env::var(PASSPHRASE_ENV)
+                    .map_err(|_| "Recordings are encrypted: passphrase required".to_string())?,
+            };
+            open_passphrase_cipher(output_dir, &passphrase)?
+        }
+        KeySource::Keyring => open_keyring_cipher()?,
+    };
+
+    Ok(Some(cipher))
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(key)
+}
+
+fn open_passphrase_cipher(output_dir: &str, passphrase: &str) -> Result<Cipher, String> {
+    let key_path = Path::new(output_dir).join(KEY_FILE);
+
+    if key_path.exists() {
+        let content = std::fs::read_to_string(&key_path)
+            .map_err(|e| format!("Failed to read {}: {}", KEY_FILE, e))?;
+        let key_file: KeyFile = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", KEY_FILE, e))?;
+
+        let salt = hex::decode(&key_file.salt).map_err(|e| format!("Invalid salt: {}", e))?;
+        let check = hex::decode(&key_file.check).map_err(|e| format!("Invalid check value: {}", e))?;
+
+        let cipher = Cipher::from_key(&derive_key(passphrase, &salt)?);
+        match cipher.decrypt(&check) {
+            Ok(plain) if plain == CHECK_PLAINTEXT => Ok(cipher),
+            _ => Err("Wrong passphrase".to_string()),
+        }
+    } else {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+
+        let cipher = Cipher::from_key(&derive_key(passphrase, &salt)?);
+        let key_file = KeyFile {
+            kdf: "argon2id".to_string(),
+            salt: hex::encode(salt),
+            check: hex::encode(cipher.encrypt(CHECK_PLAINTEXT)?),
+        };
+
+        std::fs::create_dir_all(output_dir)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        let content = serde_json::to_vec_pretty(&key_file)
+            .map_err(|e| format!("Failed to serialize {}: {}", KEY_FILE, e))?;
+        std::fs::write(&key_path, content)
+            .map_err(|e| format!("Failed to write {}: {}", KEY_FILE, e))?;
+
+        Ok(cipher)
+    }
+}
+
+fn open_keyring_cipher() -> Result<Cipher, String> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| format!("Failed to open keyring: {}", e))?;
+
+    let key = match entry.get_password() {
+        Ok(stored) => {
+            let bytes = hex::decode(stored).map_err(|e| format!("Invalid key in keyring: {}", e))?;
+            <[u8; 32]>::try_from(bytes.as_slice())
+                .map_err(|_| "Invalid key length in keyring".to_string())?
+        }
+        Err(keyring::Error::NoEntry) => {
+            let mut key = [0u8; 32];
+            OsRng.fill_bytes(&mut key);
+            entry
+                .set_password(&hex::encode(key))
+                .map_err(|e| format!("Failed to store key in keyring: {}", e))?;
+            key
+        }
+        Err(e) => return Err(format!("Failed to read keyring: {}", e)),
+    };
+
+    Ok(Cipher::from_key(&key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let cipher = Cipher::from_key(&[7u8; 32]);
+        let encrypted = cipher.encrypt(b"frame data").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), b"frame data");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_rejected() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let output_dir = dir.to_string_lossy().to_string();
+        let config = EncryptionConfig {
+            enabled: true,
+            key_source: KeySource::Passphrase,
+        };
+
+        assert!(open_cipher(&config, &output_dir, Some("correct horse")).unwrap().is_some());
+        assert!(open_cipher(&config, &output_dir, Some("correct horse")).is_ok());
+        assert!(open_cipher(&config, &output_dir, Some("wrong")).is_err());
+    }
+}
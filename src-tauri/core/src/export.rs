@@ -0,0 +1,615 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use parquet::basic::{Compression, LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::types::Type;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::crypto::Cipher;
+use crate::models::TradeEvent;
+use crate::positions::{self, ClipLink, FeeModel, RoundTrip};
+use crate::session::{self, SessionManifest};
+use crate::tracks::{self, FrameRecord};
+use crate::trade_store::{TradeQuery, TradeStore};
+
+/// 전체 화면 프레임 트랙 이름 (`frames` 데이터셋의 `track` 열)
+pub const SCREEN_TRACK: &str = "screen";
+
+/// 내보내기 파일 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+}
+
+/// 내보낼 범위
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExportFilter {
+    /// 이 시각 이상 (거래 시각, 세션 시작 시각 기준)
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// 이 시각 미만
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    /// 비어 있지 않으면 이 종목 거래와 그 거래가 있던 세션만
+    #[serde(default)]
+    pub tickers: Vec<String>,
+}
+
+/// 내보낸 파일 하나
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedFile {
+    pub dataset: String,
+    pub path: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    Text,
+    Int,
+    Float,
+    /// UTC 밀리초 (CSV는 RFC 3339)
+    Time,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Text(Option<String>),
+    Int(Option<i64>),
+    Float(Option<f64>),
+    Time(Option<DateTime<Utc>>),
+}
+
+impl Value {
+    fn csv(&self) -> String {
+        match self {
+            Value::Text(v) => v.clone().unwrap_or_default(),
+            Value::Int(v) => v.map(|v| v.to_string()).unwrap_or_default(),
+            Value::Float(v) => v.map(|v| v.to_string()).unwrap_or_default(),
+            Value::Time(v) => v
+                .map(|v| v.to_rfc3339_opts(SecondsFormat::Millis, true))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn text(v: impl Into<String>) -> Value {
+    Value::Text(Some(v.into()))
+}
+
+fn opt_text(v: Option<&str>) -> Value {
+    Value::Text(v.map(str::to_string))
+}
+
+fn int(v: impl TryInto<i64>) -> Value {
+    Value::Int(v.try_into().ok())
+}
+
+/// 열 이름/형식이 고정된 표 하나
+struct Table {
+    name: &'static str,
+    columns: &'static [(&'static str, ColumnKind)],
+    rows: Vec<Vec<Value>>,
+}
+
+use ColumnKind::{Float, Int, Text, Time};
+
+/// `trades` 열 (README의 스키마와 같이 유지)
+const TRADE_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("trade_id", Text),
+    ("timestamp", Time),
+    ("action", Text),
+    ("source", Text),
+    ("ticker", Text),
+    ("instrument_name", Text),
+    ("market", Text),
+    ("quantity", Int),
+    ("price", Float),
+    ("order_type", Text),
+    ("order_id", Text),
+    ("fill_status", Text),
+    ("confidence", Float),
+    ("session_id", Text),
+    ("session_offset_ms", Int),
+    ("frame_index", Int),
+    ("rating", Int),
+    ("tags", Text),
+    ("notes", Text),
+    ("screenshot_path", Text),
+    ("revision", Int),
+];
+
+const ROUND_TRIP_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("round_trip_id", Text),
+    ("ticker", Text),
+    ("instrument_name", Text),
+    ("opened_at", Time),
+    ("closed_at", Time),
+    ("quantity", Int),
+    ("avg_entry_price", Float),
+    ("avg_exit_price", Float),
+    ("gross_pnl", Float),
+    ("fees", Float),
+    ("taxes", Float),
+    ("net_pnl", Float),
+    ("return_pct", Float),
+    ("holding_secs", Int),
+    ("avg_lot_holding_secs", Int),
+    ("entry_trade_ids", Text),
+    ("exit_trade_ids", Text),
+    ("clip_session_id", Text),
+    ("clip_start_ms", Int),
+    ("clip_end_ms", Int),
+    ("clips", Text),
+];
+
+const SESSION_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("session_id", Text),
+    ("started_at", Time),
+    ("ended_at", Time),
+    ("duration_secs", Int),
+    ("frame_count", Int),
+    ("total_bytes", Int),
+    ("encrypted", Int),
+    ("hts_profile", Text),
+    ("hts_process", Text),
+    ("region_tracks", Text),
+];
+
+const FRAME_COLUMNS: &[(&str, ColumnKind)] = &[
+    ("session_id", Text),
+    ("track", Text),
+    ("frame_index", Int),
+    ("offset_ms", Int),
+    ("captured_at", Time),
+    ("file", Text),
+    ("focused_window", Text),
+];
+
+/// 목록 열은 `;`로 이어 붙임
+fn join_list<'a>(items: impl IntoIterator<Item = &'a str>) -> Value {
+    text(items.into_iter().collect::<Vec<_>>().join(";"))
+}
+
+/// serde 이름 그대로 (snake_case)
+fn enum_name<T: Serialize>(value: &T) -> Value {
+    Value::Text(serde_json::to_value(value).ok().and_then(|v| v.as_str().map(str::to_string)))
+}
+
+fn trade_row(trade: &TradeEvent) -> Vec<Value> {
+    vec![
+        text(&trade.id),
+        Value::Time(Some(trade.timestamp)),
+        enum_name(&trade.action),
+        enum_name(&trade.source),
+        opt_text(trade.ticker.as_deref()),
+        opt_text(trade.instrument_name.as_deref()),
+        trade.market.as_ref().map(enum_name).unwrap_or(Value::Text(None)),
+        Value::Int(trade.quantity.and_then(|q| i64::try_from(q).ok())),
+        Value::Float(trade.price),
+        trade.order_type.as_ref().map(enum_name).unwrap_or(Value::Text(None)),
+        opt_text(trade.order_id.as_deref()),
+        trade.fill_status.as_ref().map(enum_name).unwrap_or(Value::Text(None)),
+        Value::Float(trade.confidence.map(f64::from)),
+        opt_text(trade.session.as_ref().map(|s| s.session_id.as_str())),
+        Value::Int(trade.session.as_ref().and_then(|s| i64::try_from(s.offset_ms).ok())),
+        Value::Int(trade.session.as_ref().and_then(|s| s.frame_index).and_then(|i| i64::try_from(i).ok())),
+        Value::Int(trade.rating.map(i64::from)),
+        join_list(trade.tags.iter().map(String::as_str)),
+        join_list(trade.notes.iter().map(|n| n.text.as_str())),
+        opt_text(trade.screenshot_path.as_deref()),
+        int(trade.revision),
+    ]
+}
+
+/// `<session_id>:<start_ms>-<end_ms>`
+fn clip_ref(clip: &ClipLink) -> String {
+    format!("{}:{}-{}", clip.session_id, clip.start_ms, clip.end_ms)
+}
+
+fn round_trip_row(trip: &RoundTrip) -> Vec<Value> {
+    // clip_* 열은 첫 구간, 여러 세션에 걸친 매매는 clips 열에 전부
+    let clip = trip.clips.first();
+    let clips: Vec<String> = trip.clips.iter().map(clip_ref).collect();
+    vec![
+        text(&trip.id),
+        text(&trip.ticker),
+        opt_text(trip.instrument_name.as_deref()),
+        Value::Time(Some(trip.opened_at)),
+        Value::Time(Some(trip.closed_at)),
+        int(trip.quantity),
+        Value::Float(Some(trip.avg_entry_price)),
+        Value::Float(Some(trip.avg_exit_price)),
+        Value::Float(Some(trip.gross_pnl)),
+        Value::Float(Some(trip.fees)),
+        Value::Float(Some(trip.taxes)),
+        Value::Float(Some(trip.net_pnl)),
+        Value::Float(Some(trip.return_pct)),
+        int(trip.holding_secs),
+        int(trip.avg_lot_holding_secs),
+        join_list(trip.entry_trade_ids.iter().map(String::as_str)),
+        join_list(trip.exit_trade_ids.iter().map(String::as_str)),
+        opt_text(clip.map(|c| c.session_id.as_str())),
+        Value::Int(clip.and_then(|c| i64::try_from(c.start_ms).ok())),
+        Value::Int(clip.and_then(|c| i64::try_from(c.end_ms).ok())),
+        join_list(clips.iter().map(String::as_str)),
+    ]
+}
+
+fn frame_row(session_id: &str, track: &str, frame: &FrameRecord) -> Vec<Value> {
+    vec![
+        text(session_id),
+        text(track),
+        int(frame.index),
+        int(frame.offset_ms),
+        Value::Time(Some(frame.captured_at)),
+        text(&frame.file),
+        opt_text(frame.focused_window.as_deref()),
+    ]
+}
+
+/// 거래, 왕복 매매, 세션, 프레임 트랙을 `dest_dir`에 데이터셋별 파일로 내보내기
+///
+/// 파일 이름은 `trades`, `round_trips`, `sessions`, `frames` + 확장자.
+pub fn export_data(
+    output_dir: &str,
+    dest_dir: &str,
+    format: ExportFormat,
+    filter: &ExportFilter,
+    cipher: Option<&Cipher>,
+    fees: &FeeModel,
+) -> Result<Vec<ExportedFile>, String> {
+    let in_range = |at: DateTime<Utc>| filter.from.is_none_or(|from| at >= from) && filter.to.is_none_or(|to| at < to);
+    let ticker_matches = |ticker: &str| filter.tickers.is_empty() || filter.tickers.iter().any(|t| t == ticker);
+
+    // 거래와 왕복 매매 (선입선출은 종목별이라 종목 필터는 계산 후에 적용)
+    let query = TradeQuery {
+        from: filter.from,
+        to: filter.to,
+        ..Default::default()
+    };
+    let trades = TradeStore::open(output_dir, cipher)?.matching(&query)?;
+    let report = positions::compute_positions(&trades, fees);
+    let trades: Vec<&TradeEvent> = trades
+        .iter()
+        .filter(|t| filter.tickers.is_empty() || t.ticker.as_deref().is_some_and(ticker_matches))
+        .collect();
+
+    // 종목 필터가 있으면 그 거래가 있던 세션만
+    let trade_sessions: BTreeSet<&str> = trades
+        .iter()
+        .filter_map(|t| t.session.as_ref().map(|s| s.session_id.as_str()))
+        .collect();
+
+    let mut sessions = Table {
+        name: "sessions",
+        columns: SESSION_COLUMNS,
+        rows: Vec::new(),
+    };
+    let mut frames = Table {
+        name: "frames",
+        columns: FRAME_COLUMNS,
+        rows: Vec::new(),
+    };
+
+    let mut infos = session::list_sessions(output_dir)?;
+    infos.reverse();
+    for info in infos {
+        if !filter.tickers.is_empty() && !trade_sessions.contains(info.id.as_str()) {
+            continue;
+        }
+        let dir = Path::new(&info.path);
+        let manifest: SessionManifest = match session::read_manifest(dir, cipher) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("Skipping session {}: {}", info.id, e);
+                continue;
+            }
+        };
+        if !in_range(manifest.started_at) {
+            continue;
+        }
+
+        let screen_frames: Vec<FrameRecord> = tracks::read_track(&dir.join(tracks::FRAMES_TRACK), cipher)?;
+        let ended_at = manifest.ended_at.or_else(|| screen_frames.last().map(|f| f.captured_at));
+
+        sessions.rows.push(vec![
+            text(&info.id),
+            Value::Time(Some(manifest.started_at)),
+            Value::Time(ended_at),
+            Value::Int(ended_at.map(|end| (end - manifest.started_at).num_seconds())),
+            int(info.frame_count),
+            int(info.total_bytes),
+            int(manifest.encrypted as i64),
+            opt_text(manifest.profile.as_ref().map(|p| p.profile_name.as_str())),
+            opt_text(manifest.profile.as_ref().map(|p| p.process_name.as_str())),
+            join_list(manifest.tracks.iter().map(|t| t.region.id.as_str())),
+        ]);
+
+        for frame in screen_frames.iter().filter(|f| in_range(f.captured_at)) {
+            frames.rows.push(frame_row(&info.id, SCREEN_TRACK, frame));
+        }
+        for track in &manifest.tracks {
+            let records: Vec<FrameRecord> = tracks::read_track(&dir.join(&track.dir).join(tracks::FRAMES_TRACK), cipher)?;
+            for frame in records.iter().filter(|f| in_range(f.captured_at)) {
+                frames.rows.push(frame_row(&info.id, &track.region.id, frame));
+            }
+        }
+    }
+
+    let tables = [
+        Table {
+            name: "trades",
+            columns: TRADE_COLUMNS,
+            rows: trades.iter().map(|t| trade_row(t)).collect(),
+        },
+        Table {
+            name: "round_trips",
+            columns: ROUND_TRIP_COLUMNS,
+            rows: report
+                .round_trips
+                .iter()
+                .filter(|t| ticker_matches(&t.ticker))
+                .map(round_trip_row)
+                .collect(),
+        },
+        sessions,
+        frames,
+    ];
+
+    std::fs::create_dir_all(dest_dir).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    let mut files = Vec::new();
+    for table in &tables {
+        let path = Path::new(dest_dir).join(format!("{}.{}", table.name, format.extension()));
+        match format {
+            ExportFormat::Csv => write_csv(&path, table)?,
+            ExportFormat::Parquet => write_parquet(&path, table)?,
+        }
+        files.push(ExportedFile {
+            dataset: table.name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            rows: table.rows.len(),
+        });
+    }
+    Ok(files)
+}
+
+fn write_csv(path: &Path, table: &Table) -> Result<(), String> {
+    let mut writer = csv::Writer::from_path(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    writer
+        .write_record(table.columns.iter().map(|(name, _)| *name))
+        .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    for row in &table.rows {
+        writer
+            .write_record(row.iter().map(Value::csv))
+            .map_err(|e| format!("Failed to write CSV: {}", e))?;
+    }
+    writer.flush().map_err(|e| format!("Failed to write CSV: {}", e))
+}
+
+fn parquet_schema(table: &Table) -> Result<Type, String> {
+    let fields = table
+        .columns
+        .iter()
+        .map(|(name, kind)| {
+            let (physical, logical) = match kind {
+                Text => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                Int => (PhysicalType::INT64, None),
+                Float => (PhysicalType::DOUBLE, None),
+                Time => (
+                    PhysicalType::INT64,
+                    Some(LogicalType::Timestamp {
+                        is_adjusted_to_u_t_c: true,
+                        unit: TimeUnit::MILLIS(Default::default()),
+                    }),
+                ),
+            };
+            Type::primitive_type_builder(name, physical)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical)
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to build Parquet schema: {}", e))?;
+
+    Type::group_type_builder(table.name)
+        .with_fields(fields)
+        .build()
+        .map_err(|e| format!("Failed to build Parquet schema: {}", e))
+}
+
+/// 값이 없는 칸은 정의 레벨 0 (optional 열)
+fn definition_levels<T>(values: &[Option<T>]) -> Vec<i16> {
+    values.iter().map(|v| v.is_some() as i16).collect()
+}
+
+fn write_parquet(path: &Path, table: &Table) -> Result<(), String> {
+    let to_err = |e: parquet::errors::ParquetError| format!("Failed to write Parquet {}: {}", path.display(), e);
+
+    let file = std::fs::File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = SerializedFileWriter::new(file, Arc::new(parquet_schema(table)?), Arc::new(props)).map_err(to_err)?;
+
+    let mut row_group = writer.next_row_group().map_err(to_err)?;
+    let mut column = 0;
+    while let Some(mut column_writer) = row_group.next_column().map_err(to_err)? {
+        let cells = table.rows.iter().map(|row| &row[column]);
+        match table.columns[column].1 {
+            Text => {
+                let values: Vec<Option<ByteArray>> = cells
+                    .map(|v| match v {
+                        Value::Text(Some(s)) => Some(ByteArray::from(s.as_str())),
+                        _ => None,
+                    })
+                    .collect();
+                let present: Vec<ByteArray> = values.iter().flatten().cloned().collect();
+                column_writer
+                    .typed::<ByteArrayType>()
+                    .write_batch(&present, Some(&definition_levels(&values)), None)
+                    .map_err(to_err)?;
+            }
+            Int | Time => {
+                let values: Vec<Option<i64>> = cells
+                    .map(|v| match v {
+                        Value::Int(v) => *v,
+                        Value::Time(v) => v.map(|t| t.timestamp_millis()),
+                        _ => None,
+                    })
+                    .collect();
+                let present: Vec<i64> = values.iter().flatten().copied().collect();
+                column_writer
+                    .typed::<Int64Type>()
+                    .write_batch(&present, Some(&definition_levels(&values)), None)
+                    .map_err(to_err)?;
+            }
+            Float => {
+                let values: Vec<Option<f64>> = cells
+                    .map(|v| match v {
+                        Value::Float(v) => *v,
+                        _ => None,
+                    })
+                    .collect();
+                let present: Vec<f64> = values.iter().flatten().copied().collect();
+                column_writer
+                    .typed::<DoubleType>()
+                    .write_batch(&present, Some(&definition_levels(&values)), None)
+                    .map_err(to_err)?;
+            }
+        }
+        column_writer.close().map_err(to_err)?;
+        column += 1;
+    }
+    row_group.close().map_err(to_err)?;
+    writer.close().map_err(to_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::{self, JournalEdit};
+    use crate::models::{TradeAction, TradeSource};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn record_trades(output_dir: &str) {
+        let mut store = TradeStore::open(output_dir, None).unwrap();
+        for (ticker, action, minutes_ago, price) in [
+            ("005930", TradeAction::Buy, 10, 70_000.0),
+            ("005930", TradeAction::Sell, 5, 71_000.0),
+            ("000660", TradeAction::Buy, 3, 200_000.0),
+        ] {
+            let at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+            let mut trade = TradeEvent::new(action, at, TradeSource::LogImport);
+            trade.ticker = Some(ticker.to_string());
+            trade.quantity = Some(10);
+            trade.price = Some(price);
+            trade.tags = vec!["plan".to_string(), "scalp".to_string()];
+            for text in ["진입 근거", "손절 라인"] {
+                journal::apply(&mut trade, &JournalEdit::AddNote { text: text.to_string() }, at).unwrap();
+            }
+            store.record(trade).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_csv_export_with_ticker_filter() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let output_dir = dir.to_str().unwrap();
+        record_trades(output_dir);
+
+        let dest = dir.join("export");
+        let filter = ExportFilter {
+            tickers: vec!["005930".to_string()],
+            ..Default::default()
+        };
+        let fees = FeeModel::default();
+        let files = export_data(output_dir, dest.to_str().unwrap(), ExportFormat::Csv, &filter, None, &fees).unwrap();
+        let rows: Vec<(&str, usize)> = files.iter().map(|f| (f.dataset.as_str(), f.rows)).collect();
+        assert_eq!(rows, vec![("trades", 2), ("round_trips", 1), ("sessions", 0), ("frames", 0)]);
+
+        let mut reader = csv::Reader::from_path(dest.join("trades.csv")).unwrap();
+        let headers: Vec<String> = reader.headers().unwrap().iter().map(str::to_string).collect();
+        assert_eq!(headers.len(), TRADE_COLUMNS.len());
+        let first = reader.records().next().unwrap().unwrap();
+        assert_eq!(&first[2], "buy");
+        assert_eq!(&first[4], "005930");
+        assert_eq!(&first[17], "plan;scalp");
+        assert_eq!(&first[18], "진입 근거;손절 라인");
+
+        let mut reader = csv::Reader::from_path(dest.join("round_trips.csv")).unwrap();
+        assert_eq!(reader.headers().unwrap().len(), ROUND_TRIP_COLUMNS.len());
+    }
+
+    #[test]
+    fn test_round_trip_row_lists_all_clips() {
+        let at = Utc::now();
+        let clip = |session_id: &str, start_ms, end_ms| ClipLink {
+            session_id: session_id.to_string(),
+            start_ms,
+            end_ms,
+            start_frame: None,
+            end_frame: None,
+        };
+        let trip = RoundTrip {
+            id: "t1".to_string(),
+            ticker: "005930".to_string(),
+            instrument_name: None,
+            market: None,
+            opened_at: at,
+            closed_at: at,
+            quantity: 10,
+            avg_entry_price: 70_000.0,
+            avg_exit_price: 71_000.0,
+            gross_pnl: 10_000.0,
+            fees: 0.0,
+            taxes: 0.0,
+            net_pnl: 10_000.0,
+            return_pct: 1.4,
+            holding_secs: 0,
+            avg_lot_holding_secs: 0,
+            entry_trade_ids: vec!["t1".to_string()],
+            exit_trade_ids: vec!["t2".to_string()],
+            clips: vec![clip("recording_a", 0, 5_000), clip("recording_b", 1_000, 9_000)],
+        };
+
+        let row = round_trip_row(&trip);
+        assert_eq!(row.len(), ROUND_TRIP_COLUMNS.len());
+        assert!(matches!(&row[17], Value::Text(Some(id)) if id == "recording_a"));
+        assert!(matches!(&row[20], Value::Text(Some(clips)) if clips == "recording_a:0-5000;recording_b:1000-9000"));
+    }
+
+    #[test]
+    fn test_parquet_export_readable() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let output_dir = dir.to_str().unwrap();
+        record_trades(output_dir);
+
+        let dest = dir.join("export");
+        let filter = ExportFilter::default();
+        let fees = FeeModel::default();
+        export_data(output_dir, dest.to_str().unwrap(), ExportFormat::Parquet, &filter, None, &fees).unwrap();
+
+        let reader = SerializedFileReader::new(std::fs::File::open(dest.join("trades.parquet")).unwrap()).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 3);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), TRADE_COLUMNS.len());
+    }
+}
@@ -0,0 +1,437 @@
+use rdev::{Button, EventType};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use screenshots::Screen;
+use tokio_util::sync::CancellationToken;
+use xcap::image::{Rgba, RgbaImage};
+
+use crate::crypto::Cipher;
+use crate::matcher::PatternMatcher;
+use crate::models::Rect;
+use crate::pipeline::SessionShared;
+use crate::redaction::Redactor;
+use crate::tracks::TrackWriter;
+use crate::window_capture::WindowCapture;
+
+/// 커서 위치/클릭 트랙 파일
+pub const INPUT_TRACK: &str = "input.jsonl";
+
+/// 취소 확인 간격
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// HTS 윈도우 위치를 다시 찾는 간격
+const WINDOW_REFRESH: Duration = Duration::from_secs(1);
+
+/// 클릭 표시를 남기는 시간 (ms)
+const CLICK_HIGHLIGHT_MS: u64 = 700;
+
+/// 마지막 위치가 이보다 오래됐으면 커서를 그리지 않음 (HTS 밖으로 나간 경우 등)
+const CURSOR_STALE_MS: u64 = 5_000;
+
+/// 커서/클릭 기록 설정 (기본 꺼짐)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputTrackingConfig {
+    /// 켠 경우에만 입력 훅 설치. `rdev::listen`은 키보드 훅도 함께 설치하지만
+    /// 키 입력은 훅 콜백에서 바로 버리고 기록하지 않음
+    #[serde(default)]
+    pub enabled: bool,
+    /// HTS 윈도우 안의 위치/클릭만 기록 (다른 프로그램에서의 움직임은 버림)
+    #[serde(default = "default_hts_window_only")]
+    pub hts_window_only: bool,
+    /// 커서 이동은 이 간격(ms)마다 하나만 기록 (클릭은 모두 기록)
+    #[serde(default = "default_move_interval_ms")]
+    pub move_interval_ms: u64,
+    /// 클립 내보내기에서 커서/클릭을 프레임에 그릴지 기본값
+    #[serde(default = "default_render_in_clips")]
+    pub render_in_clips: bool,
+}
+
+fn default_hts_window_only() -> bool {
+    true
+}
+
+fn default_move_interval_ms() -> u64 {
+    100
+}
+
+fn default_render_in_clips() -> bool {
+    true
+}
+
+impl Default for InputTrackingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hts_window_only: default_hts_window_only(),
+            move_interval_ms: default_move_interval_ms(),
+            render_in_clips: default_render_in_clips(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    Move,
+    Press,
+    Release,
+    Wheel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other,
+}
+
+impl From<Button> for MouseButton {
+    fn from(button: Button) -> Self {
+        match button {
+            Button::Left => MouseButton::Left,
+            Button::Right => MouseButton::Right,
+            Button::Middle => MouseButton::Middle,
+            _ => MouseButton::Other,
+        }
+    }
+}
+
+/// 커서 이벤트 하나
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputRecord {
+    /// 세션 시작부터의 오프셋 (ms, 프레임 트랙과 같은 기준)
+    pub offset_ms: u64,
+    /// 전체 화면 프레임 기준 좌표 (px)
+    pub x: i32,
+    pub y: i32,
+    pub kind: InputKind,
+    #[serde(default)]
+    pub button: Option<MouseButton>,
+}
+
+/// 훅이 넘기는 마우스 이벤트 (키보드 이벤트는 만들지 않음)
+enum HookEvent {
+    Move(f64, f64),
+    Press(MouseButton),
+    Release(MouseButton),
+    Wheel,
+}
+
+/// 녹화 중인 세션의 이벤트 수신기
+///
+/// `rdev::listen`은 한 번 시작하면 멈출 수 없으므로 처음 쓸 때 프로세스에 하나만 띄우고,
+/// 녹화 중이 아닐 때는 받은 이벤트를 바로 버린다.
+fn hook_sink() -> &'static Mutex<Option<Sender<HookEvent>>> {
+    static SINK: OnceLock<Mutex<Option<Sender<HookEvent>>>> = OnceLock::new();
+    SINK.get_or_init(|| {
+        let spawned = thread::Builder::new().name("input-hook".to_string()).spawn(|| {
+            let result = rdev::listen(|event| {
+                let event = match event.event_type {
+                    EventType::MouseMove { x, y } => HookEvent::Move(x, y),
+                    EventType::ButtonPress(button) => HookEvent::Press(button.into()),
+                    EventType::ButtonRelease(button) => HookEvent::Release(button.into()),
+                    EventType::Wheel { .. } => HookEvent::Wheel,
+                    // 키 입력은 훅 밖으로 내보내지 않음
+                    EventType::KeyPress(_) | EventType::KeyRelease(_) => return,
+                };
+                // 훅 콜백이 막히면 OS 마우스 입력이 느려지므로 제한 없는 채널로 넘기기만 함
+                if let Some(tx) = hook_sink().lock().unwrap().as_ref() {
+                    let _ = tx.send(event);
+                }
+            });
+            if let Err(e) = result {
+                eprintln!("Input hook failed: {:?}", e);
+            }
+        });
+        if let Err(e) = spawned {
+            eprintln!("Failed to start input hook: {}", e);
+        }
+        Mutex::new(None)
+    })
+}
+
+/// 세션 커서 기록에 필요한 상태
+pub(crate) struct InputContext {
+    pub session_dir: PathBuf,
+    pub config: InputTrackingConfig,
+    pub window_matcher: PatternMatcher,
+    pub redactor: Arc<Redactor>,
+    /// 있으면 커서 기록을 암호화해서 씀
+    pub cipher: Option<Cipher>,
+    pub cancel: CancellationToken,
+    pub shared: Arc<SessionShared>,
+}
+
+/// 화면 좌표 → 프레임 좌표 변환과 기록 범위
+struct InputFilter {
+    display: Rect,
+    /// 프레임 좌표계 HTS 윈도우 (`hts_window_only`이거나 가림 영역이 있을 때만)
+    hts_window: Option<Rect>,
+    hts_window_only: bool,
+    /// 가림 영역 안의 위치/클릭은 프레임에서 가려지므로 기록하지 않음
+    redactor: Arc<Redactor>,
+}
+
+impl InputFilter {
+    fn to_frame(&self, x: f64, y: f64) -> (i32, i32) {
+        (x.round() as i32 - self.display.x, y.round() as i32 - self.display.y)
+    }
+
+    fn allows(&self, (x, y): (i32, i32)) -> bool {
+        let inside = |r: &Rect| x >= r.x && y >= r.y && x < r.x + r.width as i32 && y < r.y + r.height as i32;
+        let frame = Rect {
+            x: 0,
+            y: 0,
+            ..self.display
+        };
+        if !inside(&frame) || self.redactor.hides((x, y), self.hts_window) {
+            return false;
+        }
+        !self.hts_window_only || self.hts_window.as_ref().is_some_and(inside)
+    }
+
+    fn tracks_window(&self) -> bool {
+        self.hts_window_only || self.redactor.masks_regions()
+    }
+}
+
+/// 녹화 중 커서/클릭 기록 스레드 시작
+pub(crate) fn spawn(ctx: InputContext) -> Result<JoinHandle<()>, String> {
+    let mut track = TrackWriter::<InputRecord>::open_sealed(&ctx.session_dir.join(INPUT_TRACK), ctx.cipher.as_ref())?;
+    let (tx, rx) = mpsc::channel::<HookEvent>();
+    *hook_sink().lock().unwrap() = Some(tx);
+
+    thread::Builder::new()
+        .name("recorder-input".to_string())
+        .spawn(move || {
+            // 전체 화면 트랙과 같은 첫 번째 화면 기준
+            let display = match Screen::all().ok().and_then(|s| s.first().cloned()) {
+                Some(screen) => Rect {
+                    x: screen.display_info.x,
+                    y: screen.display_info.y,
+                    width: screen.display_info.width,
+                    height: screen.display_info.height,
+                },
+                None => {
+                    eprintln!("Input tracking disabled: no screen found");
+                    *hook_sink().lock().unwrap() = None;
+                    return;
+                }
+            };
+            let mut filter = InputFilter {
+                display,
+                hts_window: None,
+                hts_window_only: ctx.config.hts_window_only,
+                redactor: Arc::clone(&ctx.redactor),
+            };
+            let move_interval = Duration::from_millis(ctx.config.move_interval_ms);
+            let mut last_window_check: Option<Instant> = None;
+            let mut last_move: Option<Instant> = None;
+            let mut position: Option<(i32, i32)> = None;
+            let mut written = 0usize;
+
+            while !ctx.cancel.is_cancelled() {
+                let event = match rx.recv_timeout(CANCEL_POLL) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                if ctx.shared.paused.load(Ordering::Relaxed) {
+                    continue;
+                }
+
+                if filter.tracks_window() && last_window_check.is_none_or(|t| t.elapsed() >= WINDOW_REFRESH) {
+                    filter.hts_window = WindowCapture::find_window_rect(&ctx.window_matcher).map(|r| Rect {
+                        x: r.x - display.x,
+                        y: r.y - display.y,
+                        ..r
+                    });
+                    last_window_check = Some(Instant::now());
+                }
+
+                let (kind, button) = match event {
+                    HookEvent::Move(x, y) => {
+                        position = Some(filter.to_frame(x, y));
+                        if last_move.is_some_and(|t| t.elapsed() < move_interval) {
+                            continue;
+                        }
+                        last_move = Some(Instant::now());
+                        (InputKind::Move, None)
+                    }
+                    HookEvent::Press(button) => (InputKind::Press, Some(button)),
+                    HookEvent::Release(button) => (InputKind::Release, Some(button)),
+                    HookEvent::Wheel => (InputKind::Wheel, None),
+                };
+
+                // 위치를 모르는 클릭, 화면/HTS 밖, 가림 영역 안 이벤트는 버림
+                let Some((x, y)) = position.filter(|&p| filter.allows(p)) else {
+                    continue;
+                };
+                let record = InputRecord {
+                    offset_ms: ctx.shared.clock.lock().unwrap().stamp().offset_ms,
+                    x,
+                    y,
+                    kind,
+                    button,
+                };
+                match track.append(&record) {
+                    Ok(()) => written += 1,
+                    Err(e) => eprintln!("{}", e),
+                }
+            }
+
+            *hook_sink().lock().unwrap() = None;
+            println!("Input tracking stopped: {} events", written);
+        })
+        .map_err(|e| format!("Failed to start input thread: {}", e))
+}
+
+fn blend(image: &mut RgbaImage, x: i32, y: i32, color: Rgba<u8>) {
+    if x < 0 || y < 0 || x >= image.width() as i32 || y >= image.height() as i32 {
+        return;
+    }
+    let pixel = image.get_pixel_mut(x as u32, y as u32);
+    let alpha = color[3] as u32;
+    for c in 0..3 {
+        pixel[c] = ((color[c] as u32 * alpha + pixel[c] as u32 * (255 - alpha)) / 255) as u8;
+    }
+}
+
+/// 중심에서 거리가 `inner`~`outer`인 픽셀 칠하기 (`inner`가 0이면 채운 원)
+fn draw_ring(image: &mut RgbaImage, cx: i32, cy: i32, inner: i32, outer: i32, color: Rgba<u8>) {
+    for dy in -outer..=outer {
+        for dx in -outer..=outer {
+            let d2 = dx * dx + dy * dy;
+            if d2 <= outer * outer && d2 >= inner * inner {
+                blend(image, cx + dx, cy + dy, color);
+            }
+        }
+    }
+}
+
+fn click_color(button: Option<MouseButton>) -> Rgba<u8> {
+    match button {
+        Some(MouseButton::Left) => Rgba([230, 40, 40, 220]),
+        Some(MouseButton::Right) => Rgba([40, 110, 230, 220]),
+        _ => Rgba([240, 190, 20, 220]),
+    }
+}
+
+/// 프레임에 `offset_ms` 시점의 커서 위치와 최근 클릭 표시 그리기
+///
+/// `records`는 오프셋 순으로 정렬돼 있어야 한다.
+pub fn render_overlay(image: &mut RgbaImage, records: &[InputRecord], offset_ms: u64) {
+    let next = records.partition_point(|r| r.offset_ms <= offset_ms);
+
+    // 클릭은 시간이 지날수록 커지는 고리
+    for record in records[..next].iter().rev() {
+        let age = offset_ms - record.offset_ms;
+        if age > CLICK_HIGHLIGHT_MS {
+            break;
+        }
+        if record.kind == InputKind::Press {
+            let radius = 10 + (age * 14 / CLICK_HIGHLIGHT_MS) as i32;
+            draw_ring(image, record.x, record.y, radius - 3, radius, click_color(record.button));
+        }
+    }
+
+    if let Some(last) = next.checked_sub(1).map(|i| &records[i]) {
+        if offset_ms - last.offset_ms <= CURSOR_STALE_MS {
+            draw_ring(image, last.x, last.y, 0, 7, Rgba([20, 20, 20, 200]));
+            draw_ring(image, last.x, last.y, 0, 5, Rgba([255, 230, 0, 230]));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redaction::{RedactionConfig, RedactionRegion};
+
+    fn record(offset_ms: u64, x: i32, y: i32, kind: InputKind) -> InputRecord {
+        InputRecord {
+            offset_ms,
+            x,
+            y,
+            kind,
+            button: (kind == InputKind::Press).then_some(MouseButton::Left),
+        }
+    }
+
+    #[test]
+    fn test_filter_hts_window_only() {
+        let filter = InputFilter {
+            display: Rect { x: -1920, y: 0, width: 1920, height: 1080 },
+            hts_window: Some(Rect { x: 100, y: 100, width: 800, height: 600 }),
+            hts_window_only: true,
+            redactor: Arc::new(Redactor::new(RedactionConfig::default()).unwrap()),
+        };
+        assert_eq!(filter.to_frame(-1720.4, 300.6), (200, 301));
+        assert!(filter.allows((200, 301)));
+        assert!(!filter.allows((50, 50)));
+        assert!(!filter.allows((2000, 300)));
+
+        let hidden = InputFilter { hts_window: None, ..filter };
+        assert!(!hidden.allows((200, 301)));
+    }
+
+    #[test]
+    fn test_filter_drops_redacted_region() {
+        let config = RedactionConfig {
+            enabled: true,
+            regions: vec![RedactionRegion {
+                name: "계좌".to_string(),
+                x: 0.5,
+                y: 0.0,
+                width: 0.5,
+                height: 1.0,
+            }],
+            ..Default::default()
+        };
+        let filter = InputFilter {
+            display: Rect { x: 0, y: 0, width: 1920, height: 1080 },
+            hts_window: Some(Rect { x: 100, y: 100, width: 800, height: 600 }),
+            hts_window_only: false,
+            redactor: Arc::new(Redactor::new(config).unwrap()),
+        };
+        assert!(filter.tracks_window());
+        assert!(filter.allows((200, 300)));
+        assert!(!filter.allows((600, 300)));
+        assert!(filter.allows((1500, 300)));
+
+        // 윈도우를 못 찾으면 프레임 전체가 가려지므로 모두 버림
+        let unanchored = InputFilter { hts_window: None, ..filter };
+        assert!(!unanchored.allows((200, 300)));
+    }
+
+    #[test]
+    fn test_render_overlay() {
+        let records = vec![
+            record(1_000, 20, 20, InputKind::Move),
+            record(1_200, 40, 40, InputKind::Press),
+            record(1_250, 40, 40, InputKind::Release),
+        ];
+        let blank = RgbaImage::from_pixel(80, 80, Rgba([255, 255, 255, 255]));
+
+        // 클릭 직후: 커서(노랑)와 클릭 고리(빨강)
+        let mut image = blank.clone();
+        render_overlay(&mut image, &records, 1_300);
+        assert_ne!(image.get_pixel(40, 40), blank.get_pixel(40, 40));
+        let ring = image.get_pixel(40 + 11, 40);
+        assert!(ring[0] > ring[2]);
+
+        // 기록 전이거나 오래 지나면 아무것도 그리지 않음
+        let mut image = blank.clone();
+        render_overlay(&mut image, &records, 500);
+        assert_eq!(image, blank);
+        render_overlay(&mut image, &records, 60_000);
+        assert_eq!(image, blank);
+    }
+}
@@ -0,0 +1,576 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+
+use crate::crypto::Cipher;
+use crate::regions;
+use crate::session;
+use crate::trade_store;
+
+/// 세션 디렉토리 안의 해시 체인 파일 (한 줄에 항목 하나, 추가만 함)
+pub const CHAIN_FILE: &str = "chain.jsonl";
+
+/// 출력 디렉토리에 두는 서명 키 기본 파일 이름
+pub const SIGNING_KEY_FILE: &str = "signing.key";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainEntryKind {
+    Frame,
+    Trade,
+}
+
+/// 해시 체인 항목
+///
+/// `hash = SHA-256(prev_hash | index | kind | reference | content_hash | recorded_at)`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainEntry {
+    pub index: u64,
+    pub kind: ChainEntryKind,
+    /// 프레임이면 세션 디렉토리 기준 파일 이름, 거래 이벤트면 이벤트 식별자
+    pub reference: String,
+    pub content_hash: String,
+    pub recorded_at: DateTime<Utc>,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// 매니페스트에 남기는 체인 요약과 서명
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityInfo {
+    pub chain_file: String,
+    pub length: u64,
+    pub root: String,
+    /// 루트 해시에 대한 Ed25519 서명 (hex)
+    pub signature: Option<String>,
+    pub public_key: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityConfig {
+    /// 녹화 종료 시 루트 해시를 로컬 키로 서명
+    pub sign_root: bool,
+    /// 서명 키 경로 (없으면 출력 디렉토리의 `signing.key`, 처음 사용할 때 생성)
+    pub signing_key_path: Option<String>,
+    /// 서명 검증에 쓸 공개 키 (hex). 없으면 로컬 서명 키의 공개 키를 쓴다.
+    #[serde(default)]
+    pub trusted_public_key: Option<String>,
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// 체인 시작 값 (세션마다 다름)
+fn genesis_hash(session_id: &str) -> String {
+    sha256_hex(format!("test-recorder:{}", session_id).as_bytes())
+}
+
+fn link_hash(
+    prev_hash: &str,
+    index: u64,
+    kind: ChainEntryKind,
+    reference: &str,
+    content_hash: &str,
+    recorded_at: &DateTime<Utc>,
+) -> String {
+    let kind = match kind {
+        ChainEntryKind::Frame => "frame",
+        ChainEntryKind::Trade => "trade",
+    };
+    sha256_hex(
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            prev_hash,
+            index,
+            kind,
+            reference,
+            content_hash,
+            recorded_at.to_rfc3339()
+        )
+        .as_bytes(),
+    )
+}
+
+/// 녹화 중인 세션의 해시 체인
+pub struct HashChain {
+    file: File,
+    last_hash: String,
+    length: u64,
+}
+
+impl HashChain {
+    pub fn create(session_dir: &Path, session_id: &str) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(session_dir.join(CHAIN_FILE))
+            .map_err(|e| format!("Failed to open hash chain: {}", e))?;
+
+        Ok(Self {
+            file,
+            last_hash: genesis_hash(session_id),
+            length: 0,
+        })
+    }
+
+    /// 체인에 항목 추가 (`content_hash`는 디스크에 쓴 바이트의 SHA-256)
+    pub fn append(
+        &mut self,
+        kind: ChainEntryKind,
+        reference: &str,
+        content_hash: &str,
+    ) -> Result<ChainEntry, String> {
+        let recorded_at = Utc::now();
+        let hash = link_hash(&self.last_hash, self.length, kind, reference, content_hash, &recorded_at);
+
+        let entry = ChainEntry {
+            index: self.length,
+            kind,
+            reference: reference.to_string(),
+            content_hash: content_hash.to_string(),
+            recorded_at,
+            prev_hash: self.last_hash.clone(),
+            hash: hash.clone(),
+        };
+
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize chain entry: {}", e))?;
+        writeln!(self.file, "{}", line)
+            .and_then(|_| self.file.flush())
+            .map_err(|e| format!("Failed to append to hash chain: {}", e))?;
+
+        self.last_hash = hash;
+        self.length += 1;
+        Ok(entry)
+    }
+
+    pub fn root(&self) -> &str {
+        &self.last_hash
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// 매니페스트에 남길 요약
+    pub fn info(&self) -> IntegrityInfo {
+        IntegrityInfo {
+            chain_file: CHAIN_FILE.to_string(),
+            length: self.length,
+            root: self.last_hash.clone(),
+            signature: None,
+            public_key: None,
+        }
+    }
+}
+
+fn signing_key_path(config: &IntegrityConfig, output_dir: &str) -> PathBuf {
+    match &config.signing_key_path {
+        Some(path) => PathBuf::from(path),
+        None => Path::new(output_dir).join(SIGNING_KEY_FILE),
+    }
+}
+
+/// 서명 키 읽기 (없으면 새로 만들어 저장)
+pub fn load_signing_key(config: &IntegrityConfig, output_dir: &str) -> Result<SigningKey, String> {
+    let path = signing_key_path(config, output_dir);
+
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read signing key: {}", e))?;
+        let bytes = hex::decode(content.trim()).map_err(|e| format!("Invalid signing key: {}", e))?;
+        let secret = <[u8; 32]>::try_from(bytes.as_slice())
+            .map_err(|_| "Invalid signing key length".to_string())?;
+        Ok(SigningKey::from_bytes(&secret))
+    } else {
+        let key = SigningKey::generate(&mut rand_core::OsRng);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create key directory: {}", e))?;
+        }
+        std::fs::write(&path, hex::encode(key.to_bytes()))
+            .map_err(|e| format!("Failed to write signing key: {}", e))?;
+        println!("Created signing key: {}", path.display());
+        Ok(key)
+    }
+}
+
+/// 루트 해시 서명
+pub fn sign_root(info: &mut IntegrityInfo, key: &SigningKey) {
+    let signature = key.sign(info.root.as_bytes());
+    info.signature = Some(hex::encode(signature.to_bytes()));
+    info.public_key = Some(hex::encode(key.verifying_key().to_bytes()));
+}
+
+fn parse_public_key(hex_key: &str) -> Option<VerifyingKey> {
+    let key_bytes = <[u8; 32]>::try_from(hex::decode(hex_key).ok()?.as_slice()).ok()?;
+    VerifyingKey::from_bytes(&key_bytes).ok()
+}
+
+/// 서명 검증에 믿을 공개 키
+///
+/// 설정에 고정한 공개 키가 있으면 그 키, 없으면 로컬 서명 키의 공개 키.
+/// 둘 다 없으면 None (서명된 세션은 모두 신뢰할 수 없는 키로 보고된다).
+pub fn trusted_key(config: &IntegrityConfig, output_dir: &str) -> Result<Option<VerifyingKey>, String> {
+    if let Some(pinned) = &config.trusted_public_key {
+        return parse_public_key(pinned.trim())
+            .map(Some)
+            .ok_or_else(|| "Invalid trusted public key".to_string());
+    }
+    if !signing_key_path(config, output_dir).exists() {
+        return Ok(None);
+    }
+    load_signing_key(config, output_dir).map(|key| Some(key.verifying_key()))
+}
+
+/// 루트 서명 검증 결과
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Valid,
+    Invalid,
+    /// 서명은 맞지만 믿는 키로 한 서명이 아님 (다시 서명된 세션)
+    UntrustedKey,
+}
+
+fn verify_signature(info: &IntegrityInfo, trusted: Option<&VerifyingKey>) -> Option<SignatureStatus> {
+    let signature = info.signature.as_ref()?;
+    let Some(key) = info.public_key.as_deref().and_then(parse_public_key) else {
+        return Some(SignatureStatus::Invalid);
+    };
+
+    let verified = <[u8; 64]>::try_from(hex::decode(signature).unwrap_or_default().as_slice())
+        .is_ok_and(|bytes| key.verify(info.root.as_bytes(), &Signature::from_bytes(&bytes)).is_ok());
+    Some(if !verified {
+        SignatureStatus::Invalid
+    } else if trusted != Some(&key) {
+        SignatureStatus::UntrustedKey
+    } else {
+        SignatureStatus::Valid
+    })
+}
+
+/// 거래 항목이 가리키는 기록이 체인에 올린 내용 그대로인지
+///
+/// 거래 파일은 추가만 하므로 ID의 첫 기록이 체인에 올린 원본이다 (뒤 기록은 저널 수정).
+fn trade_matches(path: &Path, id: &str, content_hash: &str, cipher: Option<&Cipher>) -> Result<(), String> {
+    let records = trade_store::read_trade_records(path, cipher)?;
+    let original = records
+        .iter()
+        .find(|e| e.id == id)
+        .ok_or_else(|| format!("Trade missing from {}", path.display()))?;
+    let content = serde_json::to_vec(original)
+        .map_err(|e| format!("Failed to serialize trade event: {}", e))?;
+    if sha256_hex(&content) == content_hash {
+        Ok(())
+    } else {
+        Err(format!("Trade record was modified in {}", path.display()))
+    }
+}
+
+/// 세션 안의 프레임 파일 (최상위와 영역 트랙), 세션 디렉토리 기준 경로
+fn frame_files(session_dir: &Path) -> Vec<String> {
+    fn collect(dir: &Path, prefix: &str, recurse: bool, files: &mut Vec<String>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if path.is_dir() && recurse {
+                collect(&path, &format!("{}{}/", prefix, name), recurse, files);
+            } else if path.is_file() && name.starts_with("frame_") {
+                files.push(format!("{}{}", prefix, name));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    collect(session_dir, "", false, &mut files);
+    collect(&session_dir.join(regions::REGIONS_DIR), &format!("{}/", regions::REGIONS_DIR), true, &mut files);
+    files.sort();
+    files
+}
+
+/// 처음으로 끊어진 체인 지점
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub index: u64,
+    pub reference: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub session_id: String,
+    pub valid: bool,
+    pub entries_checked: u64,
+    pub root: Option<String>,
+    pub first_broken: Option<BrokenLink>,
+    /// 매니페스트의 루트/길이와 일치하는지 (매니페스트나 무결성 정보가 없으면 None, 이 경우 실패)
+    pub root_matches_manifest: Option<bool>,
+    /// 매니페스트를 읽지 못했거나 무결성 정보가 없는 이유
+    pub manifest_error: Option<String>,
+    /// 서명 검증 결과 (서명이 없으면 None)
+    pub signature: Option<SignatureStatus>,
+    /// 체인에 없는 프레임 파일 (나중에 끼워 넣은 파일)
+    pub unreferenced_files: Vec<String>,
+}
+
+/// 세션의 해시 체인 검증
+///
+/// 체인 링크, 프레임/거래 기록 내용, 매니페스트 루트와 길이, 서명 순서로 확인하고
+/// 처음으로 어긋난 지점을 보고한다. 체인이 있는 세션은 매니페스트에 무결성 정보가 있어야 하고,
+/// 서명은 `trusted`(보통 [`trusted_key`]) 키로 한 것만 유효하다.
+pub fn verify_session(
+    session_dir: &Path,
+    cipher: Option<&Cipher>,
+    trusted: Option<&VerifyingKey>,
+) -> Result<VerifyReport, String> {
+    let session_id = session_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid session path: {}", session_dir.display()))?;
+
+    let file = File::open(session_dir.join(CHAIN_FILE))
+        .map_err(|e| format!("Failed to open hash chain: {}", e))?;
+
+    let mut prev_hash = genesis_hash(&session_id);
+    let mut checked = 0u64;
+    let mut first_broken = None;
+    let mut referenced = HashSet::new();
+
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Failed to read hash chain: {}", e))?;
+        let index = line_no as u64;
+
+        let broken = |reference: Option<String>, reason: String| BrokenLink { index, reference, reason };
+
+        let entry: ChainEntry = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(e) => {
+                first_broken = Some(broken(None, format!("Unreadable entry: {}", e)));
+                break;
+            }
+        };
+        let reference = Some(entry.reference.clone());
+
+        if entry.index != index {
+            first_broken = Some(broken(reference, format!("Expected index {}, found {}", index, entry.index)));
+            break;
+        }
+        if entry.prev_hash != prev_hash {
+            first_broken = Some(broken(reference, "Previous hash does not match".to_string()));
+            break;
+        }
+
+        let expected = link_hash(
+            &entry.prev_hash,
+            entry.index,
+            entry.kind,
+            &entry.reference,
+            &entry.content_hash,
+            &entry.recorded_at,
+        );
+        if entry.hash != expected {
+            first_broken = Some(broken(reference, "Entry hash does not match its contents".to_string()));
+            break;
+        }
+
+        match entry.kind {
+            ChainEntryKind::Frame => match std::fs::read(session_dir.join(&entry.reference)) {
+                Ok(data) if sha256_hex(&data) == entry.content_hash => {}
+                Ok(_) => {
+                    first_broken = Some(broken(reference, "Frame content was modified".to_string()));
+                    break;
+                }
+                Err(e) => {
+                    first_broken = Some(broken(reference, format!("Frame missing: {}", e)));
+                    break;
+                }
+            },
+            ChainEntryKind::Trade => {
+                let id = entry.reference.strip_prefix("trade:").unwrap_or(&entry.reference);
+                let mut checked_files = vec![session_dir.join(trade_store::TRADES_TRACK)];
+                if let Some(output_dir) = session_dir.parent() {
+                    checked_files.push(output_dir.join(trade_store::TRADE_INDEX_FILE));
+                }
+                if let Some(reason) = checked_files
+                    .iter()
+                    .find_map(|path| trade_matches(path, id, &entry.content_hash, cipher).err())
+                {
+                    first_broken = Some(broken(reference, reason));
+                    break;
+                }
+            }
+        }
+
+        referenced.insert(entry.reference);
+        prev_hash = entry.hash;
+        checked += 1;
+    }
+
+    let (integrity, manifest_error) = match session::read_manifest(session_dir, cipher) {
+        Ok(manifest) => match manifest.integrity {
+            Some(info) => (Some(info), None),
+            None => (None, Some("Manifest has no integrity information".to_string())),
+        },
+        Err(e) => (None, Some(format!("Manifest unreadable: {}", e))),
+    };
+
+    let root = (checked > 0).then(|| prev_hash.clone());
+    let root_matches_manifest = integrity.as_ref().map(|info| {
+        first_broken.is_none() && info.length == checked && info.root == prev_hash
+    });
+    let signature = integrity.as_ref().and_then(|info| verify_signature(info, trusted));
+    let unreferenced_files: Vec<String> = frame_files(session_dir)
+        .into_iter()
+        .filter(|file| !referenced.contains(file))
+        .collect();
+
+    Ok(VerifyReport {
+        session_id,
+        valid: first_broken.is_none()
+            && root_matches_manifest == Some(true)
+            && signature.is_none_or(|status| status == SignatureStatus::Valid)
+            && unreferenced_files.is_empty(),
+        entries_checked: checked,
+        root,
+        first_broken,
+        root_matches_manifest,
+        manifest_error,
+        signature,
+        unreferenced_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TradeAction, TradeEvent, TradeSource};
+    use crate::tracks::TrackWriter;
+
+    /// 임시 출력 디렉토리 아래 세션 디렉토리
+    fn temp_session(output: &tempfile::TempDir, name: &str) -> PathBuf {
+        let dir = output.path().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 프레임 `count`개와 거래 하나를 기록하고 매니페스트를 마무리한 세션
+    fn write_session(dir: &Path, count: usize, key: Option<&SigningKey>) {
+        let session_id = dir.file_name().unwrap().to_string_lossy().to_string();
+        let mut chain = HashChain::create(dir, &session_id).unwrap();
+        for i in 1..=count {
+            let name = format!("frame_{:06}.png", i);
+            let data = format!("frame {}", i);
+            std::fs::write(dir.join(&name), &data).unwrap();
+            chain.append(ChainEntryKind::Frame, &name, &sha256_hex(data.as_bytes())).unwrap();
+        }
+
+        let event = TradeEvent::new(TradeAction::Buy, Utc::now(), TradeSource::Manual);
+        let content = serde_json::to_vec(&event).unwrap();
+        chain.append(ChainEntryKind::Trade, &format!("trade:{}", event.id), &sha256_hex(&content)).unwrap();
+        TrackWriter::open(&dir.join(trade_store::TRADES_TRACK)).unwrap().append(&event).unwrap();
+        TrackWriter::open(&dir.parent().unwrap().join(trade_store::TRADE_INDEX_FILE))
+            .unwrap()
+            .append(&event)
+            .unwrap();
+
+        let mut info = chain.info();
+        if let Some(key) = key {
+            sign_root(&mut info, key);
+        }
+        let mut manifest = session::SessionManifest::new(&session_id, Utc::now());
+        manifest.integrity = Some(info);
+        session::write_manifest(dir, &manifest, None).unwrap();
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let output = tempfile::tempdir().unwrap();
+        let dir = temp_session(&output, "recording_intact");
+        write_session(&dir, 3, None);
+
+        let report = verify_session(&dir, None, None).unwrap();
+        assert!(report.valid);
+        assert_eq!(report.entries_checked, 4);
+    }
+
+    #[test]
+    fn test_missing_integrity_block_fails() {
+        let output = tempfile::tempdir().unwrap();
+        let dir = temp_session(&output, "recording_stripped");
+        write_session(&dir, 2, None);
+        let manifest = session::SessionManifest::new("recording_stripped", Utc::now());
+        session::write_manifest(&dir, &manifest, None).unwrap();
+
+        let report = verify_session(&dir, None, None).unwrap();
+        assert!(!report.valid);
+        assert!(report.manifest_error.is_some());
+    }
+
+    #[test]
+    fn test_signature_requires_trusted_key() {
+        let output = tempfile::tempdir().unwrap();
+        let dir = temp_session(&output, "recording_signed");
+        let key = SigningKey::from_bytes(&[1; 32]);
+        write_session(&dir, 2, Some(&key));
+
+        let trusted = key.verifying_key();
+        let report = verify_session(&dir, None, Some(&trusted)).unwrap();
+        assert_eq!(report.signature, Some(SignatureStatus::Valid));
+        assert!(report.valid);
+
+        // 다른 키로 다시 서명한 세션
+        let other = SigningKey::from_bytes(&[2; 32]).verifying_key();
+        let report = verify_session(&dir, None, Some(&other)).unwrap();
+        assert_eq!(report.signature, Some(SignatureStatus::UntrustedKey));
+        assert!(!report.valid);
+    }
+
+    #[test]
+    fn test_edited_trade_and_inserted_frame_fail() {
+        let output = tempfile::tempdir().unwrap();
+        let dir = temp_session(&output, "recording_trade_edit");
+        write_session(&dir, 1, None);
+        std::fs::write(dir.join("frame_000009.png"), "inserted").unwrap();
+
+        let report = verify_session(&dir, None, None).unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.unreferenced_files, vec!["frame_000009.png".to_string()]);
+        std::fs::remove_file(dir.join("frame_000009.png")).unwrap();
+
+        let track = dir.join(trade_store::TRADES_TRACK);
+        let edited = std::fs::read_to_string(&track).unwrap().replace("\"buy\"", "\"sell\"");
+        std::fs::write(&track, edited).unwrap();
+
+        let report = verify_session(&dir, None, None).unwrap();
+        assert!(!report.valid);
+        assert_eq!(report.first_broken.unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_modified_frame_reports_first_broken_link() {
+        let output = tempfile::tempdir().unwrap();
+        let dir = temp_session(&output, "recording_tampered");
+        write_session(&dir, 3, None);
+        std::fs::write(dir.join("frame_000002.png"), "edited").unwrap();
+
+        let report = verify_session(&dir, None, None).unwrap();
+        assert!(!report.valid);
+        let broken = report.first_broken.unwrap();
+        assert_eq!(broken.index, 1);
+        assert_eq!(broken.reference.as_deref(), Some("frame_000002.png"));
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+
+use crate::crypto::Cipher;
+use crate::models::{TradeAttachment, TradeEvent, TradeNote};
+use crate::storage;
+
+/// 저널 스크린샷을 복사해 두는 출력 디렉토리 하위 폴더
+pub const JOURNAL_DIR: &str = "journal";
+
+/// 태그 최대 길이 (글자 수)
+const MAX_TAG_LEN: usize = 40;
+
+/// 거래 저널 수정 하나
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalEdit {
+    AddNote { text: String },
+    EditNote { note_id: String, text: String },
+    DeleteNote { note_id: String },
+    AddTag { tag: String },
+    RemoveTag { tag: String },
+    /// 태그 전체 교체
+    SetTags { tags: Vec<String> },
+    /// 1~5, `None`이면 평가 지우기
+    SetRating { rating: Option<u8> },
+    /// `path`는 이미 출력 디렉토리로 복사한 경로 ([`import_screenshot`])
+    AddScreenshot {
+        path: String,
+        #[serde(default)]
+        caption: Option<String>,
+    },
+    RemoveScreenshot { path: String },
+}
+
+/// 태그 정리 (앞뒤 공백 제거, 빈 태그/너무 긴 태그 거부)
+fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is empty".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(format!("Tag is longer than {} characters: {}", MAX_TAG_LEN, tag));
+    }
+    Ok(tag.to_string())
+}
+
+fn note_text(text: &str) -> Result<String, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Note is empty".to_string());
+    }
+    Ok(text.to_string())
+}
+
+/// 거래 이벤트에 저널 수정 적용
+///
+/// 실패하면 이벤트는 바뀌지 않는다. 수정 횟수/시각은 저장할 때 [`TradeStore::update`]가 올린다.
+///
+/// [`TradeStore::update`]: crate::trade_store::TradeStore::update
+pub fn apply(event: &mut TradeEvent, edit: &JournalEdit, now: DateTime<Utc>) -> Result<(), String> {
+    match edit {
+        JournalEdit::AddNote { text } => {
+            event.notes.push(TradeNote {
+                id: uuid::Uuid::new_v4().simple().to_string()[..16].to_string(),
+                text: note_text(text)?,
+                created_at: now,
+                updated_at: None,
+            });
+        }
+        JournalEdit::EditNote { note_id, text } => {
+            let text = note_text(text)?;
+            let note = event
+                .notes
+                .iter_mut()
+                .find(|n| &n.id == note_id)
+                .ok_or_else(|| format!("Note not found: {}", note_id))?;
+            note.text = text;
+            note.updated_at = Some(now);
+        }
+        JournalEdit::DeleteNote { note_id } => {
+            let before = event.notes.len();
+            event.notes.retain(|n| &n.id != note_id);
+            if event.notes.len() == before {
+                return Err(format!("Note not found: {}", note_id));
+            }
+        }
+        JournalEdit::AddTag { tag } => {
+            let tag = normalize_tag(tag)?;
+            if !event.tags.contains(&tag) {
+                event.tags.push(tag);
+            }
+        }
+        JournalEdit::RemoveTag { tag } => {
+            event.tags.retain(|t| t != tag.trim());
+        }
+        JournalEdit::SetTags { tags } => {
+            let mut normalized: Vec<String> = Vec::new();
+            for tag in tags {
+                let tag = normalize_tag(tag)?;
+                if !normalized.contains(&tag) {
+                    normalized.push(tag);
+                }
+            }
+            event.tags = normalized;
+        }
+        JournalEdit::SetRating { rating } => {
+            if let Some(r) = rating {
+                if !(1..=5).contains(r) {
+                    return Err(format!("Rating must be between 1 and 5: {}", r));
+                }
+            }
+            event.rating = *rating;
+        }
+        JournalEdit::AddScreenshot { path, caption } => {
+            if event.attachments.iter().any(|a| &a.path == path) {
+                return Err(format!("Screenshot already attached: {}", path));
+            }
+            event.attachments.push(TradeAttachment {
+                path: path.clone(),
+                caption: caption.as_ref().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+                added_at: now,
+            });
+        }
+        JournalEdit::RemoveScreenshot { path } => {
+            let before = event.attachments.len();
+            event.attachments.retain(|a| &a.path != path);
+            if event.attachments.len() == before {
+                return Err(format!("Screenshot not attached: {}", path));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 사용자가 고른 스크린샷을 `<output_dir>/journal/<trade_id>/`로 복사
+///
+/// 원본을 옮기거나 지워도 저널이 깨지지 않게 복사본 경로를 돌려준다.
+/// 암호화 키가 있으면 녹화 파일처럼 암호화해서 저장하고, 돌려주는 경로는
+/// `.enc`를 뺀 경로다 ([`storage::read_file`]로 읽는다).
+/// 예전 수정 이력이 참조할 수 있으므로 첨부를 빼도 복사본은 지우지 않는다.
+pub fn import_screenshot(
+    output_dir: &str,
+    trade_id: &str,
+    source: &Path,
+    cipher: Option<&Cipher>,
+) -> Result<String, String> {
+    // 거래 ID가 하위 폴더 하나가 아니면 출력 디렉토리 밖에 쓸 수 있음
+    let mut components = Path::new(trade_id).components();
+    if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+        return Err(format!("Invalid trade id: {}", trade_id));
+    }
+    if !source.is_file() {
+        return Err(format!("Screenshot not found: {}", source.display()));
+    }
+    let file_name = source
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("Invalid screenshot path: {}", source.display()))?;
+    let data = std::fs::read(source).map_err(|e| format!("Failed to read screenshot: {}", e))?;
+
+    let dir = Path::new(output_dir).join(JOURNAL_DIR).join(trade_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create journal directory: {}", e))?;
+
+    let target = dir.join(format!("{}_{}", Utc::now().format("%Y%m%d_%H%M%S%3f"), file_name));
+    storage::write_file(&target, &data, cipher)?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// 메모, 태그, 종목, 스크린샷 설명에 검색어가 들어 있는지 (대소문자 무시)
+pub fn matches_text(event: &TradeEvent, needle: &str) -> bool {
+    let needle = needle.trim().to_lowercase();
+    if needle.is_empty() {
+        return true;
+    }
+    let contains = |s: &str| s.to_lowercase().contains(&needle);
+
+    event.notes.iter().any(|n| contains(&n.text))
+        || event.tags.iter().any(|t| contains(t))
+        || event.ticker.as_deref().is_some_and(contains)
+        || event.instrument_name.as_deref().is_some_and(contains)
+        || event.attachments.iter().filter_map(|a| a.caption.as_deref()).any(contains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TradeAction, TradeSource};
+
+    fn event() -> TradeEvent {
+        TradeEvent::new(TradeAction::Buy, Utc::now(), TradeSource::Manual)
+    }
+
+    #[test]
+    fn test_note_lifecycle() {
+        let mut event = event();
+        let now = Utc::now();
+        apply(&mut event, &JournalEdit::AddNote { text: " 눌림목 진입 ".to_string() }, now).unwrap();
+        let id = event.notes[0].id.clone();
+        assert_eq!(event.notes[0].text, "눌림목 진입");
+
+        let edit = JournalEdit::EditNote { note_id: id.clone(), text: "추격 매수".to_string() };
+        apply(&mut event, &edit, now).unwrap();
+        assert_eq!(event.notes[0].text, "추격 매수");
+        assert_eq!(event.notes[0].updated_at, Some(now));
+
+        apply(&mut event, &JournalEdit::DeleteNote { note_id: id.clone() }, now).unwrap();
+        assert!(event.notes.is_empty());
+        assert!(apply(&mut event, &JournalEdit::DeleteNote { note_id: id }, now).is_err());
+    }
+
+    #[test]
+    fn test_tags_and_rating_validated() {
+        let mut event = event();
+        let now = Utc::now();
+        let tags = vec!["FOMO".to_string(), " FOMO ".to_string(), "plan followed".to_string()];
+        apply(&mut event, &JournalEdit::SetTags { tags }, now).unwrap();
+        assert_eq!(event.tags, vec!["FOMO".to_string(), "plan followed".to_string()]);
+        assert!(apply(&mut event, &JournalEdit::AddTag { tag: "  ".into() }, now).is_err());
+
+        assert!(apply(&mut event, &JournalEdit::SetRating { rating: Some(6) }, now).is_err());
+        assert_eq!(event.rating, None);
+        apply(&mut event, &JournalEdit::SetRating { rating: Some(4) }, now).unwrap();
+        assert_eq!(event.rating, Some(4));
+    }
+
+    #[test]
+    fn test_matches_text() {
+        let mut event = event();
+        event.instrument_name = Some("삼성전자".to_string());
+        apply(&mut event, &JournalEdit::AddNote { text: "Stop loss too tight".to_string() }, Utc::now()).unwrap();
+
+        assert!(matches_text(&event, "stop LOSS"));
+        assert!(matches_text(&event, "삼성"));
+        assert!(!matches_text(&event, "FOMO"));
+    }
+
+    #[test]
+    fn test_import_screenshot() {
+        let temp = tempfile::tempdir().unwrap();
+        let output_dir = temp.path().join("recordings");
+        let output_dir = output_dir.to_str().unwrap();
+        let source = temp.path().join("chart.png");
+        std::fs::write(&source, b"png").unwrap();
+
+        for trade_id in ["../escape", "a/b", "/abs", ".", ""] {
+            assert!(import_screenshot(output_dir, trade_id, &source, None).is_err(), "{}", trade_id);
+        }
+        assert!(!temp.path().join("escape").exists());
+
+        let cipher = Cipher::from_key(&[5; 32]);
+        let copied = import_screenshot(output_dir, "trade-1", &source, Some(&cipher)).unwrap();
+        let copied = Path::new(&copied);
+        assert!(copied.starts_with(Path::new(output_dir).join(JOURNAL_DIR).join("trade-1")));
+        assert!(!copied.exists());
+        assert_eq!(storage::read_file(copied, Some(&cipher)).unwrap(), b"png");
+    }
+}
@@ -0,0 +1,37 @@
+//! HTS Trading Recorder 코어
+//!
+//! 화면 녹화, HTS 프로세스 감지, 윈도우 캡처, 세션 관리 로직을 담고 있으며
+//! Tauri 앱(`test-recorder`)과 헤드리스 CLI(`test-recorder-cli`)가 함께 사용한다.
+
+pub mod activity;
+pub mod api;
+pub mod clips;
+pub mod clock;
+pub mod controller;
+pub mod crypto;
+pub mod export;
+pub mod input;
+pub mod integrity;
+pub mod journal;
+pub mod matcher;
+pub mod models;
+pub mod ocr;
+pub mod order_detector;
+pub mod pipeline;
+pub mod positions;
+pub mod process_monitor;
+pub mod profiles;
+pub mod recorder;
+pub mod redaction;
+pub mod report;
+pub mod regions;
+pub mod schedule;
+pub mod session;
+pub mod state_machine;
+pub mod storage;
+pub mod thumbnails;
+pub mod tracks;
+pub mod trade_log;
+pub mod trade_store;
+pub mod webhooks;
+pub mod window_capture;
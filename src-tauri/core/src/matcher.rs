@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use regex::{Regex, RegexBuilder};
+
+/// 패턴 비교 방식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// 부분 문자열 (예전 설정의 기본 동작)
+    #[default]
+    Contains,
+    Exact,
+    /// `*`, `?`, `[abc]` 와일드카드
+    Glob,
+    Regex,
+}
+
+/// 프로세스의 어느 값과 비교할지 (윈도우 패턴은 항상 제목과 비교)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchTarget {
+    /// 프로세스 이름 (`kiwoom.exe`)
+    #[default]
+    Name,
+    /// 실행 파일 전체 경로
+    ExePath,
+    /// 인자를 공백으로 이은 명령줄
+    Cmdline,
+}
+
+/// 프로세스/윈도우 이름 패턴
+///
+/// 설정 파일에서는 문자열 하나(`"키움"`, 부분 일치)로 쓰거나
+/// `{ "pattern": "영웅문4*", "mode": "glob" }`처럼 객체로 쓴다.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PatternRepr")]
+pub struct NamePattern {
+    pub pattern: String,
+    pub mode: MatchMode,
+    pub target: MatchTarget,
+    pub case_sensitive: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PatternRepr {
+    Plain(String),
+    Full {
+        pattern: String,
+        #[serde(default)]
+        mode: MatchMode,
+        #[serde(default)]
+        target: MatchTarget,
+        #[serde(default)]
+        case_sensitive: bool,
+    },
+}
+
+impl From<PatternRepr> for NamePattern {
+    fn from(repr: PatternRepr) -> Self {
+        match repr {
+            PatternRepr::Plain(pattern) => Self::contains(&pattern),
+            PatternRepr::Full {
+                pattern,
+                mode,
+                target,
+                case_sensitive,
+            } => Self {
+                pattern,
+                mode,
+                target,
+                case_sensitive,
+            },
+        }
+    }
+}
+
+impl NamePattern {
+    fn with_mode(pattern: &str, mode: MatchMode) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            mode,
+            target: MatchTarget::Name,
+            case_sensitive: false,
+        }
+    }
+
+    pub fn contains(pattern: &str) -> Self {
+        Self::with_mode(pattern, MatchMode::Contains)
+    }
+
+    pub fn exact(pattern: &str) -> Self {
+        Self::with_mode(pattern, MatchMode::Exact)
+    }
+
+    pub fn glob(pattern: &str) -> Self {
+        Self::with_mode(pattern, MatchMode::Glob)
+    }
+
+    pub fn regex(pattern: &str) -> Self {
+        Self::with_mode(pattern, MatchMode::Regex)
+    }
+
+    pub fn on(mut self, target: MatchTarget) -> Self {
+        self.target = target;
+        self
+    }
+}
+
+/// glob 패턴을 정규식으로 변환 (`*` → `.*`, `?` → `.`, `[...]`는 그대로)
+fn glob_to_regex(glob: &str) -> String {
+    let mut out = String::from("^");
+    let mut chars = glob.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => out.push_str(".*"),
+            '?' => out.push('.'),
+            '[' => {
+                out.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    out.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' {
+                        out.push('\\');
+                    }
+                    out.push(c);
+                }
+                out.push(']');
+            }
+            c => out.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    out.push('$');
+    out
+}
+
+/// 컴파일된 패턴
+#[derive(Debug, Clone)]
+struct CompiledPattern {
+    target: MatchTarget,
+    regex: Regex,
+}
+
+impl CompiledPattern {
+    fn new(pattern: &NamePattern) -> Result<Self, String> {
+        let source = match pattern.mode {
+            MatchMode::Contains => regex::escape(&pattern.pattern),
+            MatchMode::Exact => format!("^{}$", regex::escape(&pattern.pattern)),
+            MatchMode::Glob => glob_to_regex(&pattern.pattern),
+            MatchMode::Regex => pattern.pattern.clone(),
+        };
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!pattern.case_sensitive)
+            .build()
+            .map_err(|e| format!("Invalid pattern '{}': {}", pattern.pattern, e))?;
+
+        Ok(Self {
+            target: pattern.target,
+            regex,
+        })
+    }
+}
+
+/// 패턴을 비교할 프로세스 정보
+#[derive(Debug, Clone, Default)]
+pub struct ProcessInfo {
+    pub name: String,
+    pub exe_path: Option<String>,
+    pub cmdline: String,
+}
+
+impl ProcessInfo {
+    fn field(&self, target: MatchTarget) -> Option<&str> {
+        match target {
+            MatchTarget::Name => Some(&self.name),
+            MatchTarget::ExePath => self.exe_path.as_deref(),
+            MatchTarget::Cmdline => (!self.cmdline.is_empty()).then_some(self.cmdline.as_str()),
+        }
+    }
+}
+
+/// 포함 패턴 중 하나와 맞고 제외 패턴과는 하나도 안 맞으면 일치
+#[derive(Debug, Clone, Default)]
+struct PatternRule {
+    include: Vec<CompiledPattern>,
+    exclude: Vec<CompiledPattern>,
+}
+
+impl PatternRule {
+    fn matches(&self, hit: impl Fn(&CompiledPattern) -> bool) -> bool {
+        self.include.iter().any(&hit) && !self.exclude.iter().any(&hit)
+    }
+}
+
+/// 포함/제외 패턴 묶음
+///
+/// [`PatternMatcher::extend`]로 합친 매처는 묶음 중 하나라도 일치하면 일치한다
+/// (한 프로필의 제외 패턴이 다른 프로필의 윈도우를 막지 않음).
+#[derive(Debug, Clone, Default)]
+pub struct PatternMatcher {
+    rules: Vec<PatternRule>,
+}
+
+impl PatternMatcher {
+    pub fn new(include: &[NamePattern], exclude: &[NamePattern]) -> Result<Self, String> {
+        let compile = |patterns: &[NamePattern]| {
+            patterns.iter().map(CompiledPattern::new).collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            rules: vec![PatternRule {
+                include: compile(include)?,
+                exclude: compile(exclude)?,
+            }],
+        })
+    }
+
+    /// 다른 매처를 합침 (여러 프로필의 윈도우 패턴을 한 번에 찾을 때)
+    pub fn extend(&mut self, other: PatternMatcher) {
+        self.rules.extend(other.rules);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.iter().all(|rule| rule.include.is_empty())
+    }
+
+    pub fn matches_process(&self, process: &ProcessInfo) -> bool {
+        let hit = |p: &CompiledPattern| process.field(p.target).is_some_and(|v| p.regex.is_match(v));
+        self.rules.iter().any(|rule| rule.matches(hit))
+    }
+
+    /// 윈도우 제목 등 문자열 하나와 비교 (대상 구분 없음)
+    pub fn matches_text(&self, text: &str) -> bool {
+        let hit = |p: &CompiledPattern| p.regex.is_match(text);
+        self.rules.iter().any(|rule| rule.matches(hit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(name: &str, exe: &str, cmdline: &str) -> ProcessInfo {
+        ProcessInfo {
+            name: name.to_string(),
+            exe_path: Some(exe.to_string()),
+            cmdline: cmdline.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_exact_does_not_match_helpers() {
+        let matcher = PatternMatcher::new(&[NamePattern::exact("kiwoom.exe")], &[]).unwrap();
+
+        assert!(matcher.matches_process(&process("KIWOOM.EXE", "", "")));
+        assert!(!matcher.matches_process(&process("kiwoom.exe.helper", "", "")));
+    }
+
+    #[test]
+    fn test_glob_with_exclude() {
+        let matcher = PatternMatcher::new(
+            &[NamePattern::glob("영웅문*")],
+            &[NamePattern::regex("updater|업데이트")],
+        )
+        .unwrap();
+
+        assert!(matcher.matches_text("영웅문4 [키움증권]"));
+        assert!(!matcher.matches_text("영웅문S# updater"));
+        assert!(!matcher.matches_text("키움 영웅문"));
+    }
+
+    #[test]
+    fn test_exe_path_and_cmdline_targets() {
+        let matcher = PatternMatcher::new(
+            &[NamePattern::glob(r"C:\KiwoomHero4\*").on(MatchTarget::ExePath)],
+            &[NamePattern::contains("/update").on(MatchTarget::Cmdline)],
+        )
+        .unwrap();
+
+        assert!(matcher.matches_process(&process("nkrunlite.exe", r"C:\KiwoomHero4\bin\nkrunlite.exe", "")));
+        assert!(!matcher.matches_process(&process("nkrunlite.exe", r"C:\KiwoomHero4\bin\nkrunlite.exe", "nkrunlite.exe /update")));
+        assert!(!matcher.matches_process(&process("nkrunlite.exe", r"D:\Other\nkrunlite.exe", "")));
+    }
+
+    #[test]
+    fn test_plain_string_deserializes_as_contains() {
+        let patterns: Vec<NamePattern> =
+            serde_json::from_str(r#"["키움", {"pattern": "^hable\\.exe$", "mode": "regex"}]"#).unwrap();
+
+        assert_eq!(patterns[0], NamePattern::contains("키움"));
+        assert_eq!(patterns[1].mode, MatchMode::Regex);
+    }
+
+    #[test]
+    fn test_invalid_regex_rejected() {
+        assert!(PatternMatcher::new(&[NamePattern::regex("(")], &[]).is_err());
+    }
+
+    #[test]
+    fn test_extended_excludes_stay_per_profile() {
+        let mut matcher = PatternMatcher::new(&[NamePattern::contains("영웅문")], &[NamePattern::contains("주문")]).unwrap();
+        matcher.extend(PatternMatcher::new(&[NamePattern::contains("eFriend")], &[]).unwrap());
+
+        assert!(matcher.matches_text("eFriend Plus 주문"));
+        assert!(!matcher.matches_text("영웅문4 주문"));
+        assert!(matcher.matches_text("영웅문4"));
+    }
+}
@@ -0,0 +1,523 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+use crate::api::ApiConfig;
+use crate::crypto::EncryptionConfig;
+use crate::input::InputTrackingConfig;
+use crate::integrity::IntegrityConfig;
+use crate::matcher::{NamePattern, PatternMatcher};
+use crate::order_detector::OrderDetectionConfig;
+use crate::pipeline::PipelineSnapshot;
+use crate::positions::FeeModel;
+use crate::profiles::{self, BrokerProfile, DetectedProfile, OrderDialogSignature};
+use crate::redaction::RedactionConfig;
+use crate::report::ReportConfig;
+use crate::regions::CaptureRegion;
+use crate::schedule::ScheduleConfig;
+use crate::session::SessionRef;
+use crate::state_machine::RecorderState;
+use crate::thumbnails::ThumbnailConfig;
+use crate::trade_log::TradeLogSource;
+use crate::webhooks::WebhookConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingStatus {
+    pub is_recording: bool,
+    pub hts_detected: bool,
+    pub hts_name: Option<String>,
+    pub recording_duration: Option<u64>, // 초 단위
+    /// 캡처 파이프라인 처리량/버린 프레임 (녹화 중일 때만)
+    #[serde(default)]
+    pub pipeline: Option<PipelineSnapshot>,
+    /// 녹화 상태 머신의 현재 상태
+    #[serde(default)]
+    pub state: Option<RecorderState>,
+    /// 감지된 HTS 프로필
+    #[serde(default)]
+    pub profile: Option<DetectedProfile>,
+}
+
+/// 화면/프레임 좌표계의 사각형 (픽셀)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 거래 이벤트 저장 형식 버전 (1: 주문창 감지만 있던 예전 형식)
+pub const TRADE_EVENT_VERSION: u32 = 2;
+
+/// 거래 하나 (주문창 감지, 로그 가져오기, 직접 입력)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "TradeEventRepr")]
+pub struct TradeEvent {
+    pub version: u32,
+    pub id: String,
+    pub action: TradeAction,
+    pub timestamp: DateTime<Utc>,
+    pub source: TradeSource,
+    /// 종목 코드 (예: "005930")
+    pub ticker: Option<String>,
+    /// 종목명 (예: "삼성전자")
+    pub instrument_name: Option<String>,
+    pub market: Option<Market>,
+    pub quantity: Option<u64>,
+    pub price: Option<f64>,
+    pub order_type: Option<OrderType>,
+    /// 증권사 주문번호
+    pub order_id: Option<String>,
+    pub fill_status: Option<FillStatus>,
+    /// 주문창 감지 확신도 (0.0~1.0)
+    pub confidence: Option<f32>,
+    /// 주문창 스크린샷
+    pub screenshot_path: Option<String>,
+    pub window_title: Option<String>,
+    /// 거래 시각의 녹화 세션 위치
+    pub session: Option<SessionRef>,
+    pub notes: Vec<TradeNote>,
+    pub tags: Vec<String>,
+    /// 실행 평가 (1~5)
+    pub rating: Option<u8>,
+    /// 복기하면서 덧붙인 스크린샷
+    pub attachments: Vec<TradeAttachment>,
+    /// 저널 수정 횟수 (처음 기록은 0)
+    pub revision: u32,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl TradeEvent {
+    pub fn new(action: TradeAction, timestamp: DateTime<Utc>, source: TradeSource) -> Self {
+        Self {
+            version: TRADE_EVENT_VERSION,
+            id: uuid::Uuid::new_v4().to_string(),
+            action,
+            timestamp,
+            source,
+            ticker: None,
+            instrument_name: None,
+            market: None,
+            quantity: None,
+            price: None,
+            order_type: None,
+            order_id: None,
+            fill_status: None,
+            confidence: None,
+            screenshot_path: None,
+            window_title: None,
+            session: None,
+            notes: Vec::new(),
+            tags: Vec::new(),
+            rating: None,
+            attachments: Vec::new(),
+            revision: 0,
+            edited_at: None,
+        }
+    }
+}
+
+/// 저장된 거래 이벤트 (예전 형식 포함)
+#[derive(Deserialize)]
+struct TradeEventRepr {
+    #[serde(default)]
+    version: Option<u32>,
+    #[serde(default)]
+    id: Option<String>,
+    action: TradeAction,
+    timestamp: DateTime<Utc>,
+    #[serde(default)]
+    source: Option<TradeSource>,
+    #[serde(default)]
+    ticker: Option<String>,
+    #[serde(default)]
+    instrument_name: Option<String>,
+    #[serde(default)]
+    market: Option<Market>,
+    #[serde(default)]
+    quantity: Option<u64>,
+    #[serde(default)]
+    price: Option<f64>,
+    #[serde(default)]
+    order_type: Option<OrderType>,
+    #[serde(default)]
+    order_id: Option<String>,
+    #[serde(default)]
+    fill_status: Option<FillStatus>,
+    #[serde(default)]
+    confidence: Option<f32>,
+    #[serde(default)]
+    screenshot_path: Option<String>,
+    #[serde(default)]
+    window_title: Option<String>,
+    #[serde(default)]
+    session: Option<SessionRef>,
+    #[serde(default)]
+    notes: Vec<TradeNote>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    rating: Option<u8>,
+    #[serde(default)]
+    attachments: Vec<TradeAttachment>,
+    #[serde(default)]
+    revision: u32,
+    #[serde(default)]
+    edited_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<TradeEventRepr> for TradeEvent {
+    type Error = String;
+
+    fn try_from(repr: TradeEventRepr) -> Result<Self, Self::Error> {
+        let version = repr.version.unwrap_or(1);
+        if version > TRADE_EVENT_VERSION {
+            return Err(format!("Unsupported trade event version: {}", version));
+        }
+
+        // 예전 형식은 빈 문자열로 "없음"을 표시했다
+        let non_empty = |v: Option<String>| v.filter(|s| !s.is_empty());
+        let screenshot_path = non_empty(repr.screenshot_path);
+        let window_title = non_empty(repr.window_title);
+
+        // ID가 없던 이벤트는 내용으로 고정 ID를 만들어서 다시 읽어도 같은 ID가 되게 함
+        let id = repr.id.unwrap_or_else(|| {
+            let key = format!(
+                "{}|{:?}|{}|{}",
+                repr.timestamp.to_rfc3339(),
+                repr.action,
+                screenshot_path.as_deref().unwrap_or(""),
+                repr.order_id.as_deref().unwrap_or("")
+            );
+            crate::integrity::sha256_hex(key.as_bytes())[..32].to_string()
+        });
+        // ID 없이 저장된 메모도 수정/삭제할 수 있게 내용으로 고정 ID 부여
+        let mut notes = repr.notes;
+        for note in notes.iter_mut().filter(|n| n.id.is_empty()) {
+            let key = format!("{}|{}|{}", id, note.created_at.to_rfc3339(), note.text);
+            note.id = crate::integrity::sha256_hex(key.as_bytes())[..16].to_string();
+        }
+
+        let source = repr.source.unwrap_or(if repr.ticker.is_some() {
+            TradeSource::LogImport
+        } else {
+            TradeSource::Dialog
+        });
+
+        Ok(Self {
+            version: TRADE_EVENT_VERSION,
+            id,
+            action: repr.action,
+            timestamp: repr.timestamp,
+            source,
+            ticker: repr.ticker,
+            instrument_name: repr.instrument_name,
+            market: repr.market,
+            quantity: repr.quantity,
+            price: repr.price,
+            order_type: repr.order_type,
+            order_id: repr.order_id,
+            fill_status: repr.fill_status,
+            confidence: repr.confidence,
+            screenshot_path,
+            window_title,
+            session: repr.session,
+            notes,
+            tags: repr.tags,
+            rating: repr.rating,
+            attachments: repr.attachments,
+            revision: repr.revision,
+            edited_at: repr.edited_at,
+        })
+    }
+}
+
+/// 거래를 어떻게 기록했는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeSource {
+    /// 사용자가 직접 입력
+    Manual,
+    /// 주문창을 OCR로 읽음
+    Ocr,
+    /// 주문창 제목/기준 이미지로 감지
+    Dialog,
+    /// 주문/체결 로그, 체결내역 CSV
+    LogImport,
+}
+
+/// 시장 구분
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Market {
+    Kospi,
+    Kosdaq,
+    Konex,
+    /// 선물/옵션
+    Derivatives,
+    Overseas,
+    Other,
+}
+
+/// 호가 구분
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderType {
+    /// 지정가
+    Limit,
+    /// 시장가
+    Market,
+    /// 조건부지정가
+    ConditionalLimit,
+    /// 최유리지정가
+    BestLimit,
+    /// 최우선지정가
+    PriorityLimit,
+    Other,
+}
+
+/// 체결 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillStatus {
+    /// 주문 접수 (미체결)
+    Submitted,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+/// 거래에 남긴 메모
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeNote {
+    #[serde(default)]
+    pub id: String,
+    pub text: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 거래에 덧붙인 스크린샷
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeAttachment {
+    pub path: String,
+    #[serde(default)]
+    pub caption: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeAction {
+    Buy,
+    Sell,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HTSConfig {
+    /// 증권사별 HTS 프로필
+    #[serde(default = "profiles::builtin_profiles")]
+    pub profiles: Vec<BrokerProfile>,
+    pub check_interval_ms: u64,
+    /// 예전 설정 파일 호환용 (읽을 때만, `migrate_legacy`로 프로필로 옮김)
+    #[serde(default, skip_serializing)]
+    pub process_names: Vec<String>,
+    #[serde(default, skip_serializing)]
+    pub window_titles: Vec<String>,
+}
+
+impl Default for HTSConfig {
+    fn default() -> Self {
+        Self {
+            profiles: profiles::builtin_profiles(),
+            check_interval_ms: 1000,
+            process_names: Vec::new(),
+            window_titles: Vec::new(),
+        }
+    }
+}
+
+impl HTSConfig {
+    /// 예전 `process_names`/`window_titles` 목록을 "legacy" 프로필로 변환
+    pub fn migrate_legacy(&mut self) {
+        if self.process_names.is_empty() && self.window_titles.is_empty() {
+            return;
+        }
+
+        let legacy = BrokerProfile {
+            id: "legacy".to_string(),
+            name: "기존 설정".to_string(),
+            process_names: self.process_names.drain(..).map(|p| NamePattern::contains(&p)).collect(),
+            process_excludes: Vec::new(),
+            window_titles: self.window_titles.drain(..).map(|t| NamePattern::contains(&t)).collect(),
+            window_excludes: Vec::new(),
+            order_dialogs: vec![OrderDialogSignature::default()],
+            redaction_regions: Vec::new(),
+            ocr_layout: Vec::new(),
+        };
+        profiles::merge_profiles(&mut self.profiles, vec![legacy]);
+    }
+
+    pub fn find_profile(&self, id: &str) -> Option<&BrokerProfile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// 모든 프로필의 윈도우 패턴 (프로필을 모를 때 사용)
+    ///
+    /// 패턴이 잘못된 프로필은 건너뛴다 (설정 저장 시 이미 검사됨).
+    pub fn window_matcher(&self) -> PatternMatcher {
+        let mut matcher = PatternMatcher::default();
+        for profile in &self.profiles {
+            match profile.window_matcher() {
+                Ok(m) => matcher.extend(m),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        matcher
+    }
+
+    /// 감지된 프로필의 윈도우 패턴 (없으면 전체)
+    pub fn window_matcher_for(&self, detected: Option<&DetectedProfile>) -> PatternMatcher {
+        detected
+            .and_then(|d| self.find_profile(&d.profile_id))
+            .and_then(|p| p.window_matcher().ok())
+            .unwrap_or_else(|| self.window_matcher())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub hts: HTSConfig,
+    pub output_dir: String,
+    pub fps: u32,
+    pub enable_ocr: bool,
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub integrity: IntegrityConfig,
+    /// 장 시간 기반 자동 녹화
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    /// HTS 윈도우 안에서 따로 캡처할 영역 (호가창, 체결창, 차트 등)
+    #[serde(default)]
+    pub regions: Vec<CaptureRegion>,
+    /// 주문확인/체결 창 감지
+    #[serde(default)]
+    pub order_detection: OrderDetectionConfig,
+    /// HTS 주문/체결 로그와 체결내역 CSV
+    #[serde(default)]
+    pub trade_logs: Vec<TradeLogSource>,
+    /// 실현 손익 계산용 수수료/거래세
+    #[serde(default)]
+    pub fees: FeeModel,
+    /// 장 마감 후 일일 리포트
+    #[serde(default)]
+    pub report: ReportConfig,
+    /// 녹화 후 타임라인 미리보기용 썸네일
+    #[serde(default)]
+    pub thumbnails: ThumbnailConfig,
+    /// 커서 위치/클릭 기록 (기본 꺼짐)
+    #[serde(default)]
+    pub input_tracking: InputTrackingConfig,
+    /// 로컬 REST/WebSocket 제어 API (기본 꺼짐)
+    #[serde(default)]
+    pub api: ApiConfig,
+    /// 녹화/거래 이벤트 웹훅 알림
+    #[serde(default)]
+    pub webhooks: WebhookConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            hts: HTSConfig::default(),
+            output_dir: "./recordings".to_string(),
+            fps: 30,
+            enable_ocr: false,
+            redaction: RedactionConfig::default(),
+            encryption: EncryptionConfig::default(),
+            integrity: IntegrityConfig::default(),
+            schedule: ScheduleConfig::default(),
+            regions: Vec::new(),
+            order_detection: OrderDetectionConfig::default(),
+            trade_logs: Vec::new(),
+            fees: FeeModel::default(),
+            report: ReportConfig::default(),
+            thumbnails: ThumbnailConfig::default(),
+            input_tracking: InputTrackingConfig::default(),
+            api: ApiConfig::default(),
+            webhooks: WebhookConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// JSON 설정 파일에서 읽기
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config: {}", e))?;
+        let mut config: Self = serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse config: {}", e))?;
+        config.hts.migrate_legacy();
+        Ok(config)
+    }
+
+    /// JSON 설정 파일로 저장
+    pub fn save_to_file(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write config: {}", e))
+    }
+
+    /// 공통 가림 설정에 감지된 프로필의 가림 영역을 더한 설정
+    pub fn redaction_for(&self, detected: Option<&DetectedProfile>) -> RedactionConfig {
+        let mut redaction = self.redaction.clone();
+        if let Some(profile) = detected.and_then(|d| self.hts.find_profile(&d.profile_id)) {
+            redaction.regions.extend(profile.redaction_regions.iter().cloned());
+        }
+        redaction
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_trade_event_deserializes() {
+        let json = r#"{"action":"buy","timestamp":"2026-03-09T00:01:02Z","screenshot_path":"recordings/orders/a.png","window_title":"매수 주문확인"}"#;
+
+        let event: TradeEvent = serde_json::from_str(json).unwrap();
+        let again: TradeEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.version, TRADE_EVENT_VERSION);
+        assert_eq!(event.source, TradeSource::Dialog);
+        assert_eq!(event.id, again.id);
+        assert_eq!(event.window_title.as_deref(), Some("매수 주문확인"));
+        assert!(event.notes.is_empty());
+    }
+
+    #[test]
+    fn test_trade_event_round_trip() {
+        let mut event = TradeEvent::new(TradeAction::Sell, Utc::now(), TradeSource::LogImport);
+        event.ticker = Some("005930".to_string());
+        event.order_type = Some(OrderType::Limit);
+        event.tags.push("손절".to_string());
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed: TradeEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.id, event.id);
+        assert_eq!(parsed.order_type, Some(OrderType::Limit));
+        assert_eq!(parsed.tags, vec!["손절".to_string()]);
+    }
+
+    #[test]
+    fn test_newer_trade_event_version_rejected() {
+        let json = r#"{"version":99,"action":"buy","timestamp":"2026-03-09T00:01:02Z"}"#;
+        assert!(serde_json::from_str::<TradeEvent>(json).is_err());
+    }
+}
@@ -0,0 +1,19 @@
+use xcap::image::RgbaImage;
+
+/// OCR로 인식한 단어와 프레임 내 위치 (픽셀)
+#[derive(Debug, Clone)]
+pub struct OcrWord {
+    pub text: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 플랫폼 OCR 엔진 연결 지점
+///
+/// 코어는 특정 OCR 엔진에 의존하지 않는다. 앱/CLI가 사용할 엔진
+/// (Windows.Media.Ocr, tesseract 등)을 이 트레이트로 감싸서 넘겨준다.
+pub trait OcrEngine: Send + Sync {
+    fn recognize(&self, image: &RgbaImage) -> Result<Vec<OcrWord>, String>;
+}
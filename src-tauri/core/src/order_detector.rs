@@ -0,0 +1,757 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use xcap::image::{imageops, GrayImage, RgbaImage};
+use xcap::Window;
+
+use crate::crypto::Cipher;
+use crate::matcher::{NamePattern, PatternMatcher};
+use crate::models::{Rect, TradeAction, TradeEvent, TradeSource};
+use crate::ocr::OcrEngine;
+use crate::profiles::{BrokerProfile, OrderDialogSignature};
+use crate::redaction::Redactor;
+use crate::storage;
+use crate::window_capture;
+
+/// 세션/출력 디렉토리 안의 주문창 스크린샷 디렉토리
+pub const ORDERS_DIR: &str = "orders";
+
+/// 제목과 매수/매도 문구가 모두 맞을 때 확신도
+const TITLE_KEYWORD_CONFIDENCE: f32 = 0.9;
+
+/// 제목은 맞고 OCR로 매수/매도를 구분했을 때 확신도
+const OCR_KEYWORD_CONFIDENCE: f32 = 0.8;
+
+/// 제목만 맞고 매수/매도를 모를 때 확신도
+const TITLE_ONLY_CONFIDENCE: f32 = 0.5;
+
+/// 주문창 감지 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderDetectionConfig {
+    pub enabled: bool,
+    /// 새 윈도우 확인 주기
+    pub poll_interval_ms: u64,
+    /// 기준 이미지 비교 주기 (기준 이미지가 있을 때만 화면을 캡처)
+    pub template_interval_ms: u64,
+    /// 기준 이미지 비교 전 축소 배율 (클수록 빠르지만 부정확)
+    pub template_scale: u32,
+    /// 기준 이미지의 기본 최소 유사도
+    pub template_threshold: f32,
+    /// 다른 방법으로 감지한 같은 주문 종류를 기준 이미지로 다시 감지하지 않는 시간
+    pub cooldown_ms: u64,
+}
+
+impl Default for OrderDetectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_ms: 300,
+            template_interval_ms: 1000,
+            template_scale: 4,
+            template_threshold: 0.85,
+            cooldown_ms: 3000,
+        }
+    }
+}
+
+/// 주문창을 어떻게 찾았는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionSource {
+    /// 새로 뜬 윈도우의 제목
+    WindowTitle,
+    /// 새로 뜬 윈도우를 OCR로 읽은 문구
+    Ocr,
+    /// 화면과 기준 이미지 비교
+    Template,
+}
+
+/// 감지된 주문창
+#[derive(Debug, Clone)]
+pub struct OrderDetection {
+    pub action: TradeAction,
+    pub confidence: f32,
+    pub source: DetectionSource,
+    pub window_title: String,
+    /// 화면 좌표
+    pub bounds: Rect,
+    /// 주문창 이미지
+    pub image: RgbaImage,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// 문구로 매수/매도 구분 (둘 다 있거나 둘 다 없으면 None)
+pub fn classify_text(text: &str, signature: &OrderDialogSignature) -> Option<TradeAction> {
+    let has = |keywords: &[String]| keywords.iter().any(|k| !k.is_empty() && text.contains(k.as_str()));
+
+    match (has(&signature.buy_keywords), has(&signature.sell_keywords)) {
+        (true, false) => Some(TradeAction::Buy),
+        (false, true) => Some(TradeAction::Sell),
+        _ => None,
+    }
+}
+
+/// 열려 있는 윈도우 (비교용으로 필요한 값만)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowInfo {
+    pub id: u32,
+    pub title: String,
+    pub bounds: Rect,
+}
+
+/// 직전 확인 이후 새로 뜬 윈도우 찾기
+#[derive(Debug, Default)]
+pub struct WindowDiff {
+    known: HashSet<u32>,
+    primed: bool,
+}
+
+impl WindowDiff {
+    /// 새 윈도우만 돌려줌 (첫 호출은 이미 떠 있던 윈도우라 기준으로만 씀)
+    pub fn update(&mut self, windows: Vec<WindowInfo>) -> Vec<WindowInfo> {
+        let current: HashSet<u32> = windows.iter().map(|w| w.id).collect();
+        let appeared = if self.primed {
+            windows.into_iter().filter(|w| !self.known.contains(&w.id)).collect()
+        } else {
+            Vec::new()
+        };
+
+        self.known = current;
+        self.primed = true;
+        appeared
+    }
+}
+
+/// 기준 이미지 일치 위치 (비교한 이미지 좌표)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemplateMatch {
+    pub x: u32,
+    pub y: u32,
+    /// 정규화 상호상관 (-1.0~1.0)
+    pub score: f32,
+}
+
+/// 대략 찾기에 쓰는 추가 축소 배율
+const COARSE_FACTOR: u32 = 4;
+
+/// 대략 찾기를 하려면 기준 이미지가 축소 후에도 이 크기 이상이어야 함
+const MIN_COARSE_SIZE: u32 = 4;
+
+/// 대략 찾기에서 원래 크기로 다시 확인할 후보 수
+const COARSE_CANDIDATES: usize = 4;
+
+/// 정규화 상호상관으로 `template`과 가장 비슷한 위치 찾기
+///
+/// 밝기/대비가 조금 달라도 같은 배치면 높은 점수가 나온다.
+/// 기준 이미지가 충분히 크면 한 번 더 줄인 이미지에서 후보 위치를 찾은 뒤
+/// 원래 크기에서는 후보 주변만 비교한다 (전체 비교보다 수백 배 빠름).
+/// 기준 이미지가 단색이거나 화면보다 크면 None.
+pub fn match_template(haystack: &GrayImage, template: &GrayImage) -> Option<TemplateMatch> {
+    let (hw, hh) = (haystack.width(), haystack.height());
+    let (tw, th) = (template.width(), template.height());
+    if tw == 0 || th == 0 || tw > hw || th > hh {
+        return None;
+    }
+    let full = || ncc_search(haystack, template, 0..=hw - tw, 0..=hh - th, 1).into_iter().next();
+
+    if tw < MIN_COARSE_SIZE * COARSE_FACTOR || th < MIN_COARSE_SIZE * COARSE_FACTOR {
+        return full();
+    }
+    let shrink = |image: &GrayImage| {
+        imageops::resize(
+            image,
+            image.width() / COARSE_FACTOR,
+            image.height() / COARSE_FACTOR,
+            imageops::FilterType::Triangle,
+        )
+    };
+    let (small_haystack, small_template) = (shrink(haystack), shrink(template));
+    let (sw, sh) = (small_haystack.width(), small_haystack.height());
+    let (stw, sth) = (small_template.width(), small_template.height());
+    if stw > sw || sth > sh {
+        return full();
+    }
+
+    let candidates = ncc_search(&small_haystack, &small_template, 0..=sw - stw, 0..=sh - sth, COARSE_CANDIDATES);
+    if candidates.is_empty() {
+        // 축소하니 단색이 된 경우
+        return full();
+    }
+
+    let around = |center: u32, limit: u32| {
+        let center = center * COARSE_FACTOR;
+        center.saturating_sub(COARSE_FACTOR).min(limit)..=(center + COARSE_FACTOR).min(limit)
+    };
+    candidates
+        .iter()
+        .filter_map(|c| {
+            ncc_search(haystack, template, around(c.x, hw - tw), around(c.y, hh - th), 1)
+                .into_iter()
+                .next()
+        })
+        .max_by(|a, b| a.score.total_cmp(&b.score))
+}
+
+/// `xs`/`ys` 범위의 위치에서 정규화 상호상관 계산, 점수가 높은 `keep`개 (높은 순)
+fn ncc_search(
+    haystack: &GrayImage,
+    template: &GrayImage,
+    xs: RangeInclusive<u32>,
+    ys: RangeInclusive<u32>,
+    keep: usize,
+) -> Vec<TemplateMatch> {
+    let (hw, hh) = (haystack.width() as usize, haystack.height() as usize);
+    let (tw, th) = (template.width() as usize, template.height() as usize);
+
+    let n = (tw * th) as f64;
+    let t = template.as_raw();
+    let t_mean = t.iter().map(|&v| v as f64).sum::<f64>() / n;
+    let t_diff: Vec<f64> = t.iter().map(|&v| v as f64 - t_mean).collect();
+    let t_var: f64 = t_diff.iter().map(|d| d * d).sum();
+    if t_var <= f64::EPSILON {
+        return Vec::new();
+    }
+
+    // 창 안의 합/제곱합을 바로 구하기 위한 적분 이미지
+    let h = haystack.as_raw();
+    let stride = hw + 1;
+    let mut sum = vec![0f64; stride * (hh + 1)];
+    let mut sum_sq = vec![0f64; stride * (hh + 1)];
+    for y in 0..hh {
+        let (mut row, mut row_sq) = (0f64, 0f64);
+        for x in 0..hw {
+            let v = h[y * hw + x] as f64;
+            row += v;
+            row_sq += v * v;
+            sum[(y + 1) * stride + x + 1] = sum[y * stride + x + 1] + row;
+            sum_sq[(y + 1) * stride + x + 1] = sum_sq[y * stride + x + 1] + row_sq;
+        }
+    }
+    let area = |table: &[f64], x: usize, y: usize| {
+        table[(y + th) * stride + x + tw] - table[y * stride + x + tw] - table[(y + th) * stride + x]
+            + table[y * stride + x]
+    };
+
+    let mut best: Vec<TemplateMatch> = Vec::with_capacity(keep + 1);
+    for y in ys.clone() {
+        for x in xs.clone() {
+            let (x, y) = (x as usize, y as usize);
+            let s = area(&sum, x, y);
+            let w_var = area(&sum_sq, x, y) - s * s / n;
+            if w_var <= f64::EPSILON {
+                continue;
+            }
+
+            // 기준 이미지 편차의 합이 0이므로 창 평균은 빼지 않아도 된다
+            let mut cross = 0f64;
+            for j in 0..th {
+                let h_row = &h[(y + j) * hw + x..(y + j) * hw + x + tw];
+                let t_row = &t_diff[j * tw..(j + 1) * tw];
+                cross += h_row.iter().zip(t_row).map(|(&a, &b)| a as f64 * b).sum::<f64>();
+            }
+
+            let score = (cross / (t_var * w_var).sqrt()) as f32;
+            if best.len() < keep || best.last().is_some_and(|b| score > b.score) {
+                let at = best.partition_point(|b| b.score >= score);
+                best.insert(
+                    at,
+                    TemplateMatch {
+                        x: x as u32,
+                        y: y as u32,
+                        score,
+                    },
+                );
+                best.truncate(keep);
+            }
+        }
+    }
+
+    best
+}
+
+/// 흑백 변환 후 축소
+fn to_gray(image: &RgbaImage, scale: u32) -> GrayImage {
+    let gray = imageops::grayscale(image);
+    if scale <= 1 {
+        return gray;
+    }
+    imageops::resize(
+        &gray,
+        (gray.width() / scale).max(1),
+        (gray.height() / scale).max(1),
+        imageops::FilterType::Triangle,
+    )
+}
+
+/// 읽어 둔 기준 이미지
+struct LoadedTemplate {
+    path: String,
+    action: TradeAction,
+    threshold: f32,
+    width: u32,
+    height: u32,
+    gray: GrayImage,
+}
+
+/// 프로필 주문창 정보와 제목 매처
+struct DialogMatcher {
+    signature: OrderDialogSignature,
+    titles: PatternMatcher,
+}
+
+/// 주문확인/체결 창 감지기
+///
+/// 새로 뜬 윈도우의 제목을 프로필 주문창 패턴과 비교하고, 기준 이미지가
+/// 있으면 주기적으로 화면을 캡처해서 비교한다.
+pub struct OrderDetector {
+    config: OrderDetectionConfig,
+    dialogs: Vec<DialogMatcher>,
+    templates: Vec<LoadedTemplate>,
+    ocr: Option<Arc<dyn OcrEngine>>,
+    diff: WindowDiff,
+    last_template_scan: Option<Instant>,
+    last_hit: HashMap<TradeAction, Instant>,
+    /// 직전 비교에서 화면에 있던 기준 이미지 (사라져야 다시 감지)
+    visible_templates: HashSet<usize>,
+}
+
+impl OrderDetector {
+    pub fn new(config: OrderDetectionConfig, profiles: &[BrokerProfile]) -> Result<Self, String> {
+        let scale = config.template_scale.max(1);
+        let mut dialogs = Vec::new();
+        let mut templates = Vec::new();
+
+        for signature in profiles.iter().flat_map(|p| &p.order_dialogs) {
+            let patterns: Vec<NamePattern> =
+                signature.title_patterns.iter().map(|t| NamePattern::contains(t)).collect();
+            dialogs.push(DialogMatcher {
+                signature: signature.clone(),
+                titles: PatternMatcher::new(&patterns, &[])?,
+            });
+
+            for template in &signature.templates {
+                let image = xcap::image::open(&template.path)
+                    .map_err(|e| format!("Failed to load dialog template {}: {}", template.path, e))?
+                    .to_rgba8();
+                templates.push(LoadedTemplate {
+                    path: template.path.clone(),
+                    action: template.action,
+                    threshold: template.threshold.unwrap_or(config.template_threshold),
+                    width: image.width(),
+                    height: image.height(),
+                    gray: to_gray(&image, scale),
+                });
+            }
+        }
+
+        Ok(Self {
+            config,
+            dialogs,
+            templates,
+            ocr: None,
+            diff: WindowDiff::default(),
+            last_template_scan: None,
+            last_hit: HashMap::new(),
+            visible_templates: HashSet::new(),
+        })
+    }
+
+    /// 제목만으로 매수/매도를 모를 때 주문창을 읽을 OCR 엔진
+    pub fn with_ocr(mut self, engine: Arc<dyn OcrEngine>) -> Self {
+        self.ocr = Some(engine);
+        self
+    }
+
+    pub fn config(&self) -> &OrderDetectionConfig {
+        &self.config
+    }
+
+    /// 한 번 확인 (새 윈도우 + 주기가 됐으면 기준 이미지)
+    pub fn tick(&mut self) -> Vec<OrderDetection> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let mut found = self.poll_windows();
+
+        let interval = Duration::from_millis(self.config.template_interval_ms);
+        let due = self.last_template_scan.is_none_or(|t| t.elapsed() >= interval);
+        if !self.templates.is_empty() && due {
+            self.last_template_scan = Some(Instant::now());
+            match self.scan_screen() {
+                Ok(detections) => found.extend(detections),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        found
+    }
+
+    /// 새로 뜬 윈도우 중 주문창 찾기
+    pub fn poll_windows(&mut self) -> Vec<OrderDetection> {
+        let windows = match Window::all() {
+            Ok(windows) => windows,
+            Err(e) => {
+                eprintln!("Failed to get windows: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let infos = windows
+            .iter()
+            .map(|w| WindowInfo {
+                id: w.id(),
+                title: w.title().to_string(),
+                bounds: Rect {
+                    x: w.x(),
+                    y: w.y(),
+                    width: w.width(),
+                    height: w.height(),
+                },
+            })
+            .collect();
+        let appeared = self.diff.update(infos);
+
+        let mut found = Vec::new();
+        for info in appeared {
+            let Some(dialog) = self.dialogs.iter().find(|d| d.titles.matches_text(&info.title)) else {
+                continue;
+            };
+            let Some(window) = windows.iter().find(|w| w.id() == info.id) else {
+                continue;
+            };
+
+            let image = match window.capture_image() {
+                Ok(image) => image,
+                Err(e) => {
+                    eprintln!("Failed to capture order dialog: {}", e);
+                    continue;
+                }
+            };
+
+            let (action, confidence, source) = match classify_text(&info.title, &dialog.signature) {
+                Some(action) => (action, TITLE_KEYWORD_CONFIDENCE, DetectionSource::WindowTitle),
+                None => match self.classify_ocr(&image, &dialog.signature) {
+                    Some(action) => (action, OCR_KEYWORD_CONFIDENCE, DetectionSource::Ocr),
+                    None => (TradeAction::Unknown, TITLE_ONLY_CONFIDENCE, DetectionSource::WindowTitle),
+                },
+            };
+
+            self.last_hit.insert(action, Instant::now());
+            found.push(OrderDetection {
+                action,
+                confidence,
+                source,
+                window_title: info.title,
+                bounds: info.bounds,
+                image,
+                detected_at: Utc::now(),
+            });
+        }
+
+        found
+    }
+
+    fn classify_ocr(&self, image: &RgbaImage, signature: &OrderDialogSignature) -> Option<TradeAction> {
+        let engine = self.ocr.as_ref()?;
+        let words = match engine.recognize(image) {
+            Ok(words) => words,
+            Err(e) => {
+                eprintln!("{}", e);
+                return None;
+            }
+        };
+        let text: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        classify_text(&text.join(" "), signature)
+    }
+
+    /// 주 모니터를 캡처해서 기준 이미지와 비교
+    pub fn scan_screen(&mut self) -> Result<Vec<OrderDetection>, String> {
+        let screens = screenshots::Screen::all()
+            .map_err(|e| format!("Failed to get screens: {}", e))?;
+        let screen = screens.first().ok_or_else(|| "No screen found".to_string())?;
+        let captured = screen
+            .capture()
+            .map_err(|e| format!("Failed to capture screen: {}", e))?;
+        let frame = window_capture::screen_image_to_rgba(&captured)?;
+
+        let mut found = self.scan_frame(&frame);
+        for detection in &mut found {
+            detection.bounds.x += screen.display_info.x;
+            detection.bounds.y += screen.display_info.y;
+        }
+        Ok(found)
+    }
+
+    /// 캡처한 화면에서 기준 이미지 찾기 (좌표는 `frame` 기준)
+    ///
+    /// 기준 이미지가 화면에 새로 나타났을 때만 감지한다 (주문창이 떠 있는 동안은 한 번만).
+    /// 같은 주문 종류가 `cooldown_ms` 안에 제목으로 이미 감지됐으면 건너뛴다.
+    pub fn scan_frame(&mut self, frame: &RgbaImage) -> Vec<OrderDetection> {
+        let scale = self.config.template_scale.max(1);
+        let cooldown = Duration::from_millis(self.config.cooldown_ms);
+        let gray = to_gray(frame, scale);
+        let mut found = Vec::new();
+
+        for (index, template) in self.templates.iter().enumerate() {
+            let hit = match_template(&gray, &template.gray).filter(|hit| hit.score >= template.threshold);
+            let Some(hit) = hit else {
+                self.visible_templates.remove(&index);
+                continue;
+            };
+            if !self.visible_templates.insert(index) {
+                continue;
+            }
+
+            let recent = self.last_hit.get(&template.action).is_some_and(|t| t.elapsed() < cooldown);
+            if recent {
+                continue;
+            }
+
+            let x = (hit.x * scale).min(frame.width().saturating_sub(1));
+            let y = (hit.y * scale).min(frame.height().saturating_sub(1));
+            let width = template.width.min(frame.width() - x);
+            let height = template.height.min(frame.height() - y);
+
+            self.last_hit.insert(template.action, Instant::now());
+            found.push(OrderDetection {
+                action: template.action,
+                confidence: hit.score.clamp(0.0, 1.0),
+                source: DetectionSource::Template,
+                window_title: template.path.clone(),
+                bounds: Rect {
+                    x: x as i32,
+                    y: y as i32,
+                    width,
+                    height,
+                },
+                image: imageops::crop_imm(frame, x, y, width, height).to_image(),
+                detected_at: Utc::now(),
+            });
+        }
+
+        found
+    }
+
+    /// 취소될 때까지 감지기를 돌림 (블로킹, 전용 스레드에서 호출)
+    ///
+    /// 감지기는 설정 변경 시 바꿔 끼울 수 있게 Mutex로 받는다.
+    pub fn run(
+        detector: Arc<Mutex<OrderDetector>>,
+        cancel: CancellationToken,
+        mut on_detect: impl FnMut(OrderDetection),
+    ) {
+        while !cancel.is_cancelled() {
+            let (found, poll) = {
+                let mut detector = detector.lock().unwrap();
+                (detector.tick(), detector.config.poll_interval_ms)
+            };
+            for detection in found {
+                on_detect(detection);
+            }
+            thread::sleep(Duration::from_millis(poll.max(50)));
+        }
+    }
+}
+
+/// 주문창 이미지에 가림 적용
+///
+/// 가림 영역은 HTS 윈도우 기준이므로 화면 좌표의 `hts_window`를 주문창 이미지 좌표로 옮겨서 적용한다.
+/// HTS 윈도우를 찾지 못했으면 [`Redactor::apply`]처럼 이미지 전체를 가린다.
+pub fn redact_detection(detection: &OrderDetection, redactor: &Redactor, hts_window: Option<Rect>) -> RgbaImage {
+    let mut image = detection.image.clone();
+    let anchor = hts_window.map(|window| Rect {
+        x: window.x - detection.bounds.x,
+        y: window.y - detection.bounds.y,
+        ..window
+    });
+    redactor.apply(&mut image, anchor);
+    image
+}
+
+/// 주문창 이미지를 가려서 `<dir>/orders/`에 저장하고 거래 이벤트 생성
+///
+/// `hts_window`는 가림 기준이 되는 HTS 윈도우의 화면 좌표 ([`redact_detection`]).
+pub fn save_detection(
+    detection: &OrderDetection,
+    dir: &Path,
+    redactor: &Redactor,
+    hts_window: Option<Rect>,
+    cipher: Option<&Cipher>,
+) -> Result<TradeEvent, String> {
+    let png = window_capture::encode_png(&redact_detection(detection, redactor, hts_window))?;
+    let action = serde_json::to_value(detection.action)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let filename = format!(
+        "order_{}_{}.png",
+        detection.detected_at.format("%Y%m%d_%H%M%S_%3f"),
+        action
+    );
+    let orders_dir = dir.join(ORDERS_DIR);
+    std::fs::create_dir_all(&orders_dir).map_err(|e| format!("Failed to create orders directory: {}", e))?;
+    let written = storage::write_file(&orders_dir.join(filename), &png, cipher)?;
+
+    let source = match detection.source {
+        DetectionSource::Ocr => TradeSource::Ocr,
+        DetectionSource::WindowTitle | DetectionSource::Template => TradeSource::Dialog,
+    };
+    let mut event = TradeEvent::new(detection.action, detection.detected_at, source);
+    event.screenshot_path = Some(written.path.to_string_lossy().to_string());
+    event.window_title = Some(detection.window_title.clone());
+    event.confidence = Some(detection.confidence);
+
+    Ok(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redaction::{RedactionConfig, RedactionMode, RedactionRegion};
+    use xcap::image::{Luma, Rgba};
+
+    fn window(id: u32, title: &str) -> WindowInfo {
+        WindowInfo {
+            id,
+            title: title.to_string(),
+            bounds: Rect {
+                x: 0,
+                y: 0,
+                width: 100,
+                height: 100,
+            },
+        }
+    }
+
+    #[test]
+    fn test_save_detection_applies_redaction() {
+        let temp = tempfile::tempdir().unwrap();
+        let white = Rgba([255, 255, 255, 255]);
+        let detection = OrderDetection {
+            action: TradeAction::Buy,
+            confidence: 0.9,
+            source: DetectionSource::WindowTitle,
+            window_title: "매수 주문확인".to_string(),
+            bounds: Rect { x: 150, y: 100, width: 100, height: 100 },
+            image: RgbaImage::from_pixel(100, 100, white),
+            detected_at: Utc::now(),
+        };
+        // HTS 윈도우 (100,100)~(300,300)의 오른쪽 절반 → 주문창 이미지의 x 50부터
+        let redactor = Redactor::new(RedactionConfig {
+            enabled: true,
+            mode: RedactionMode::Fill,
+            regions: vec![RedactionRegion {
+                name: "계좌".to_string(),
+                x: 0.5,
+                y: 0.0,
+                width: 0.5,
+                height: 1.0,
+            }],
+        })
+        .unwrap();
+        let hts_window = Some(Rect { x: 100, y: 100, width: 200, height: 200 });
+
+        let event = save_detection(&detection, temp.path(), &redactor, hts_window, None).unwrap();
+        let saved = xcap::image::open(event.screenshot_path.unwrap()).unwrap().to_rgba8();
+        assert_eq!(*saved.get_pixel(10, 10), white);
+        assert_ne!(*saved.get_pixel(60, 10), white);
+
+        let unanchored = redact_detection(&detection, &redactor, None);
+        assert!(unanchored.pixels().all(|p| *p != white));
+    }
+
+    #[test]
+    fn test_classify_text() {
+        let signature = OrderDialogSignature::default();
+
+        assert_eq!(classify_text("현금매수 주문확인", &signature), Some(TradeAction::Buy));
+        assert_eq!(classify_text("매도 주문확인", &signature), Some(TradeAction::Sell));
+        assert_eq!(classify_text("주문확인", &signature), None);
+        assert_eq!(classify_text("매수/매도 주문", &signature), None);
+    }
+
+    #[test]
+    fn test_window_diff_reports_only_new_windows() {
+        let mut diff = WindowDiff::default();
+
+        assert!(diff.update(vec![window(1, "영웅문4"), window(2, "주문확인")]).is_empty());
+        let appeared = diff.update(vec![window(1, "영웅문4"), window(3, "매수 주문확인")]);
+        assert_eq!(appeared, vec![window(3, "매수 주문확인")]);
+        assert!(diff.update(vec![window(1, "영웅문4"), window(3, "매수 주문확인")]).is_empty());
+    }
+
+    #[test]
+    fn test_match_template_finds_pattern() {
+        let haystack = GrayImage::from_fn(40, 30, |x, y| Luma([((x * x * 31 + y * y * 17 + x * y * 5) % 251) as u8]));
+        let template = imageops::crop_imm(&haystack, 12, 9, 10, 8).to_image();
+
+        let hit = match_template(&haystack, &template).unwrap();
+        assert_eq!((hit.x, hit.y), (12, 9));
+        assert!(hit.score > 0.99);
+    }
+
+    #[test]
+    fn test_match_template_rejects_flat_or_oversized() {
+        let haystack = GrayImage::from_fn(20, 20, |x, _| Luma([x as u8]));
+
+        assert!(match_template(&haystack, &GrayImage::from_pixel(5, 5, Luma([9]))).is_none());
+        assert!(match_template(&haystack, &GrayImage::new(30, 5)).is_none());
+    }
+
+    /// 비교 위치마다 모양이 다른 매끄러운 화면 (축소해도 위치가 하나로 정해짐)
+    fn chirp(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            let (x, y) = (x as f32, y as f32);
+            let v = 128.0 + 60.0 * (x / 9.0 + y * y / 400.0).sin() + 50.0 * (y / 11.0 + x * x / 900.0).cos();
+            Luma([v as u8])
+        })
+    }
+
+    #[test]
+    fn test_match_template_coarse_to_fine() {
+        let haystack = chirp(240, 160);
+        let template = imageops::crop_imm(&haystack, 133, 71, 48, 32).to_image();
+
+        let hit = match_template(&haystack, &template).unwrap();
+        assert_eq!((hit.x, hit.y), (133, 71));
+        assert!(hit.score > 0.99);
+    }
+
+    #[test]
+    fn test_template_detection_is_edge_triggered() {
+        let screen = xcap::image::DynamicImage::ImageLuma8(chirp(240, 160)).to_rgba8();
+        let blank = RgbaImage::from_pixel(240, 160, xcap::image::Rgba([30, 30, 30, 255]));
+        let dialog = imageops::crop_imm(&screen, 40, 20, 64, 48).to_image();
+
+        let mut detector = OrderDetector::new(
+            OrderDetectionConfig {
+                template_scale: 1,
+                cooldown_ms: 0,
+                ..Default::default()
+            },
+            &[],
+        )
+        .unwrap();
+        detector.templates.push(LoadedTemplate {
+            path: "buy.png".to_string(),
+            action: TradeAction::Buy,
+            threshold: 0.9,
+            width: dialog.width(),
+            height: dialog.height(),
+            gray: to_gray(&dialog, 1),
+        });
+
+        assert_eq!(detector.scan_frame(&screen).len(), 1);
+        // 주문창이 계속 떠 있으면 다시 감지하지 않음
+        assert!(detector.scan_frame(&screen).is_empty());
+        assert!(detector.scan_frame(&blank).is_empty());
+        assert_eq!(detector.scan_frame(&screen).len(), 1);
+    }
+}
@@ -0,0 +1,526 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use screenshots::Screen;
+use tokio_util::sync::CancellationToken;
+
+use crate::activity::{ActivityRecord, BlockSignature};
+use crate::clock::{FrameTimestamp, SessionClock};
+use crate::crypto::Cipher;
+use crate::input::{self, InputContext, InputTrackingConfig};
+use crate::integrity::{ChainEntryKind, HashChain};
+use crate::matcher::PatternMatcher;
+use crate::models::Rect;
+use crate::redaction::Redactor;
+use crate::regions::{self, CaptureRegion, RegionContext, RegionThreads, RegionTrack};
+use crate::storage;
+use crate::tracks::{FrameRecord, TrackWriter};
+use crate::window_capture::{self, WindowCapture};
+
+/// 캡처 → 인코더 큐 크기 (가득 차면 프레임을 버림)
+const CAPTURE_QUEUE: usize = 4;
+
+/// 인코더 → 기록기 큐 크기 (가득 차면 인코더가 대기)
+const WRITE_QUEUE: usize = 4;
+
+/// PNG 인코딩 스레드 최대 개수
+const MAX_ENCODERS: usize = 4;
+
+/// 파이프라인 처리량 카운터
+#[derive(Debug, Default)]
+pub struct PipelineStats {
+    pub(crate) captured: AtomicU64,
+    pub(crate) encoded: AtomicU64,
+    pub(crate) written: AtomicU64,
+    pub(crate) dropped: AtomicU64,
+    pub(crate) bytes_written: AtomicU64,
+    pub(crate) errors: AtomicU64,
+}
+
+/// 상태 조회용 파이프라인 카운터 스냅샷
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PipelineSnapshot {
+    pub captured: u64,
+    pub encoded: u64,
+    pub written: u64,
+    /// 인코더가 밀려서 버린 프레임 수
+    pub dropped: u64,
+    pub bytes_written: u64,
+    /// 인코딩/쓰기 실패 수
+    pub errors: u64,
+    /// 아직 디스크에 쓰이지 않은 프레임 수
+    pub backlog: u64,
+}
+
+impl PipelineStats {
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        let captured = self.captured.load(Ordering::Relaxed);
+        let written = self.written.load(Ordering::Relaxed);
+        let dropped = self.dropped.load(Ordering::Relaxed);
+        let errors = self.errors.load(Ordering::Relaxed);
+
+        PipelineSnapshot {
+            captured,
+            encoded: self.encoded.load(Ordering::Relaxed),
+            written,
+            dropped,
+            bytes_written: self.bytes_written.load(Ordering::Relaxed),
+            errors,
+            backlog: captured.saturating_sub(written + dropped + errors),
+        }
+    }
+}
+
+/// 캡처 스레드가 넘기는 원본 프레임
+struct RawFrame {
+    index: usize,
+    captured: FrameTimestamp,
+    image: screenshots::Image,
+    hts_window: Option<Rect>,
+    focused_window: Option<String>,
+}
+
+/// 인코딩이 끝난 프레임 (인코딩 실패면 `png`가 None, 순서 유지를 위해 그대로 넘김)
+struct EncodedFrame {
+    index: usize,
+    captured: FrameTimestamp,
+    png: Option<Vec<u8>>,
+    focused_window: Option<String>,
+    /// 화면 변화량 계산용 (기록기가 순서대로 이전 프레임과 비교)
+    signature: Option<BlockSignature>,
+}
+
+/// 취소 확인 간격 (프레임 간격이 길어도 중지 요청에 빨리 반응하도록)
+const CANCEL_POLL: Duration = Duration::from_millis(50);
+
+/// 여유 공간이 이보다 적으면 디스크 부족으로 판단
+const MIN_FREE_BYTES: u64 = 512 * 1024 * 1024;
+
+/// 프레임 몇 개마다 여유 공간을 확인할지
+const DISK_CHECK_EVERY: usize = 30;
+
+/// 녹화 중 레코더와 파이프라인 스레드가 함께 쓰는 세션 상태
+pub(crate) struct SessionShared {
+    pub frame_count: AtomicUsize,
+    /// 일시정지 중에는 캡처 틱을 건너뜀
+    pub paused: AtomicBool,
+    /// 기록기가 디스크 부족을 감지하면 설정 (레코더 상태 머신이 확인)
+    pub disk_full: AtomicBool,
+    pub chain: Mutex<HashChain>,
+    pub clock: Mutex<SessionClock>,
+    pub stats: PipelineStats,
+}
+
+impl SessionShared {
+    pub fn new(chain: HashChain, clock: SessionClock) -> Self {
+        Self {
+            frame_count: AtomicUsize::new(0),
+            paused: AtomicBool::new(false),
+            disk_full: AtomicBool::new(false),
+            chain: Mutex::new(chain),
+            clock: Mutex::new(clock),
+            stats: PipelineStats::default(),
+        }
+    }
+}
+
+/// 파이프라인 실행에 필요한 세션 상태
+pub(crate) struct PipelineContext {
+    pub session_dir: PathBuf,
+    pub frame_interval: Duration,
+    pub redactor: Arc<Redactor>,
+    pub window_matcher: PatternMatcher,
+    pub cipher: Option<Cipher>,
+    pub cancel: CancellationToken,
+    pub shared: Arc<SessionShared>,
+    pub frame_track: TrackWriter<FrameRecord>,
+    /// 프레임별 화면 변화량 트랙
+    pub activity_track: TrackWriter<ActivityRecord>,
+    /// 전체 화면과 별도로 더 자주 캡처할 HTS 윈도우 영역
+    pub regions: Vec<CaptureRegion>,
+    /// 커서/클릭 기록 (꺼져 있으면 훅을 설치하지 않음)
+    pub input: InputTrackingConfig,
+}
+
+/// 파이프라인 스레드 핸들
+pub(crate) struct PipelineHandles {
+    capture: JoinHandle<()>,
+    encoders: Vec<JoinHandle<()>>,
+    writer: JoinHandle<()>,
+    regions: RegionThreads,
+    input: Option<JoinHandle<()>>,
+}
+
+impl PipelineHandles {
+    /// 모든 단계가 끝날 때까지 대기
+    ///
+    /// 캡처 스레드가 끝나면 큐가 닫히고, 인코더와 기록기는 남은 프레임을
+    /// 모두 처리한 뒤에 종료한다. 따라서 반환 시점에는 마지막 프레임까지 디스크에 있다.
+    ///
+    /// 영역 트랙별 최종 프레임 수를 돌려준다.
+    pub fn join(self) -> Vec<RegionTrack> {
+        if self.capture.join().is_err() {
+            eprintln!("Capture thread panicked");
+        }
+        for encoder in self.encoders {
+            if encoder.join().is_err() {
+                eprintln!("Encoder thread panicked");
+            }
+        }
+        if self.writer.join().is_err() {
+            eprintln!("Writer thread panicked");
+        }
+        for region in self.regions.handles {
+            if region.join().is_err() {
+                eprintln!("Region thread panicked");
+            }
+        }
+        if let Some(input) = self.input {
+            if input.join().is_err() {
+                eprintln!("Input thread panicked");
+            }
+        }
+
+        self.regions.tracks.iter().map(|t| t.summary()).collect()
+    }
+}
+
+/// 캡처 스레드 → 인코더 스레드 풀 → 기록 스레드 파이프라인 시작
+///
+/// 모든 블로킹 작업(화면 캡처, PNG 인코딩, 파일 쓰기)은 전용 스레드에서
+/// 돌기 때문에 Tauri/tokio 런타임을 막지 않는다.
+pub(crate) fn spawn(ctx: PipelineContext) -> Result<PipelineHandles, String> {
+    let (raw_tx, raw_rx) = mpsc::sync_channel::<RawFrame>(CAPTURE_QUEUE);
+    let (encoded_tx, encoded_rx) = mpsc::sync_channel::<EncodedFrame>(WRITE_QUEUE);
+    let raw_rx = Arc::new(Mutex::new(raw_rx));
+
+    let encoder_count = thread::available_parallelism()
+        .map(|n| n.get().saturating_sub(1))
+        .unwrap_or(1)
+        .clamp(1, MAX_ENCODERS);
+
+    let mut encoders = Vec::with_capacity(encoder_count);
+    for i in 0..encoder_count {
+        let raw_rx = Arc::clone(&raw_rx);
+        let encoded_tx = encoded_tx.clone();
+        let redactor = Arc::clone(&ctx.redactor);
+        let shared = Arc::clone(&ctx.shared);
+
+        let handle = thread::Builder::new()
+            .name(format!("recorder-encoder-{}", i))
+            .spawn(move || encode_loop(raw_rx, encoded_tx, redactor, shared))
+            .map_err(|e| format!("Failed to start encoder thread: {}", e))?;
+        encoders.push(handle);
+    }
+    drop(encoded_tx);
+
+    // 영역 캡처는 자체 스레드에서 HTS 윈도우만 캡처
+    let regions = regions::spawn(RegionContext {
+        session_dir: ctx.session_dir.clone(),
+        regions: ctx.regions,
+        window_matcher: ctx.window_matcher.clone(),
+        redactor: Arc::clone(&ctx.redactor),
+        cipher: ctx.cipher.clone(),
+        cancel: ctx.cancel.clone(),
+        shared: Arc::clone(&ctx.shared),
+    })?;
+
+    // 커서/클릭 기록은 사용자가 켠 경우에만
+    let input = if ctx.input.enabled {
+        Some(input::spawn(InputContext {
+            session_dir: ctx.session_dir.clone(),
+            config: ctx.input.clone(),
+            window_matcher: ctx.window_matcher.clone(),
+            redactor: Arc::clone(&ctx.redactor),
+            cipher: ctx.cipher.clone(),
+            cancel: ctx.cancel.clone(),
+            shared: Arc::clone(&ctx.shared),
+        })?)
+    } else {
+        None
+    };
+
+    let writer = {
+        let session_dir = ctx.session_dir.clone();
+        let cipher = ctx.cipher.clone();
+        let shared = Arc::clone(&ctx.shared);
+        let frame_track = ctx.frame_track;
+        let activity_track = ctx.activity_track;
+
+        thread::Builder::new()
+            .name("recorder-writer".to_string())
+            .spawn(move || write_loop(encoded_rx, session_dir, cipher, shared, frame_track, activity_track))
+            .map_err(|e| format!("Failed to start writer thread: {}", e))?
+    };
+
+    let capture = {
+        let frame_interval = ctx.frame_interval;
+        let redaction_active = ctx.redactor.is_active();
+        let window_matcher = ctx.window_matcher;
+        let cancel = ctx.cancel;
+        let shared = ctx.shared;
+
+        thread::Builder::new()
+            .name("recorder-capture".to_string())
+            .spawn(move || {
+                capture_loop(raw_tx, frame_interval, redaction_active, window_matcher, cancel, shared)
+            })
+            .map_err(|e| format!("Failed to start capture thread: {}", e))?
+    };
+
+    Ok(PipelineHandles {
+        capture,
+        encoders,
+        writer,
+        regions,
+        input,
+    })
+}
+
+fn capture_loop(
+    raw_tx: SyncSender<RawFrame>,
+    frame_interval: Duration,
+    redaction_active: bool,
+    window_matcher: PatternMatcher,
+    cancel: CancellationToken,
+    shared: Arc<SessionShared>,
+) {
+    // 화면 가져오기
+    let screens = match Screen::all() {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to get screens: {}", e);
+            return;
+        }
+    };
+
+    let screen = match screens.first() {
+        Some(s) => s,
+        None => {
+            eprintln!("No screen found");
+            return;
+        }
+    };
+
+    println!("Capturing screen: {}x{}", screen.display_info.width, screen.display_info.height);
+
+    // 가림 영역 기준이 되는 HTS 윈도우 위치 (프레임 좌표계)
+    let mut hts_window: Option<Rect> = None;
+    let mut focused_window: Option<String> = None;
+    let mut next_index = 0usize;
+    let mut next_tick = Instant::now();
+
+    while !cancel.is_cancelled() {
+        // 다음 틱까지 대기 (중간에 취소되면 바로 종료)
+        let mut now = Instant::now();
+        while next_tick > now && !cancel.is_cancelled() {
+            thread::sleep((next_tick - now).min(CANCEL_POLL));
+            now = Instant::now();
+        }
+        if cancel.is_cancelled() {
+            break;
+        }
+        // 캡처가 한 주기 넘게 밀렸으면 밀린 틱은 건너뜀
+        next_tick = (next_tick + frame_interval).max(Instant::now());
+
+        if shared.paused.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        // 스크린샷 캡처
+        let image = match screen.capture() {
+            Ok(img) => img,
+            Err(e) => {
+                eprintln!("Failed to capture: {}", e);
+                continue;
+            }
+        };
+
+        // 캡처 직후 시각 기록 (프레임 번호로 추정하지 않음)
+        let captured = shared.clock.lock().unwrap().stamp();
+        shared.stats.captured.fetch_add(1, Ordering::Relaxed);
+
+        // 가림 기준 윈도우 위치는 매 프레임 갱신 (창을 옮긴 직후 프레임이 가려지지 않은 채 저장되지 않게)
+        if redaction_active {
+            hts_window = WindowCapture::find_window_rect(&window_matcher).map(|r| Rect {
+                x: r.x - screen.display_info.x,
+                y: r.y - screen.display_info.y,
+                ..r
+            });
+        }
+        // 포커스 창 제목은 5프레임마다 갱신
+        if next_index.is_multiple_of(5) {
+            focused_window = WindowCapture::focused_window_title();
+        }
+
+        let frame = RawFrame {
+            index: next_index + 1,
+            captured,
+            image,
+            hts_window,
+            focused_window: focused_window.clone(),
+        };
+
+        match raw_tx.try_send(frame) {
+            Ok(()) => next_index += 1,
+            Err(TrySendError::Full(_)) => {
+                shared.stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) => break,
+        }
+    }
+
+    println!("Capture thread stopped");
+}
+
+fn encode_loop(
+    raw_rx: Arc<Mutex<Receiver<RawFrame>>>,
+    encoded_tx: SyncSender<EncodedFrame>,
+    redactor: Arc<Redactor>,
+    shared: Arc<SessionShared>,
+) {
+    loop {
+        // 큐에서 하나 꺼낼 때만 락을 잡음
+        let frame = match raw_rx.lock().unwrap().recv() {
+            Ok(frame) => frame,
+            Err(_) => break,
+        };
+
+        // 변화량은 저장되는 화면(가림 적용 후) 기준
+        let mut signature = None;
+        let encoded = if redactor.is_active() {
+            window_capture::screen_image_to_rgba(&frame.image).and_then(|mut rgba| {
+                redactor.apply(&mut rgba, frame.hts_window);
+                signature = Some(BlockSignature::from_rgba(rgba.width(), rgba.height(), rgba.as_raw()));
+                window_capture::encode_png(&rgba)
+            })
+        } else {
+            let image = &frame.image;
+            signature = Some(BlockSignature::from_rgba(image.width(), image.height(), image.rgba()));
+            image.to_png().map_err(|e| format!("Failed to encode PNG: {}", e))
+        };
+
+        let png = match encoded {
+            Ok(buf) => {
+                shared.stats.encoded.fetch_add(1, Ordering::Relaxed);
+                Some(buf)
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                shared.stats.errors.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        };
+
+        let out = EncodedFrame {
+            index: frame.index,
+            captured: frame.captured,
+            png,
+            focused_window: frame.focused_window,
+            signature,
+        };
+        if encoded_tx.send(out).is_err() {
+            break;
+        }
+    }
+}
+
+fn write_loop(
+    encoded_rx: Receiver<EncodedFrame>,
+    session_dir: PathBuf,
+    cipher: Option<Cipher>,
+    shared: Arc<SessionShared>,
+    mut frame_track: TrackWriter<FrameRecord>,
+    mut activity_track: TrackWriter<ActivityRecord>,
+) {
+    // 인코더가 여러 개라 순서가 뒤바뀔 수 있음 → 번호 순서대로 기록
+    let mut pending: BTreeMap<usize, EncodedFrame> = BTreeMap::new();
+    let mut next_index = 1usize;
+    // 마지막으로 기록한 프레임 (일시정지 후에는 정지 직전 프레임과 비교)
+    let mut previous: Option<BlockSignature> = None;
+
+    let mut write_frame = |frame: EncodedFrame| {
+        let Some(png) = frame.png else {
+            return;
+        };
+
+        let filename = format!("frame_{:06}.png", frame.index);
+        let filepath = session_dir.join(filename);
+
+        let stored = match storage::write_file(&filepath, &png, cipher.as_ref()) {
+            Ok(stored) => stored,
+            Err(e) => {
+                eprintln!("Failed to write frame: {}", e);
+                shared.stats.errors.fetch_add(1, Ordering::Relaxed);
+                check_disk_space(&session_dir, &shared);
+                return;
+            }
+        };
+
+        let reference = stored.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        if let Err(e) = shared.chain.lock().unwrap().append(ChainEntryKind::Frame, &reference, &stored.sha256) {
+            eprintln!("{}", e);
+        }
+
+        let record = FrameRecord {
+            index: frame.index,
+            file: reference,
+            offset_ms: frame.captured.offset_ms,
+            captured_at: frame.captured.utc,
+            focused_window: frame.focused_window,
+        };
+        if let Err(e) = frame_track.append(&record) {
+            eprintln!("{}", e);
+        }
+
+        if let Some(signature) = frame.signature {
+            let score = previous.as_ref().map(|p| signature.change_score(p)).unwrap_or(0.0);
+            let activity = ActivityRecord {
+                index: frame.index,
+                offset_ms: frame.captured.offset_ms,
+                score,
+            };
+            if let Err(e) = activity_track.append(&activity) {
+                eprintln!("{}", e);
+            }
+            previous = Some(signature);
+        }
+
+        let written = shared.frame_count.fetch_add(1, Ordering::Relaxed) + 1;
+        shared.stats.written.fetch_add(1, Ordering::Relaxed);
+        shared.stats.bytes_written.fetch_add(stored.size, Ordering::Relaxed);
+
+        if written.is_multiple_of(DISK_CHECK_EVERY) {
+            check_disk_space(&session_dir, &shared);
+        }
+    };
+
+    for frame in encoded_rx {
+        pending.insert(frame.index, frame);
+        while let Some(frame) = pending.remove(&next_index) {
+            write_frame(frame);
+            next_index += 1;
+        }
+    }
+
+    // 채널이 닫혔는데 남은 게 있으면 (정상이라면 없음) 순서대로 기록
+    for (_, frame) in std::mem::take(&mut pending) {
+        write_frame(frame);
+    }
+
+    println!("Writer thread stopped");
+}
+
+/// 세션 디렉토리가 있는 디스크의 여유 공간 확인
+fn check_disk_space(session_dir: &Path, shared: &SessionShared) {
+    if let Some(free) = storage::available_space(session_dir) {
+        if free < MIN_FREE_BYTES && !shared.disk_full.swap(true, Ordering::Relaxed) {
+            eprintln!("Disk almost full: {} bytes free", free);
+        }
+    }
+}
@@ -0,0 +1,486 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::models::{FillStatus, Market, TradeAction, TradeEvent};
+use crate::session::SessionRef;
+
+/// 왕복 매매 클립 앞뒤로 더 보여줄 시간 (ms)
+const CLIP_PADDING_MS: u64 = 10_000;
+
+/// 수수료/거래세 모델 (원 단위, 반올림 없음)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeModel {
+    /// 매수/매도 체결 금액에 붙는 증권사 수수료율 (0.00015 = 0.015%)
+    #[serde(default = "default_commission_rate")]
+    pub commission_rate: f64,
+    /// 체결 한 건당 최소 수수료
+    #[serde(default)]
+    pub min_commission: f64,
+    /// 매도 체결 금액에 붙는 거래세 (농특세 포함)
+    #[serde(default = "default_sell_tax_rate")]
+    pub sell_tax_rate: f64,
+    /// 시장별 거래세 (없으면 `sell_tax_rate`)
+    #[serde(default = "default_market_tax_rates")]
+    pub market_tax_rates: HashMap<Market, f64>,
+}
+
+fn default_commission_rate() -> f64 {
+    0.00015
+}
+
+fn default_sell_tax_rate() -> f64 {
+    0.002
+}
+
+fn default_market_tax_rates() -> HashMap<Market, f64> {
+    HashMap::from([(Market::Derivatives, 0.0), (Market::Overseas, 0.0)])
+}
+
+impl Default for FeeModel {
+    fn default() -> Self {
+        Self {
+            commission_rate: default_commission_rate(),
+            min_commission: 0.0,
+            sell_tax_rate: default_sell_tax_rate(),
+            market_tax_rates: default_market_tax_rates(),
+        }
+    }
+}
+
+impl FeeModel {
+    pub fn commission(&self, notional: f64) -> f64 {
+        (notional * self.commission_rate).max(self.min_commission)
+    }
+
+    pub fn sell_tax(&self, market: Option<Market>, notional: f64) -> f64 {
+        let rate = market
+            .and_then(|m| self.market_tax_rates.get(&m).copied())
+            .unwrap_or(self.sell_tax_rate);
+        notional * rate
+    }
+}
+
+/// 녹화 세션에서 왕복 매매를 볼 구간
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipLink {
+    pub session_id: String,
+    /// 세션 시작부터의 오프셋 (ms, 앞뒤 여유 포함)
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub start_frame: Option<usize>,
+    pub end_frame: Option<usize>,
+}
+
+/// 포지션이 0에서 시작해서 다시 0이 될 때까지의 매매 한 번
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundTrip {
+    /// 첫 매수 거래 ID
+    pub id: String,
+    pub ticker: String,
+    pub instrument_name: Option<String>,
+    pub market: Option<Market>,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: DateTime<Utc>,
+    pub quantity: u64,
+    pub avg_entry_price: f64,
+    pub avg_exit_price: f64,
+    /// 수수료/세금 전 손익
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub taxes: f64,
+    pub net_pnl: f64,
+    /// 매수 금액 대비 순손익 (%)
+    pub return_pct: f64,
+    /// 첫 매수부터 마지막 매도까지 (초)
+    pub holding_secs: i64,
+    /// 선입선출로 짝지은 수량 가중 평균 보유 시간 (초)
+    pub avg_lot_holding_secs: i64,
+    pub entry_trade_ids: Vec<String>,
+    pub exit_trade_ids: Vec<String>,
+    pub clips: Vec<ClipLink>,
+}
+
+/// 아직 청산하지 않은 포지션
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenPosition {
+    pub ticker: String,
+    pub instrument_name: Option<String>,
+    pub quantity: u64,
+    pub avg_price: f64,
+    pub opened_at: DateTime<Utc>,
+    /// 지금까지 낸 수수료/세금 (부분 매도 포함)
+    pub fees: f64,
+    pub taxes: f64,
+    /// 부분 매도로 이미 실현한 손익 (수수료/세금 전)
+    pub realized_pnl: f64,
+    pub trade_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlSummary {
+    pub round_trips: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub gross_pnl: f64,
+    pub fees: f64,
+    pub taxes: f64,
+    pub net_pnl: f64,
+}
+
+/// 거래 목록의 실현 손익 계산 결과
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionReport {
+    /// 청산 시각순
+    pub round_trips: Vec<RoundTrip>,
+    pub open_positions: Vec<OpenPosition>,
+    pub summary: PnlSummary,
+    /// 보유 수량 없이 매도된 거래 (조회 기간 전에 산 종목 등)
+    pub unmatched_sells: Vec<String>,
+    /// 종목/수량/가격이 없거나 체결되지 않아 빠진 거래 수
+    pub skipped: usize,
+}
+
+/// 매수 체결 중 아직 팔지 않은 수량
+struct Lot {
+    quantity: u64,
+    price: f64,
+    bought_at: DateTime<Utc>,
+}
+
+/// 진행 중인 왕복 매매
+struct OpenTrip {
+    id: String,
+    instrument_name: Option<String>,
+    market: Option<Market>,
+    opened_at: DateTime<Utc>,
+    lots: VecDeque<Lot>,
+    bought_qty: u64,
+    bought_value: f64,
+    sold_qty: u64,
+    sold_value: f64,
+    gross_pnl: f64,
+    fees: f64,
+    taxes: f64,
+    /// 매도 수량 × 보유 시간 (초) 합
+    lot_holding_secs: f64,
+    entry_trade_ids: Vec<String>,
+    exit_trade_ids: Vec<String>,
+    sessions: Vec<SessionRef>,
+}
+
+impl OpenTrip {
+    fn open(event: &TradeEvent) -> Self {
+        Self {
+            id: event.id.clone(),
+            instrument_name: event.instrument_name.clone(),
+            market: event.market,
+            opened_at: event.timestamp,
+            lots: VecDeque::new(),
+            bought_qty: 0,
+            bought_value: 0.0,
+            sold_qty: 0,
+            sold_value: 0.0,
+            gross_pnl: 0.0,
+            fees: 0.0,
+            taxes: 0.0,
+            lot_holding_secs: 0.0,
+            entry_trade_ids: Vec::new(),
+            exit_trade_ids: Vec::new(),
+            sessions: Vec::new(),
+        }
+    }
+
+    fn held(&self) -> u64 {
+        self.lots.iter().map(|l| l.quantity).sum()
+    }
+
+    fn close(self, ticker: &str, closed_at: DateTime<Utc>) -> RoundTrip {
+        let net_pnl = self.gross_pnl - self.fees - self.taxes;
+        RoundTrip {
+            id: self.id,
+            ticker: ticker.to_string(),
+            instrument_name: self.instrument_name,
+            market: self.market,
+            opened_at: self.opened_at,
+            closed_at,
+            quantity: self.bought_qty,
+            avg_entry_price: self.bought_value / self.bought_qty as f64,
+            avg_exit_price: self.sold_value / self.sold_qty as f64,
+            gross_pnl: self.gross_pnl,
+            fees: self.fees,
+            taxes: self.taxes,
+            net_pnl,
+            return_pct: net_pnl / self.bought_value * 100.0,
+            holding_secs: (closed_at - self.opened_at).num_seconds(),
+            avg_lot_holding_secs: (self.lot_holding_secs / self.sold_qty as f64).round() as i64,
+            entry_trade_ids: self.entry_trade_ids,
+            exit_trade_ids: self.exit_trade_ids,
+            clips: clip_links(&self.sessions),
+        }
+    }
+}
+
+/// 세션별로 거래 위치를 묶어 앞뒤 여유를 둔 구간으로 만듦 (세션 시작 순)
+fn clip_links(refs: &[SessionRef]) -> Vec<ClipLink> {
+    let mut by_session: BTreeMap<&str, ClipLink> = BTreeMap::new();
+    for r in refs {
+        let clip = by_session.entry(&r.session_id).or_insert_with(|| ClipLink {
+            session_id: r.session_id.clone(),
+            start_ms: r.offset_ms,
+            end_ms: r.offset_ms,
+            start_frame: r.frame_index,
+            end_frame: r.frame_index,
+        });
+        if r.offset_ms < clip.start_ms {
+            clip.start_ms = r.offset_ms;
+            clip.start_frame = r.frame_index;
+        }
+        if r.offset_ms > clip.end_ms {
+            clip.end_ms = r.offset_ms;
+            clip.end_frame = r.frame_index;
+        }
+    }
+
+    // 세션 ID는 `recording_YYYYMMDD_HHMMSS`라 이름순이 시작 순서
+    by_session
+        .into_values()
+        .map(|mut clip| {
+            clip.start_ms = clip.start_ms.saturating_sub(CLIP_PADDING_MS);
+            clip.end_ms += CLIP_PADDING_MS;
+            clip
+        })
+        .collect()
+}
+
+/// 손익 계산에 쓸 수 있는 체결인지 (종목, 수량, 가격이 있고 체결된 매수/매도)
+fn is_fill(event: &TradeEvent) -> bool {
+    let filled = !matches!(
+        event.fill_status,
+        Some(FillStatus::Submitted | FillStatus::Cancelled | FillStatus::Rejected)
+    );
+    filled
+        && matches!(event.action, TradeAction::Buy | TradeAction::Sell)
+        && event.ticker.is_some()
+        && event.quantity.is_some_and(|q| q > 0)
+        && event.price.is_some_and(|p| p > 0.0)
+}
+
+/// 종목별로 매수/매도를 선입선출로 짝지어 실현 손익 계산
+///
+/// 매수 포지션만 다룬다. 보유 수량보다 많이 판 부분은 손익에서 빼고
+/// `unmatched_sells`에 거래 ID를 남긴다.
+pub fn compute_positions(events: &[TradeEvent], fees: &FeeModel) -> PositionReport {
+    let mut fills: Vec<&TradeEvent> = events.iter().filter(|e| is_fill(e)).collect();
+    fills.sort_by_key(|e| e.timestamp);
+    let skipped = events.len() - fills.len();
+
+    let mut open: BTreeMap<String, OpenTrip> = BTreeMap::new();
+    let mut round_trips = Vec::new();
+    let mut unmatched_sells = Vec::new();
+
+    for event in fills {
+        let ticker = event.ticker.clone().unwrap_or_default();
+        let quantity = event.quantity.unwrap_or(0);
+        let price = event.price.unwrap_or(0.0);
+
+        match event.action {
+            TradeAction::Buy => {
+                let trip = open.entry(ticker).or_insert_with(|| OpenTrip::open(event));
+                trip.lots.push_back(Lot {
+                    quantity,
+                    price,
+                    bought_at: event.timestamp,
+                });
+                trip.bought_qty += quantity;
+                trip.bought_value += quantity as f64 * price;
+                trip.fees += fees.commission(quantity as f64 * price);
+                trip.entry_trade_ids.push(event.id.clone());
+                trip.sessions.extend(event.session.clone());
+            }
+            TradeAction::Sell => {
+                let Some(trip) = open.get_mut(&ticker) else {
+                    unmatched_sells.push(event.id.clone());
+                    continue;
+                };
+                let matched = quantity.min(trip.held());
+                if matched < quantity {
+                    unmatched_sells.push(event.id.clone());
+                }
+
+                let notional = matched as f64 * price;
+                trip.sold_qty += matched;
+                trip.sold_value += notional;
+                trip.fees += fees.commission(notional);
+                trip.taxes += fees.sell_tax(event.market.or(trip.market), notional);
+                trip.exit_trade_ids.push(event.id.clone());
+                trip.sessions.extend(event.session.clone());
+
+                let mut remaining = matched;
+                while remaining > 0 {
+                    let Some(lot) = trip.lots.front_mut() else { break };
+                    let take = remaining.min(lot.quantity);
+                    trip.gross_pnl += take as f64 * (price - lot.price);
+                    trip.lot_holding_secs += take as f64 * (event.timestamp - lot.bought_at).num_seconds() as f64;
+                    lot.quantity -= take;
+                    remaining -= take;
+                    if lot.quantity == 0 {
+                        trip.lots.pop_front();
+                    }
+                }
+
+                if trip.lots.is_empty() {
+                    if let Some(trip) = open.remove(&ticker) {
+                        round_trips.push(trip.close(&ticker, event.timestamp));
+                    }
+                }
+            }
+            TradeAction::Unknown => {}
+        }
+    }
+
+    let open_positions = open
+        .into_iter()
+        .map(|(ticker, trip)| {
+            let quantity = trip.held();
+            let cost: f64 = trip.lots.iter().map(|l| l.quantity as f64 * l.price).sum();
+            OpenPosition {
+                ticker,
+                instrument_name: trip.instrument_name,
+                quantity,
+                avg_price: cost / quantity as f64,
+                opened_at: trip.opened_at,
+                fees: trip.fees,
+                taxes: trip.taxes,
+                realized_pnl: trip.gross_pnl,
+                trade_ids: trip.entry_trade_ids.into_iter().chain(trip.exit_trade_ids).collect(),
+            }
+        })
+        .collect();
+
+    let summary = PnlSummary {
+        round_trips: round_trips.len(),
+        wins: round_trips.iter().filter(|t| t.net_pnl > 0.0).count(),
+        losses: round_trips.iter().filter(|t| t.net_pnl < 0.0).count(),
+        gross_pnl: round_trips.iter().map(|t| t.gross_pnl).sum(),
+        fees: round_trips.iter().map(|t| t.fees).sum(),
+        taxes: round_trips.iter().map(|t| t.taxes).sum(),
+        net_pnl: round_trips.iter().map(|t| t.net_pnl).sum(),
+    };
+
+    PositionReport {
+        round_trips,
+        open_positions,
+        summary,
+        unmatched_sells,
+        skipped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TradeSource;
+    use chrono::{Duration, TimeZone};
+
+    fn fill(action: TradeAction, minute: i64, quantity: u64, price: f64) -> TradeEvent {
+        let at = Utc.with_ymd_and_hms(2026, 3, 9, 0, 0, 0).unwrap() + Duration::minutes(minute);
+        let mut event = TradeEvent::new(action, at, TradeSource::LogImport);
+        event.ticker = Some("005930".to_string());
+        event.quantity = Some(quantity);
+        event.price = Some(price);
+        event
+    }
+
+    fn no_fees() -> FeeModel {
+        FeeModel {
+            commission_rate: 0.0,
+            min_commission: 0.0,
+            sell_tax_rate: 0.0,
+            market_tax_rates: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_fifo_with_partial_fills() {
+        let events = vec![
+            fill(TradeAction::Buy, 0, 10, 1000.0),
+            fill(TradeAction::Buy, 10, 10, 1100.0),
+            fill(TradeAction::Sell, 20, 15, 1200.0),
+            fill(TradeAction::Sell, 30, 5, 1000.0),
+        ];
+        let report = compute_positions(&events, &no_fees());
+
+        assert_eq!(report.round_trips.len(), 1);
+        let trip = &report.round_trips[0];
+        assert_eq!(trip.quantity, 20);
+        assert_eq!(trip.avg_entry_price, 1050.0);
+        assert_eq!(trip.avg_exit_price, 1150.0);
+        // 10×200 + 5×100 + 5×(-100)
+        assert_eq!(trip.gross_pnl, 2000.0);
+        assert_eq!(trip.holding_secs, 30 * 60);
+        // (10×20분 + 5×10분 + 5×20분) / 20
+        assert_eq!(trip.avg_lot_holding_secs, 17 * 60 + 30);
+        assert!(report.open_positions.is_empty());
+    }
+
+    #[test]
+    fn test_fees_taxes_and_open_position() {
+        let fees = FeeModel {
+            commission_rate: 0.001,
+            min_commission: 0.0,
+            sell_tax_rate: 0.002,
+            market_tax_rates: HashMap::new(),
+        };
+        let events = vec![
+            fill(TradeAction::Buy, 0, 10, 1000.0),
+            fill(TradeAction::Sell, 5, 10, 1100.0),
+            fill(TradeAction::Buy, 10, 5, 1000.0),
+            fill(TradeAction::Sell, 15, 8, 900.0),
+        ];
+        let report = compute_positions(&events, &fees);
+
+        let trip = &report.round_trips[0];
+        assert!((trip.fees - 21.0).abs() < 1e-9);
+        assert!((trip.taxes - 22.0).abs() < 1e-9);
+        assert!((trip.net_pnl - 957.0).abs() < 1e-9);
+
+        // 두 번째 매수는 5주만 팔리고 나머지 3주는 보유보다 많이 판 것
+        assert_eq!(report.round_trips.len(), 2);
+        assert_eq!(report.round_trips[1].quantity, 5);
+        assert_eq!(report.unmatched_sells, vec![events[3].id.clone()]);
+        assert_eq!(report.summary.wins, 1);
+        assert_eq!(report.summary.losses, 1);
+    }
+
+    #[test]
+    fn test_clip_links_per_session() {
+        let mut buy = fill(TradeAction::Buy, 0, 1, 1000.0);
+        buy.session = Some(SessionRef {
+            session_id: "recording_20260309_090000".to_string(),
+            offset_ms: 5_000,
+            frame_index: Some(5),
+        });
+        let mut sell = fill(TradeAction::Sell, 1, 1, 1000.0);
+        sell.session = Some(SessionRef {
+            session_id: "recording_20260309_090000".to_string(),
+            offset_ms: 65_000,
+            frame_index: Some(65),
+        });
+        let mut unrelated = fill(TradeAction::Buy, 2, 1, 1000.0);
+        unrelated.fill_status = Some(FillStatus::Cancelled);
+
+        let report = compute_positions(&[buy, sell, unrelated], &no_fees());
+        assert_eq!(report.skipped, 1);
+        assert_eq!(
+            report.round_trips[0].clips,
+            vec![ClipLink {
+                session_id: "recording_20260309_090000".to_string(),
+                start_ms: 0,
+                end_ms: 75_000,
+                start_frame: Some(5),
+                end_frame: Some(65),
+            }]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use sysinfo::{ProcessRefreshKind, System, UpdateKind};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time;
+
+use crate::matcher::{PatternMatcher, ProcessInfo};
+use crate::profiles::{BrokerProfile, DetectedProfile};
+
+pub struct ProcessMonitor {
+    system: Arc<Mutex<System>>,
+    profiles: Vec<(BrokerProfile, PatternMatcher)>,
+}
+
+/// 프로필별 프로세스 패턴 컴파일 (잘못된 프로필은 건너뜀)
+fn compile_profiles(profiles: Vec<BrokerProfile>) -> Vec<(BrokerProfile, PatternMatcher)> {
+    profiles
+        .into_iter()
+        .filter_map(|profile| match profile.process_matcher() {
+            Ok(matcher) => Some((profile, matcher)),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl ProcessMonitor {
+    pub fn new(profiles: Vec<BrokerProfile>) -> Self {
+        Self {
+            system: Arc::new(Mutex::new(System::new_all())),
+            profiles: compile_profiles(profiles),
+        }
+    }
+
+    /// 프로필 목록 교체 (설정 변경/가져오기 후)
+    pub fn set_profiles(&mut self, profiles: Vec<BrokerProfile>) {
+        self.profiles = compile_profiles(profiles);
+    }
+
+    /// 실행 중인 HTS와 그 프로필 찾기
+    ///
+    /// 프로세스 이름뿐 아니라 실행 파일 경로와 명령줄도 패턴과 비교한다.
+    pub fn detect(&self) -> Option<DetectedProfile> {
+        let mut system = self.system.lock().unwrap();
+        system.refresh_processes_specifics(
+            ProcessRefreshKind::new()
+                .with_exe(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet),
+        );
+        
+        // 여러 프로필이 실행 중이면 설정 순서가 앞선 프로필 (같은 프로필이면 PID가 작은 프로세스)
+        let mut processes: Vec<(u32, ProcessInfo)> = system
+            .processes()
+            .iter()
+            .map(|(pid, process)| {
+                let info = ProcessInfo {
+                    name: process.name().to_string(),
+                    exe_path: process.exe().map(|p| p.to_string_lossy().to_string()),
+                    cmdline: process.cmd().join(" "),
+                };
+                (pid.as_u32(), info)
+            })
+            .collect();
+        processes.sort_by_key(|(pid, _)| *pid);
+        
+        for (profile, matcher) in &self.profiles {
+            if let Some((_, info)) = processes.iter().find(|(_, info)| matcher.matches_process(info)) {
+                return Some(DetectedProfile {
+                    profile_id: profile.id.clone(),
+                    profile_name: profile.name.clone(),
+                    process_name: info.name.clone(),
+                });
+            }
+        }
+        
+        None
+    }
+
+    pub fn is_target_running(&self) -> Option<String> {
+        self.detect().map(|d| d.process_name)
+    }
+
+    pub fn find_process_pid(&self, process_name: &str) -> Option<u32> {
+        let mut system = self.system.lock().unwrap();
+        system.refresh_processes();
+        
+        for (pid, process) in system.processes() {
+            if process.name().to_lowercase().contains(&process_name.to_lowercase()) {
+                return Some(pid.as_u32());
+            }
+        }
+        
+        None
+    }
+
+    pub async fn start_monitoring<F>(
+        &self,
+        interval_ms: u64,
+        mut callback: F,
+    ) -> Result<(), String>
+    where
+        F: FnMut(Option<String>) + Send + 'static,
+    {
+        let mut was_running: Option<String> = None;
+        
+        loop {
+            let is_running = self.is_target_running();
+            
+            if is_running != was_running {
+                callback(is_running.clone());
+                was_running = is_running;
+            }
+            
+            time::sleep(Duration::from_millis(interval_ms)).await;
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::matcher::{NamePattern, PatternMatcher};
+use crate::models::TradeAction;
+use crate::redaction::RedactionRegion;
+
+/// 프로필 내보내기 파일 형식 버전
+pub const PROFILE_BUNDLE_VERSION: u32 = 1;
+
+/// 주문창 식별 정보 (주문창 감지에서 사용)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderDialogSignature {
+    /// 주문창 제목에 들어가는 문자열
+    pub title_patterns: Vec<String>,
+    /// 매수 주문창/확인창에 보이는 문구
+    pub buy_keywords: Vec<String>,
+    /// 매도 주문창/확인창에 보이는 문구
+    pub sell_keywords: Vec<String>,
+    /// 화면에서 찾을 주문확인창 기준 이미지
+    #[serde(default)]
+    pub templates: Vec<DialogTemplate>,
+}
+
+impl Default for OrderDialogSignature {
+    fn default() -> Self {
+        Self {
+            title_patterns: vec!["주문".to_string()],
+            buy_keywords: vec!["매수".to_string()],
+            sell_keywords: vec!["매도".to_string()],
+            templates: Vec::new(),
+        }
+    }
+}
+
+/// 주문확인창 기준 이미지 (HTS에서 직접 잘라낸 PNG)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DialogTemplate {
+    /// 이미지 파일 경로
+    pub path: String,
+    /// 이 이미지와 일치하면 기록할 주문 종류
+    pub action: TradeAction,
+    /// 일치로 볼 최소 유사도 (0.0~1.0, 없으면 감지 설정 값)
+    #[serde(default)]
+    pub threshold: Option<f32>,
+}
+
+/// OCR로 읽을 주문창 필드 위치 (주문창 크기에 대한 0.0~1.0 비율)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrField {
+    /// 필드 이름 (예: "ticker", "quantity", "price")
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// 증권사 HTS 프로필
+///
+/// 프로세스 이름과 그 프로세스가 띄우는 윈도우, 주문창, 가림 영역을 한데 묶는다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokerProfile {
+    /// 고유 ID (예: "kiwoom")
+    pub id: String,
+    /// 표시 이름 (예: "키움증권 영웅문")
+    pub name: String,
+    /// 프로세스 패턴 (이름/실행 경로/명령줄)
+    pub process_names: Vec<NamePattern>,
+    /// 일치해도 HTS로 보지 않을 프로세스 (업데이터 등)
+    #[serde(default)]
+    pub process_excludes: Vec<NamePattern>,
+    pub window_titles: Vec<NamePattern>,
+    #[serde(default)]
+    pub window_excludes: Vec<NamePattern>,
+    #[serde(default)]
+    pub order_dialogs: Vec<OrderDialogSignature>,
+    /// 전역 가림 영역에 더해서 적용할 영역
+    #[serde(default)]
+    pub redaction_regions: Vec<RedactionRegion>,
+    #[serde(default)]
+    pub ocr_layout: Vec<OcrField>,
+}
+
+impl BrokerProfile {
+    fn builtin(id: &str, name: &str, process_names: &[&str], window_titles: &[&str]) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            process_names: process_names.iter().map(|s| NamePattern::exact(s)).collect(),
+            process_excludes: Vec::new(),
+            window_titles: window_titles.iter().map(|s| NamePattern::contains(s)).collect(),
+            window_excludes: Vec::new(),
+            order_dialogs: vec![OrderDialogSignature::default()],
+            redaction_regions: Vec::new(),
+            ocr_layout: Vec::new(),
+        }
+    }
+
+    pub fn process_matcher(&self) -> Result<PatternMatcher, String> {
+        PatternMatcher::new(&self.process_names, &self.process_excludes)
+            .map_err(|e| format!("Profile '{}': {}", self.id, e))
+    }
+
+    pub fn window_matcher(&self) -> Result<PatternMatcher, String> {
+        PatternMatcher::new(&self.window_titles, &self.window_excludes)
+            .map_err(|e| format!("Profile '{}': {}", self.id, e))
+    }
+}
+
+/// 기본 제공 프로필
+pub fn builtin_profiles() -> Vec<BrokerProfile> {
+    vec![
+        BrokerProfile::builtin("kiwoom", "키움증권 영웅문", &["kiwoom.exe", "KOAStudio.exe"], &["영웅문", "키움"]),
+        BrokerProfile::builtin("ebest", "이베스트투자증권", &["xingACE.exe", "xingPLUS.exe"], &["이베스트", "XING"]),
+        BrokerProfile::builtin("kb", "KB증권 H-able", &["hable.exe"], &["KB증권", "H-able"]),
+        BrokerProfile::builtin("mirae", "미래에셋증권", &["Ctrade.exe"], &["미래에셋"]),
+        BrokerProfile::builtin("koreainvestment", "한국투자증권 eFriend", &["eFriend.exe"], &["eFriend", "한국투자"]),
+    ]
+}
+
+/// 세션 매니페스트에 남기는 감지된 프로필
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedProfile {
+    pub profile_id: String,
+    pub profile_name: String,
+    /// 실제로 감지된 프로세스 이름
+    pub process_name: String,
+}
+
+/// 프로필 가져오기/내보내기 파일
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    pub version: u32,
+    pub profiles: Vec<BrokerProfile>,
+}
+
+/// 프로필 목록 검사 (ID 누락/중복, 감지 조건 없음, 잘못된 패턴)
+pub fn validate_profiles(profiles: &[BrokerProfile]) -> Result<(), String> {
+    let mut seen = HashSet::new();
+
+    for profile in profiles {
+        if profile.id.trim().is_empty() {
+            return Err(format!("Profile '{}' has no id", profile.name));
+        }
+        if !seen.insert(profile.id.as_str()) {
+            return Err(format!("Duplicate profile id: {}", profile.id));
+        }
+        if profile.process_names.is_empty() && profile.window_titles.is_empty() {
+            return Err(format!("Profile '{}' has no process names or window titles", profile.id));
+        }
+        profile.process_matcher()?;
+        profile.window_matcher()?;
+    }
+
+    Ok(())
+}
+
+/// 프로필을 파일로 내보내기
+pub fn export_profiles(path: &str, profiles: &[BrokerProfile]) -> Result<(), String> {
+    let bundle = ProfileBundle {
+        version: PROFILE_BUNDLE_VERSION,
+        profiles: profiles.to_vec(),
+    };
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write profiles: {}", e))
+}
+
+/// 파일에서 프로필 읽기
+pub fn import_profiles(path: &str) -> Result<Vec<BrokerProfile>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read profiles: {}", e))?;
+    let bundle: ProfileBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse profiles: {}", e))?;
+
+    if bundle.version > PROFILE_BUNDLE_VERSION {
+        return Err(format!("Unsupported profile file version: {}", bundle.version));
+    }
+    validate_profiles(&bundle.profiles)?;
+
+    Ok(bundle.profiles)
+}
+
+/// 가져온 프로필을 합침 (같은 ID는 교체). 교체된 개수를 돌려준다.
+pub fn merge_profiles(existing: &mut Vec<BrokerProfile>, imported: Vec<BrokerProfile>) -> usize {
+    let mut replaced = 0;
+
+    for profile in imported {
+        match existing.iter_mut().find(|p| p.id == profile.id) {
+            Some(slot) => {
+                *slot = profile;
+                replaced += 1;
+            }
+            None => existing.push(profile),
+        }
+    }
+
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_profiles_valid() {
+        assert!(validate_profiles(&builtin_profiles()).is_ok());
+    }
+
+    #[test]
+    fn test_merge_replaces_same_id() {
+        let mut profiles = builtin_profiles();
+        let count = profiles.len();
+
+        let mut custom = profiles[0].clone();
+        custom.window_titles = vec![NamePattern::glob("영웅문4*")];
+        let new = BrokerProfile::builtin("custom", "사내 HTS", &["custom.exe"], &[]);
+
+        assert_eq!(merge_profiles(&mut profiles, vec![custom, new]), 1);
+        assert_eq!(profiles.len(), count + 1);
+        assert_eq!(profiles[0].window_titles, vec![NamePattern::glob("영웅문4*")]);
+    }
+
+    #[test]
+    fn test_duplicate_ids_rejected() {
+        let mut profiles = builtin_profiles();
+        profiles.push(profiles[0].clone());
+        assert!(validate_profiles(&profiles).is_err());
+    }
+}
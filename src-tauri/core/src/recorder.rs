@@ -0,0 +1,331 @@
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Local, Utc};
+use tokio_util::sync::CancellationToken;
+
+use crate::activity::{self, ActivityRecord};
+use crate::clock::SessionClock;
+use crate::crypto::Cipher;
+use crate::integrity::{self, ChainEntryKind, HashChain};
+use crate::models::{AppConfig, TradeEvent};
+use crate::profiles::DetectedProfile;
+use crate::pipeline::{self, PipelineContext, PipelineHandles, PipelineSnapshot, SessionShared};
+use crate::redaction::Redactor;
+use crate::session::{self, SessionManifest, SessionRef, SessionSummary};
+use crate::tracks::{self, FrameRecord, TrackWriter};
+
+/// 화면 녹화기
+///
+/// 녹화 상태는 `active` 하나에만 있다 (녹화 중이면 Some).
+/// 언제 시작/중지할지는 [`crate::state_machine`]이 정하고, 이 타입은 실제 캡처만 담당한다.
+pub struct ScreenRecorder {
+    cipher: Mutex<Option<Cipher>>,
+    active: Mutex<Option<ActiveSession>>,
+}
+
+/// 진행 중인 녹화 세션
+struct ActiveSession {
+    session_id: String,
+    session_dir: PathBuf,
+    started: Instant,
+    config: AppConfig,
+    cipher: Option<Cipher>,
+    shared: Arc<SessionShared>,
+    task: CaptureTask,
+}
+
+/// 레코더에서 꺼낸, 마무리를 기다리는 세션
+///
+/// [`ScreenRecorder::begin_stop`]으로 꺼낸 뒤 [`StoppingSession::finish`]로 마무리한다.
+/// 마무리(파이프라인 join, 매니페스트 기록)는 오래 걸리므로 다른 락을 잡지 않은 채 부를 것.
+pub struct StoppingSession {
+    session: ActiveSession,
+}
+
+impl std::fmt::Debug for StoppingSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StoppingSession")
+            .field("session_id", &self.session.session_id)
+            .finish()
+    }
+}
+
+/// 실행 중인 캡처 파이프라인과 취소 토큰
+struct CaptureTask {
+    cancel: CancellationToken,
+    handles: PipelineHandles,
+}
+
+impl ScreenRecorder {
+    pub fn new() -> Self {
+        Self {
+            cipher: Mutex::new(None),
+            active: Mutex::new(None),
+        }
+    }
+
+    /// 녹화 파일 암호화 키 설정 (None이면 평문 저장)
+    pub fn set_cipher(&self, cipher: Option<Cipher>) {
+        *self.cipher.lock().unwrap() = cipher;
+    }
+
+    /// 녹화 시작 (연속 스크린샷 방식)
+    ///
+    /// 감지된 HTS 프로필이 있으면 그 프로필의 윈도우와 가림 영역을 사용한다.
+    pub fn start_recording(&self, config: &AppConfig, detected: Option<&DetectedProfile>) -> Result<String, String> {
+        let mut active = self.active.lock().unwrap();
+        
+        if active.is_some() {
+            return Err("Already recording".to_string());
+        }
+
+        let redactor = Redactor::new(config.redaction_for(detected))?;
+
+        let cipher = self.cipher.lock().unwrap().clone();
+        if config.encryption.enabled && cipher.is_none() {
+            return Err("Recordings are encrypted: unlock with passphrase first".to_string());
+        }
+
+        // 출력 디렉토리 생성
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+        let session_id = format!("{}{}", session::SESSION_DIR_PREFIX, timestamp);
+        let session_dir = Path::new(&config.output_dir).join(&session_id);
+        std::fs::create_dir_all(&session_dir)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+
+        let session_dir_str = session_dir.to_string_lossy().to_string();
+
+        // 프레임 타임스탬프용 세션 시계
+        let clock = SessionClock::start();
+        let frame_track = TrackWriter::<FrameRecord>::open_sealed(&session_dir.join(tracks::FRAMES_TRACK), cipher.as_ref())?;
+        let activity_track = TrackWriter::<ActivityRecord>::open(&session_dir.join(activity::ACTIVITY_TRACK))?;
+
+        // 세션 매니페스트 기록
+        let mut manifest = SessionManifest::new(&session_id, clock.info().anchor_utc);
+        if redactor.is_active() {
+            manifest.redaction = Some(redactor.config().clone());
+        }
+        manifest.encrypted = cipher.is_some();
+        manifest.profile = detected.cloned();
+        session::write_manifest(&session_dir, &manifest, cipher.as_ref())?;
+
+        // 위변조 검증용 해시 체인
+        let chain = HashChain::create(&session_dir, &session_id)?;
+        let shared = Arc::new(SessionShared::new(chain, clock));
+
+        println!("Recording started: {}", session_dir_str);
+
+        // 전용 스레드 파이프라인에서 연속 캡처 (1초에 1프레임, 부하 줄이기)
+        let cancel = CancellationToken::new();
+        let context = PipelineContext {
+            session_dir: session_dir.clone(),
+            frame_interval: Duration::from_secs(1),
+            redactor: Arc::new(redactor),
+            window_matcher: config.hts.window_matcher_for(detected),
+            cipher: cipher.clone(),
+            cancel: cancel.clone(),
+            shared: Arc::clone(&shared),
+            frame_track,
+            activity_track,
+            regions: config.regions.clone(),
+            input: config.input_tracking.clone(),
+        };
+        let handles = pipeline::spawn(context)?;
+
+        *active = Some(ActiveSession {
+            session_id,
+            session_dir,
+            started: Instant::now(),
+            config: config.clone(),
+            cipher,
+            shared,
+            task: CaptureTask { cancel, handles },
+        });
+
+        Ok(session_dir_str)
+    }
+
+    /// 녹화 중지
+    ///
+    /// 캡처를 취소하고 파이프라인이 남은 프레임을 모두 쓸 때까지 기다린 뒤
+    /// 매니페스트를 마무리한다. 블로킹 호출이므로 async 컨텍스트에서는
+    /// `spawn_blocking`으로 감싸서 부를 것.
+    pub fn stop_recording(&self) -> Result<SessionSummary, String> {
+        self.begin_stop()?.finish()
+    }
+
+    /// 녹화 중인 세션을 꺼냄 (이후 레코더는 녹화 중이 아님)
+    ///
+    /// 레코더 락은 세션을 꺼내는 동안만 잡는다. 마무리는 돌려받은 세션에서 따로 한다.
+    pub fn begin_stop(&self) -> Result<StoppingSession, String> {
+        let session = self
+            .active
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| "Not recording".to_string())?;
+        Ok(StoppingSession { session })
+    }
+}
+
+impl StoppingSession {
+    /// 세션 마무리 (파이프라인이 끝날 때까지 블로킹)
+    pub fn finish(self) -> Result<SessionSummary, String> {
+        let session = self.session;
+
+        // 캡처 중지 후 마지막 프레임까지 기록될 때까지 대기
+        session.task.cancel.cancel();
+        let region_tracks = session.task.handles.join();
+
+        let shared = &session.shared;
+        let frame_count = shared.frame_count.load(Ordering::Relaxed);
+        let duration_secs = session.started.elapsed().as_secs();
+        let stats = shared.stats.snapshot();
+
+        // 해시 체인 닫고 루트 서명
+        let mut integrity_info = shared.chain.lock().unwrap().info();
+        if session.config.integrity.sign_root {
+            match integrity::load_signing_key(&session.config.integrity, &session.config.output_dir) {
+                Ok(key) => integrity::sign_root(&mut integrity_info, &key),
+                Err(e) => eprintln!("Failed to sign session: {}", e),
+            }
+        }
+        let clock_info = shared.clock.lock().unwrap().info();
+
+        // 매니페스트 마무리
+        let dir = &session.session_dir;
+        let cipher = session.cipher.as_ref();
+        match session::read_manifest(dir, cipher) {
+            Ok(mut manifest) => {
+                manifest.ended_at = Some(Utc::now());
+                manifest.frame_count = frame_count;
+                manifest.integrity = Some(integrity_info);
+                manifest.clock = Some(clock_info);
+                manifest.tracks = region_tracks;
+                if let Err(e) = session::write_manifest(dir, &manifest, cipher) {
+                    eprintln!("{}", e);
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+
+        println!("Recording stopped. Total frames: {}", frame_count);
+        
+        Ok(SessionSummary {
+            session_id: session.session_id,
+            path: session.session_dir.to_string_lossy().to_string(),
+            frame_count,
+            duration_secs,
+            bytes_written: stats.bytes_written,
+            dropped_frames: stats.dropped,
+        })
+    }
+}
+
+impl ScreenRecorder {
+    /// 캡처 일시정지 (세션은 유지)
+    pub fn pause(&self) -> Result<(), String> {
+        self.set_paused(true)
+    }
+
+    /// 일시정지한 캡처 재개
+    pub fn resume(&self) -> Result<(), String> {
+        self.set_paused(false)
+    }
+
+    fn set_paused(&self, paused: bool) -> Result<(), String> {
+        let active = self.active.lock().unwrap();
+        let session = active.as_ref().ok_or_else(|| "Not recording".to_string())?;
+        session.shared.paused.store(paused, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 녹화 중 디스크 여유 공간이 부족해졌는지
+    pub fn is_disk_full(&self) -> bool {
+        self.active
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|s| s.shared.disk_full.load(Ordering::Relaxed))
+    }
+
+    /// 거래 이벤트를 현재 세션의 해시 체인에 기록
+    ///
+    /// 녹화 중이 아니면 아무것도 하지 않는다.
+    pub fn append_trade_event(&self, event: &TradeEvent) -> Result<(), String> {
+        let active = self.active.lock().unwrap();
+        let Some(session) = active.as_ref() else {
+            return Ok(());
+        };
+
+        let content = serde_json::to_vec(event)
+            .map_err(|e| format!("Failed to serialize trade event: {}", e))?;
+        let reference = format!("trade:{}", event.id);
+        session
+            .shared
+            .chain
+            .lock()
+            .unwrap()
+            .append(ChainEntryKind::Trade, &reference, &integrity::sha256_hex(&content))?;
+        Ok(())
+    }
+
+    /// 현재 세션에서 `at` 시각의 위치 (녹화 중이 아니면 None)
+    ///
+    /// 프레임 번호는 그 시각까지 저장된 마지막 프레임 (근사값)이다.
+    pub fn session_position(&self, at: DateTime<Utc>) -> Option<SessionRef> {
+        let active = self.active.lock().unwrap();
+        let session = active.as_ref()?;
+
+        let now = session.shared.clock.lock().unwrap().stamp();
+        let behind_ms = (now.utc - at).num_milliseconds();
+        let frame_count = session.shared.frame_count.load(Ordering::Relaxed);
+
+        Some(SessionRef {
+            session_id: session.session_id.clone(),
+            offset_ms: (now.offset_ms as i64 - behind_ms).max(0) as u64,
+            frame_index: (frame_count > 0).then_some(frame_count),
+        })
+    }
+
+    /// 녹화 중인지 확인
+    pub fn is_recording(&self) -> bool {
+        self.active.lock().unwrap().is_some()
+    }
+
+    /// 녹화 시간 가져오기 (초 단위)
+    pub fn get_recording_duration(&self) -> Option<u64> {
+        self.active.lock().unwrap().as_ref().map(|s| s.started.elapsed().as_secs())
+    }
+
+    /// 현재 출력 디렉토리 경로
+    pub fn get_output_path(&self) -> Option<String> {
+        self.active
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|s| s.session_dir.to_string_lossy().to_string())
+    }
+
+    /// 현재까지 캡처한 프레임 수
+    pub fn get_frame_count(&self) -> usize {
+        self.active
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |s| s.shared.frame_count.load(Ordering::Relaxed))
+    }
+
+    /// 캡처 파이프라인 처리량 (녹화 중이 아니면 None)
+    pub fn get_pipeline_stats(&self) -> Option<PipelineSnapshot> {
+        self.active.lock().unwrap().as_ref().map(|s| s.shared.stats.snapshot())
+    }
+}
+
+impl Default for ScreenRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use xcap::image::{imageops, Rgba, RgbaImage};
+
+use crate::models::Rect;
+
+/// 가림 방식
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionMode {
+    Blur,
+    Pixelate,
+    Fill,
+}
+
+/// HTS 윈도우 기준 가림 영역 (윈도우 크기에 대한 0.0~1.0 비율)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionRegion {
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionConfig {
+    pub enabled: bool,
+    pub mode: RedactionMode,
+    pub regions: Vec<RedactionRegion>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: RedactionMode::Pixelate,
+            regions: Vec::new(),
+        }
+    }
+}
+
+/// 가림 설정 검사 (영역은 윈도우 안의 0.0~1.0 비율)
+pub fn validate_config(config: &RedactionConfig) -> Result<(), String> {
+    for region in &config.regions {
+        let in_window = [region.x, region.y, region.width, region.height]
+            .iter()
+            .all(|v| (0.0..=1.0).contains(v))
+            && region.width > 0.0
+            && region.height > 0.0;
+        if !in_window {
+            return Err(format!("Invalid redaction region '{}': ratios must be within 0.0..=1.0", region.name));
+        }
+    }
+    Ok(())
+}
+
+/// 프레임이 디스크에 쓰이기 전에 가림 처리를 적용
+pub struct Redactor {
+    config: RedactionConfig,
+}
+
+impl Redactor {
+    pub fn new(config: RedactionConfig) -> Result<Self, String> {
+        validate_config(&config)?;
+        Ok(Self { config })
+    }
+
+    pub fn config(&self) -> &RedactionConfig {
+        &self.config
+    }
+
+    /// 실제로 가리는 것이 있는지
+    pub fn is_active(&self) -> bool {
+        self.masks_regions()
+    }
+
+    /// 고정 가림 영역이 있는지 (HTS 윈도우 위치가 필요함)
+    pub fn masks_regions(&self) -> bool {
+        self.config.enabled && !self.config.regions.is_empty()
+    }
+
+    /// 프레임 좌표 `point`가 가림 영역 안인지 (커서/클릭 기록에서 버릴 위치)
+    ///
+    /// [`Redactor::apply`]와 같이 윈도우를 찾지 못했으면 프레임 전체가 가려진 것으로 본다.
+    pub fn hides(&self, (x, y): (i32, i32), window: Option<Rect>) -> bool {
+        if !self.masks_regions() {
+            return false;
+        }
+        let Some(anchor) = window else {
+            return true;
+        };
+        self.region_rects(anchor)
+            .any(|r| x >= r.x && y >= r.y && x < r.x + r.width as i32 && y < r.y + r.height as i32)
+    }
+
+    /// 윈도우 기준 비율 영역을 프레임 좌표로
+    fn region_rects(&self, anchor: Rect) -> impl Iterator<Item = Rect> + '_ {
+        self.config.regions.iter().map(move |region| Rect {
+            x: anchor.x + (region.x * anchor.width as f32) as i32,
+            y: anchor.y + (region.y * anchor.height as f32) as i32,
+            width: (region.width * anchor.width as f32).ceil() as u32,
+            height: (region.height * anchor.height as f32).ceil() as u32,
+        })
+    }
+
+    /// 가림 적용
+    ///
+    /// `window`는 프레임 좌표계의 HTS 윈도우 위치. 가림 영역이 있는데 윈도우를 찾지 못했으면
+    /// 어디를 가려야 할지 모르므로 프레임 전체를 검게 칠한다 (계좌/잔고 창이 보이지 않도록).
+    pub fn apply(&self, image: &mut RgbaImage, window: Option<Rect>) {
+        if !self.masks_regions() {
+            return;
+        }
+
+        let Some(anchor) = window else {
+            for pixel in image.pixels_mut() {
+                *pixel = Rgba([0, 0, 0, 255]);
+            }
+            return;
+        };
+        for rect in self.region_rects(anchor) {
+            self.mask(image, rect);
+        }
+    }
+
+    fn mask(&self, image: &mut RgbaImage, rect: Rect) {
+        // 프레임 밖으로 나간 부분 잘라내기
+        let x0 = rect.x.max(0) as u32;
+        let y0 = rect.y.max(0) as u32;
+        let x1 = (rect.x + rect.width as i32).clamp(0, image.width() as i32) as u32;
+        let y1 = (rect.y + rect.height as i32).clamp(0, image.height() as i32) as u32;
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let (w, h) = (x1 - x0, y1 - y0);
+
+        match self.config.mode {
+            RedactionMode::Fill => {
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+                    }
+                }
+            }
+            RedactionMode::Pixelate => {
+                const BLOCK: u32 = 16;
+                let mut by = y0;
+                while by < y1 {
+                    let mut bx = x0;
+                    while bx < x1 {
+                        let bw = BLOCK.min(x1 - bx);
+                        let bh = BLOCK.min(y1 - by);
+                        let mut sum = [0u64; 4];
+                        for y in by..by + bh {
+                            for x in bx..bx + bw {
+                                let p = image.get_pixel(x, y);
+                                for (s, v) in sum.iter_mut().zip(p.0.iter()) {
+                                    *s += *v as u64;
+                                }
+                            }
+                        }
+                        let n = (bw * bh) as u64;
+                        let avg = Rgba([
+                            (sum[0] / n) as u8,
+                            (sum[1] / n) as u8,
+                            (sum[2] / n) as u8,
+                            (sum[3] / n) as u8,
+                        ]);
+                        for y in by..by + bh {
+                            for x in bx..bx + bw {
+                                image.put_pixel(x, y, avg);
+                            }
+                        }
+                        bx += BLOCK;
+                    }
+                    by += BLOCK;
+                }
+            }
+            RedactionMode::Blur => {
+                let region = imageops::crop_imm(image, x0, y0, w, h).to_image();
+                let blurred = imageops::blur(&region, 12.0);
+                imageops::replace(image, &blurred, x0 as i64, y0 as i64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RedactionConfig {
+        RedactionConfig {
+            enabled: true,
+            mode: RedactionMode::Fill,
+            regions: vec![RedactionRegion {
+                name: "잔고".to_string(),
+                x: 0.5,
+                y: 0.0,
+                width: 0.5,
+                height: 0.5,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_unanchored_regions_black_out_frame() {
+        let redactor = Redactor::new(config()).unwrap();
+        let white = Rgba([255, 255, 255, 255]);
+        let black = Rgba([0, 0, 0, 255]);
+
+        let mut anchored = RgbaImage::from_pixel(100, 100, white);
+        redactor.apply(&mut anchored, Some(Rect { x: 0, y: 0, width: 50, height: 50 }));
+        assert_eq!(*anchored.get_pixel(30, 10), black);
+        assert_eq!(*anchored.get_pixel(80, 10), white);
+
+        let mut unanchored = RgbaImage::from_pixel(100, 100, white);
+        redactor.apply(&mut unanchored, None);
+        assert!(unanchored.pixels().all(|p| *p == black));
+
+        let window = Some(Rect { x: 0, y: 0, width: 50, height: 50 });
+        assert!(redactor.hides((30, 10), window));
+        assert!(!redactor.hides((10, 10), window));
+        assert!(redactor.hides((10, 10), None));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let mut config = config();
+        assert!(validate_config(&config).is_ok());
+        config.regions[0].width = 1.5;
+        assert!(validate_config(&config).is_err());
+        assert!(Redactor::new(config).is_err());
+
+        let disabled = RedactionConfig::default();
+        assert!(!Redactor::new(disabled).unwrap().is_active());
+    }
+}
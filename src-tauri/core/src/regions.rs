@@ -0,0 +1,431 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use xcap::image::{imageops, RgbaImage};
+use xcap::Window;
+
+use crate::clock::FrameTimestamp;
+use crate::crypto::Cipher;
+use crate::integrity::ChainEntryKind;
+use crate::matcher::PatternMatcher;
+use crate::models::Rect;
+use crate::pipeline::SessionShared;
+use crate::redaction::Redactor;
+use crate::storage;
+use crate::tracks::{self, FrameRecord, TrackWriter};
+use crate::window_capture::{self, WindowCapture};
+
+/// 세션 디렉토리 안의 영역 트랙 디렉토리 (`regions/<id>/`)
+pub const REGIONS_DIR: &str = "regions";
+
+/// 영역 캡처 최대 속도
+const MAX_REGION_FPS: f32 = 30.0;
+
+/// 영역 캡처 최소 속도
+const MIN_REGION_FPS: f32 = 0.1;
+
+/// HTS 윈도우 위치/핸들 재확인 주기
+const WINDOW_REFRESH: Duration = Duration::from_secs(2);
+
+/// 영역 캡처 → 기록기 큐 크기 (가득 차면 프레임을 버림)
+const REGION_QUEUE: usize = 16;
+
+/// 취소 확인 간격
+const CANCEL_POLL: Duration = Duration::from_millis(20);
+
+/// HTS 윈도우 안의 캡처 영역 (윈도우 크기에 대한 0.0~1.0 비율)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureRegion {
+    /// 트랙 디렉토리 이름 (영문/숫자/`-`/`_`, 예: "orderbook")
+    pub id: String,
+    /// 표시 이름 (예: "호가창")
+    pub name: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    /// 초당 캡처 횟수
+    pub fps: f32,
+}
+
+impl CaptureRegion {
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f32(1.0 / self.fps.clamp(MIN_REGION_FPS, MAX_REGION_FPS))
+    }
+
+    /// 세션 디렉토리 기준 트랙 디렉토리
+    pub fn track_dir(&self) -> String {
+        format!("{}/{}", REGIONS_DIR, self.id)
+    }
+
+    /// 윈도우 이미지에서 영역 잘라내기 (영역이 윈도우 밖이면 None)
+    fn crop(&self, image: &RgbaImage) -> Option<RgbaImage> {
+        let (w, h) = (image.width() as f32, image.height() as f32);
+        let x = (self.x.clamp(0.0, 1.0) * w) as u32;
+        let y = (self.y.clamp(0.0, 1.0) * h) as u32;
+        let width = ((self.width.max(0.0) * w) as u32).min(image.width().saturating_sub(x));
+        let height = ((self.height.max(0.0) * h) as u32).min(image.height().saturating_sub(y));
+
+        if width == 0 || height == 0 {
+            return None;
+        }
+        Some(imageops::crop_imm(image, x, y, width, height).to_image())
+    }
+}
+
+/// 영역 설정 검사
+pub fn validate_regions(regions: &[CaptureRegion]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+
+    for region in regions {
+        let valid_id = !region.id.is_empty()
+            && region.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_id {
+            return Err(format!("Invalid region id '{}': use letters, digits, '-' or '_'", region.id));
+        }
+        if !seen.insert(region.id.as_str()) {
+            return Err(format!("Duplicate region id: {}", region.id));
+        }
+        if region.width <= 0.0 || region.height <= 0.0 {
+            return Err(format!("Region '{}' has an empty size", region.id));
+        }
+        if region.fps.is_nan() || region.fps <= 0.0 {
+            return Err(format!("Region '{}' needs a positive fps", region.id));
+        }
+    }
+
+    Ok(())
+}
+
+/// 매니페스트에 남기는 영역 트랙 정보
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionTrack {
+    pub region: CaptureRegion,
+    /// 세션 디렉토리 기준 트랙 디렉토리 (`regions/orderbook`)
+    pub dir: String,
+    pub frame_count: usize,
+    /// 기록기가 밀려서 버린 프레임 수
+    pub dropped: u64,
+}
+
+/// 녹화 중 영역별 카운터
+pub(crate) struct RegionTrackState {
+    region: CaptureRegion,
+    written: AtomicUsize,
+    dropped: AtomicU64,
+}
+
+impl RegionTrackState {
+    pub fn summary(&self) -> RegionTrack {
+        RegionTrack {
+            region: self.region.clone(),
+            dir: self.region.track_dir(),
+            frame_count: self.written.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 잘라낸 영역 프레임
+struct RegionFrame {
+    track: usize,
+    index: usize,
+    captured: FrameTimestamp,
+    image: RgbaImage,
+}
+
+/// 실행 중인 영역 캡처 스레드와 트랙 카운터
+#[derive(Default)]
+pub(crate) struct RegionThreads {
+    pub handles: Vec<JoinHandle<()>>,
+    pub tracks: Vec<Arc<RegionTrackState>>,
+}
+
+/// 영역 캡처 실행에 필요한 세션 상태
+pub(crate) struct RegionContext {
+    pub session_dir: PathBuf,
+    pub regions: Vec<CaptureRegion>,
+    pub window_matcher: PatternMatcher,
+    pub redactor: Arc<Redactor>,
+    pub cipher: Option<Cipher>,
+    pub cancel: CancellationToken,
+    pub shared: Arc<SessionShared>,
+}
+
+/// 영역 캡처 스레드와 기록 스레드 시작
+///
+/// 캡처 스레드는 영역마다 다음 캡처 시각을 따로 두고, 가장 빠른 영역에 맞춰
+/// HTS 윈도우만 캡처한 뒤 그 시점에 캡처할 영역들을 잘라낸다.
+pub(crate) fn spawn(ctx: RegionContext) -> Result<RegionThreads, String> {
+    if ctx.regions.is_empty() {
+        return Ok(RegionThreads::default());
+    }
+    validate_regions(&ctx.regions)?;
+
+    let mut writers = Vec::with_capacity(ctx.regions.len());
+    for region in &ctx.regions {
+        let dir = ctx.session_dir.join(region.track_dir());
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create region directory: {}", e))?;
+        writers.push(TrackWriter::<FrameRecord>::open_sealed(&dir.join(tracks::FRAMES_TRACK), ctx.cipher.as_ref())?);
+    }
+
+    let states: Vec<Arc<RegionTrackState>> = ctx
+        .regions
+        .into_iter()
+        .map(|region| {
+            Arc::new(RegionTrackState {
+                region,
+                written: AtomicUsize::new(0),
+                dropped: AtomicU64::new(0),
+            })
+        })
+        .collect();
+
+    let (tx, rx) = mpsc::sync_channel::<RegionFrame>(REGION_QUEUE);
+
+    let writer = {
+        let states = states.clone();
+        let session_dir = ctx.session_dir;
+        let cipher = ctx.cipher;
+        let shared = Arc::clone(&ctx.shared);
+
+        thread::Builder::new()
+            .name("recorder-region-writer".to_string())
+            .spawn(move || region_write_loop(rx, session_dir, cipher, shared, writers, states))
+            .map_err(|e| format!("Failed to start region writer thread: {}", e))?
+    };
+
+    let capture = {
+        let states = states.clone();
+        let window_matcher = ctx.window_matcher;
+        let redactor = ctx.redactor;
+        let cancel = ctx.cancel;
+        let shared = ctx.shared;
+
+        thread::Builder::new()
+            .name("recorder-region-capture".to_string())
+            .spawn(move || region_capture_loop(tx, states, window_matcher, redactor, cancel, shared))
+            .map_err(|e| format!("Failed to start region capture thread: {}", e))?
+    };
+
+    Ok(RegionThreads {
+        handles: vec![capture, writer],
+        tracks: states,
+    })
+}
+
+fn region_capture_loop(
+    tx: SyncSender<RegionFrame>,
+    states: Vec<Arc<RegionTrackState>>,
+    window_matcher: PatternMatcher,
+    redactor: Arc<Redactor>,
+    cancel: CancellationToken,
+    shared: Arc<SessionShared>,
+) {
+    let intervals: Vec<Duration> = states.iter().map(|s| s.region.interval()).collect();
+    let mut next_due = vec![Instant::now(); states.len()];
+    let mut next_index = vec![0usize; states.len()];
+
+    let mut window: Option<Window> = None;
+    let mut window_checked: Option<Instant> = None;
+
+    while !cancel.is_cancelled() {
+        // 가장 먼저 캡처할 영역 시각까지 대기
+        let due_at = next_due.iter().min().copied().unwrap_or_else(Instant::now);
+        let mut now = Instant::now();
+        while due_at > now && !cancel.is_cancelled() {
+            thread::sleep((due_at - now).min(CANCEL_POLL));
+            now = Instant::now();
+        }
+        if cancel.is_cancelled() {
+            break;
+        }
+
+        let due: Vec<usize> = (0..states.len()).filter(|&i| next_due[i] <= now).collect();
+        for &i in &due {
+            next_due[i] = (next_due[i] + intervals[i]).max(now);
+        }
+
+        if shared.paused.load(Ordering::Relaxed) {
+            continue;
+        }
+
+        // HTS 윈도우는 주기적으로 다시 찾음 (이동/재시작 대응)
+        if window.is_none() || window_checked.is_none_or(|t| t.elapsed() >= WINDOW_REFRESH) {
+            window = WindowCapture::find_window(&window_matcher);
+            window_checked = Some(Instant::now());
+        }
+        let Some(target) = window.as_ref() else {
+            continue;
+        };
+
+        let mut image = match target.capture_image() {
+            Ok(image) => image,
+            Err(e) => {
+                eprintln!("Failed to capture HTS window: {}", e);
+                window = None;
+                continue;
+            }
+        };
+        let captured = shared.clock.lock().unwrap().stamp();
+
+        redact_window_image(&redactor, &mut image);
+
+        for i in due {
+            let Some(cropped) = states[i].region.crop(&image) else {
+                continue;
+            };
+
+            let frame = RegionFrame {
+                track: i,
+                index: next_index[i] + 1,
+                captured,
+                image: cropped,
+            };
+            match tx.try_send(frame) {
+                Ok(()) => next_index[i] += 1,
+                Err(TrySendError::Full(_)) => {
+                    states[i].dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+        }
+    }
+
+    println!("Region capture thread stopped");
+}
+
+/// HTS 윈도우 캡처에 가림 적용
+///
+/// 가림 영역도 윈도우 기준이고 이미지 전체가 윈도우이므로 이미지 자체를 기준 위치로 넘긴다.
+fn redact_window_image(redactor: &Redactor, image: &mut RgbaImage) {
+    let window = Rect {
+        x: 0,
+        y: 0,
+        width: image.width(),
+        height: image.height(),
+    };
+    redactor.apply(image, Some(window));
+}
+
+fn region_write_loop(
+    rx: Receiver<RegionFrame>,
+    session_dir: PathBuf,
+    cipher: Option<Cipher>,
+    shared: Arc<SessionShared>,
+    mut writers: Vec<TrackWriter<FrameRecord>>,
+    states: Vec<Arc<RegionTrackState>>,
+) {
+    for frame in rx {
+        let state = &states[frame.track];
+
+        let png = match window_capture::encode_png(&frame.image) {
+            Ok(png) => png,
+            Err(e) => {
+                eprintln!("{}", e);
+                shared.stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+
+        let relative = format!("{}/frame_{:06}.png", state.region.track_dir(), frame.index);
+        let stored = match storage::write_file(&session_dir.join(&relative), &png, cipher.as_ref()) {
+            Ok(stored) => stored,
+            Err(e) => {
+                eprintln!("Failed to write region frame: {}", e);
+                shared.stats.errors.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        };
+
+        // 암호화되면 `.enc`가 붙으므로 실제 파일 이름으로 기록
+        let file_name = stored.path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let reference = format!("{}/{}", state.region.track_dir(), file_name);
+
+        if let Err(e) = shared.chain.lock().unwrap().append(ChainEntryKind::Frame, &reference, &stored.sha256) {
+            eprintln!("{}", e);
+        }
+
+        let record = FrameRecord {
+            index: frame.index,
+            file: reference,
+            offset_ms: frame.captured.offset_ms,
+            captured_at: frame.captured.utc,
+            focused_window: None,
+        };
+        if let Err(e) = writers[frame.track].append(&record) {
+            eprintln!("{}", e);
+        }
+
+        state.written.fetch_add(1, Ordering::Relaxed);
+        shared.stats.bytes_written.fetch_add(stored.size, Ordering::Relaxed);
+    }
+
+    println!("Region writer thread stopped");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::redaction::{RedactionConfig, RedactionMode, RedactionRegion};
+    use xcap::image::Rgba;
+
+    fn region(id: &str, x: f32, y: f32, width: f32, height: f32) -> CaptureRegion {
+        CaptureRegion {
+            id: id.to_string(),
+            name: id.to_string(),
+            x,
+            y,
+            width,
+            height,
+            fps: 5.0,
+        }
+    }
+
+    #[test]
+    fn test_crop_clamps_to_window() {
+        let image = RgbaImage::new(200, 100);
+
+        let cropped = region("chart", 0.5, 0.5, 0.8, 0.8).crop(&image).unwrap();
+        assert_eq!(cropped.dimensions(), (100, 50));
+        assert!(region("outside", 1.0, 0.0, 0.5, 0.5).crop(&image).is_none());
+    }
+
+    #[test]
+    fn test_validate_regions() {
+        assert!(validate_regions(&[region("orderbook", 0.0, 0.0, 0.3, 0.5)]).is_ok());
+        assert!(validate_regions(&[region("호가창", 0.0, 0.0, 0.3, 0.5)]).is_err());
+        assert!(validate_regions(&[region("a", 0.0, 0.0, 0.3, 0.5), region("a", 0.1, 0.0, 0.3, 0.5)]).is_err());
+    }
+
+    #[test]
+    fn test_redaction_masks_only_configured_area() {
+        let redactor = Redactor::new(RedactionConfig {
+            enabled: true,
+            mode: RedactionMode::Fill,
+            regions: vec![RedactionRegion {
+                name: "계좌".to_string(),
+                x: 0.0,
+                y: 0.0,
+                width: 0.5,
+                height: 0.5,
+            }],
+        })
+        .unwrap();
+        let white = Rgba([255, 255, 255, 255]);
+        let mut image = RgbaImage::from_pixel(200, 100, white);
+
+        redact_window_image(&redactor, &mut image);
+        assert_ne!(*image.get_pixel(10, 10), white);
+        assert_eq!(*image.get_pixel(150, 80), white);
+
+        let orderbook = region("orderbook", 0.5, 0.5, 0.5, 0.5).crop(&image).unwrap();
+        assert!(orderbook.pixels().all(|p| *p == white));
+    }
+}
@@ -0,0 +1,525 @@
+use base64::Engine;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use xcap::image;
+
+use crate::crypto::Cipher;
+use crate::models::{TradeAction, TradeEvent};
+use crate::positions::{self, ClipLink, FeeModel, PositionReport};
+use crate::schedule::kst;
+use crate::session::{self, SessionRef, SessionTimeline};
+use crate::storage;
+use crate::trade_store::{TradeQuery, TradeStore};
+
+/// 출력 디렉토리 안의 리포트 폴더
+pub const REPORTS_DIR: &str = "reports";
+
+/// 리포트에 넣는 스크린샷 썸네일 최대 크기 (px)
+const THUMBNAIL_SIZE: u32 = 240;
+
+/// 일일 리포트 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportConfig {
+    /// 그날 마지막 장 시간대가 끝나고 녹화가 멈추면 자동 생성
+    #[serde(default = "default_auto_generate")]
+    pub auto_generate: bool,
+}
+
+fn default_auto_generate() -> bool {
+    true
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            auto_generate: default_auto_generate(),
+        }
+    }
+}
+
+/// 리포트에 들어가는 녹화 세션
+#[derive(Debug, Clone)]
+pub struct ReportSession {
+    pub timeline: SessionTimeline,
+    /// 종료 기록이 없으면 마지막 프레임 시각
+    pub ended_at: Option<DateTime<Utc>>,
+    pub frame_count: usize,
+    pub total_bytes: u64,
+}
+
+impl ReportSession {
+    pub fn duration_secs(&self) -> i64 {
+        self.ended_at
+            .map(|end| (end - self.timeline.started_at).num_seconds().max(0))
+            .unwrap_or(0)
+    }
+}
+
+/// 하루치 리포트 데이터 (KST 날짜 기준)
+#[derive(Debug, Clone)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    /// 시작 시각순
+    pub sessions: Vec<ReportSession>,
+    /// 시각순
+    pub trades: Vec<TradeEvent>,
+    pub positions: PositionReport,
+}
+
+impl DailyReport {
+    /// 녹화도 거래도 없는 날
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.trades.is_empty()
+    }
+
+    pub fn recording_secs(&self) -> i64 {
+        self.sessions.iter().map(|s| s.duration_secs()).sum()
+    }
+
+    /// 세션 위치의 프레임 파일 (출력 디렉토리 기준 상대 경로)
+    fn frame_file(&self, session_id: &str, frame_index: Option<usize>) -> Option<String> {
+        let session = self.sessions.iter().find(|s| s.timeline.session_id == session_id)?;
+        let frame = session.timeline.frames.iter().find(|f| Some(f.index) == frame_index)?;
+        Some(format!("{}/{}", session_id, frame.file))
+    }
+}
+
+/// KST 날짜의 `[00:00, 다음날 00:00)` 구간 (UTC)
+pub fn kst_day_range(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = kst()
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .unwrap()
+        .with_timezone(&Utc);
+    (start, start + Duration::days(1))
+}
+
+/// `<output_dir>/reports/report_YYYYMMDD.html`
+pub fn report_path(output_dir: &str, date: NaiveDate) -> PathBuf {
+    Path::new(output_dir)
+        .join(REPORTS_DIR)
+        .join(format!("report_{}.html", date.format("%Y%m%d")))
+}
+
+/// 세션 목록, 거래, 실현 손익 모으기
+pub fn collect(
+    output_dir: &str,
+    date: NaiveDate,
+    cipher: Option<&Cipher>,
+    fees: &FeeModel,
+) -> Result<DailyReport, String> {
+    let (from, to) = kst_day_range(date);
+
+    let mut sessions: Vec<ReportSession> = session::load_timelines(output_dir, cipher)?
+        .into_iter()
+        .filter(|t| from <= t.started_at && t.started_at < to)
+        .map(|timeline| {
+            let info = session::read_session(&Path::new(output_dir).join(&timeline.session_id)).ok();
+            ReportSession {
+                ended_at: timeline.ended_at.or_else(|| timeline.frames.last().map(|f| f.captured_at)),
+                frame_count: info.as_ref().map(|i| i.frame_count).unwrap_or(timeline.frames.len()),
+                total_bytes: info.map(|i| i.total_bytes).unwrap_or(0),
+                timeline,
+            }
+        })
+        .collect();
+    sessions.sort_by_key(|s| s.timeline.started_at);
+
+    let query = TradeQuery {
+        from: Some(from),
+        to: Some(to),
+        ..Default::default()
+    };
+    let trades = TradeStore::open(output_dir, cipher)?.matching(&query)?;
+    let positions = positions::compute_positions(&trades, fees);
+
+    Ok(DailyReport {
+        date,
+        sessions,
+        trades,
+        positions,
+    })
+}
+
+/// 리포트를 만들어 `reports/`에 저장하고 경로를 돌려줌
+pub fn write_daily_report(
+    output_dir: &str,
+    date: NaiveDate,
+    cipher: Option<&Cipher>,
+    fees: &FeeModel,
+) -> Result<PathBuf, String> {
+    let report = collect(output_dir, date, cipher, fees)?;
+    write_report(output_dir, &report, cipher)
+}
+
+/// 모은 리포트를 HTML로 저장
+pub fn write_report(output_dir: &str, report: &DailyReport, cipher: Option<&Cipher>) -> Result<PathBuf, String> {
+    let html = render_html(report, Path::new(output_dir), cipher);
+
+    let path = report_path(output_dir, report.date);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create reports directory: {}", e))?;
+    }
+    // 스크린샷 썸네일이 들어가므로 암호화 중이면 리포트도 암호화해서 저장
+    let stored = storage::write_file(&path, html.as_bytes(), cipher)?;
+    Ok(stored.path)
+}
+
+/// 자동 리포트를 만들 때인지
+///
+/// 거래일 마지막 시간대가 끝났고, 녹화 중이 아니며, 아직 그날 리포트가 없을 때.
+pub fn report_due(
+    output_dir: &str,
+    date: NaiveDate,
+    day_close: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    recording: bool,
+) -> bool {
+    let path = report_path(output_dir, date);
+    !recording
+        && day_close.is_some_and(|close| now >= close)
+        && !path.exists()
+        && !storage::encrypted_path(&path).exists()
+}
+
+/// 스크린샷을 JPEG 썸네일 data URI로 (읽을 수 없으면 `None`)
+fn thumbnail_data_uri(path: &Path, cipher: Option<&Cipher>) -> Option<String> {
+    let thumbnail = storage::read_file(path, cipher)
+        .and_then(|data| image::load_from_memory(&data).map_err(|e| format!("Failed to decode image: {}", e)))
+        .map(|image| image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8());
+    let thumbnail = match thumbnail {
+        Ok(thumbnail) => thumbnail,
+        Err(e) => {
+            eprintln!("Report thumbnail skipped ({}): {}", path.display(), e);
+            return None;
+        }
+    };
+
+    let mut buffer = Vec::new();
+    image::DynamicImage::ImageRgb8(thumbnail)
+        .write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Jpeg)
+        .ok()?;
+    Some(format!(
+        "data:image/jpeg;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(buffer)
+    ))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 천 단위 구분 원화 금액 (원 단위 반올림)
+fn format_won(value: f64) -> String {
+    let rounded = value.round() as i64;
+    let digits = rounded.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    if rounded < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+fn format_duration(secs: i64) -> String {
+    format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+fn format_offset(offset_ms: u64) -> String {
+    format_duration((offset_ms / 1000) as i64)
+}
+
+fn kst_time(at: DateTime<Utc>) -> String {
+    at.with_timezone(&kst()).format("%H:%M:%S").to_string()
+}
+
+/// 리포트 파일(`reports/`) 기준 세션 위치 링크
+fn session_link(report: &DailyReport, at: &SessionRef) -> String {
+    let label = format!("{} +{}", escape(&at.session_id), format_offset(at.offset_ms));
+    match report.frame_file(&at.session_id, at.frame_index) {
+        Some(file) => format!("<a href=\"../{}\">{}</a>", escape(&file), label),
+        None => label,
+    }
+}
+
+fn clip_link(report: &DailyReport, clip: &ClipLink) -> String {
+    let label = format!(
+        "{} {}~{}",
+        escape(&clip.session_id),
+        format_offset(clip.start_ms),
+        format_offset(clip.end_ms)
+    );
+    match report.frame_file(&clip.session_id, clip.start_frame) {
+        Some(file) => format!("<a href=\"../{}\">{}</a>", escape(&file), label),
+        None => label,
+    }
+}
+
+fn pnl_class(value: f64) -> &'static str {
+    if value > 0.0 {
+        "gain"
+    } else if value < 0.0 {
+        "loss"
+    } else {
+        ""
+    }
+}
+
+const STYLE: &str = "body{font-family:-apple-system,'Malgun Gothic',sans-serif;margin:2rem;color:#1f2937}\
+h1{font-size:1.5rem}h2{font-size:1.15rem;margin-top:2rem}\
+table{border-collapse:collapse;width:100%;font-size:.875rem}\
+th,td{border-bottom:1px solid #e5e7eb;padding:.4rem .5rem;text-align:left;vertical-align:top}\
+th{background:#f9fafb}.num{text-align:right}.gain{color:#dc2626}.loss{color:#2563eb}\
+.cards{display:flex;gap:1rem;flex-wrap:wrap}.card{border:1px solid #e5e7eb;border-radius:6px;padding:.75rem 1rem}\
+.card b{display:block;font-size:1.2rem}.muted{color:#6b7280}img{max-width:240px;border-radius:4px}\
+ul{margin:0;padding-left:1.1rem}";
+
+/// 하나의 파일로 열 수 있는 HTML (썸네일은 data URI로 포함)
+///
+/// 세션 프레임 링크는 `reports/` 기준 상대 경로라 출력 디렉토리 안에서 열어야 동작한다.
+pub fn render_html(report: &DailyReport, output_dir: &Path, cipher: Option<&Cipher>) -> String {
+    let summary = &report.positions.summary;
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html><html lang=\"ko\"><head><meta charset=\"utf-8\"><title>매매 리포트 {date}</title>\
+         <style>{STYLE}</style></head><body><h1>매매 리포트 {date}</h1>",
+        date = report.date.format("%Y-%m-%d")
+    );
+
+    let _ = write!(
+        html,
+        "<div class=\"cards\">\
+         <div class=\"card\"><span class=\"muted\">녹화 세션</span><b>{}</b></div>\
+         <div class=\"card\"><span class=\"muted\">녹화 시간</span><b>{}</b></div>\
+         <div class=\"card\"><span class=\"muted\">거래</span><b>{}</b></div>\
+         <div class=\"card\"><span class=\"muted\">왕복 매매 (승/패)</span><b>{} ({}/{})</b></div>\
+         <div class=\"card\"><span class=\"muted\">실현 손익</span><b class=\"{}\">{}원</b></div>\
+         <div class=\"card\"><span class=\"muted\">수수료 / 세금</span><b>{} / {}</b></div>\
+         </div>",
+        report.sessions.len(),
+        format_duration(report.recording_secs()),
+        report.trades.len(),
+        summary.round_trips,
+        summary.wins,
+        summary.losses,
+        pnl_class(summary.net_pnl),
+        format_won(summary.net_pnl),
+        format_won(summary.fees),
+        format_won(summary.taxes)
+    );
+
+    // 세션 목록
+    html.push_str("<h2>녹화 세션</h2><table><tr><th>세션</th><th>시작</th><th>종료</th><th class=\"num\">길이</th><th class=\"num\">프레임</th><th class=\"num\">크기 (MB)</th></tr>");
+    for session in &report.sessions {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{:.1}</td></tr>",
+            escape(&session.timeline.session_id),
+            kst_time(session.timeline.started_at),
+            session.ended_at.map(kst_time).unwrap_or_else(|| "-".to_string()),
+            format_duration(session.duration_secs()),
+            session.frame_count,
+            session.total_bytes as f64 / 1_048_576.0
+        );
+    }
+    html.push_str("</table>");
+
+    // 거래
+    html.push_str("<h2>거래</h2><table><tr><th>시각</th><th>구분</th><th>종목</th><th class=\"num\">수량</th><th class=\"num\">가격</th><th>출처</th><th class=\"num\">확신도</th><th>녹화 위치</th><th>저널</th><th>스크린샷</th></tr>");
+    for trade in &report.trades {
+        let action = match trade.action {
+            TradeAction::Buy => "매수",
+            TradeAction::Sell => "매도",
+            TradeAction::Unknown => "-",
+        };
+        let instrument = trade
+            .instrument_name
+            .as_deref()
+            .or(trade.ticker.as_deref())
+            .map(escape)
+            .unwrap_or_else(|| "-".to_string());
+
+        let mut journal = String::new();
+        if let Some(rating) = trade.rating {
+            let _ = write!(journal, "{}{}", "★".repeat(rating.min(5) as usize), "☆".repeat(5 - rating.min(5) as usize));
+        }
+        for tag in &trade.tags {
+            let _ = write!(journal, " <span class=\"muted\">#{}</span>", escape(tag));
+        }
+        if !trade.notes.is_empty() {
+            journal.push_str("<ul>");
+            for note in &trade.notes {
+                let _ = write!(journal, "<li>{}</li>", escape(&note.text));
+            }
+            journal.push_str("</ul>");
+        }
+
+        let mut images = String::new();
+        let screenshots = trade
+            .screenshot_path
+            .iter()
+            .map(|p| (p.as_str(), None))
+            .chain(trade.attachments.iter().map(|a| (a.path.as_str(), a.caption.as_deref())));
+        for (path, caption) in screenshots {
+            let path = Path::new(path);
+            let path = if path.is_absolute() { path.to_path_buf() } else { output_dir.join(path) };
+            if let Some(uri) = thumbnail_data_uri(&path, cipher) {
+                let _ = write!(images, "<img src=\"{}\" alt=\"{}\">", uri, escape(caption.unwrap_or("")));
+            }
+        }
+
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{:?}</td><td class=\"num\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            kst_time(trade.timestamp),
+            action,
+            instrument,
+            trade.quantity.map(|q| q.to_string()).unwrap_or_default(),
+            trade.price.map(format_won).unwrap_or_default(),
+            trade.source,
+            trade.confidence.map(|c| format!("{:.0}%", c * 100.0)).unwrap_or_default(),
+            trade.session.as_ref().map(|s| session_link(report, s)).unwrap_or_default(),
+            journal,
+            images
+        );
+    }
+    html.push_str("</table>");
+
+    // 왕복 매매
+    html.push_str("<h2>실현 손익</h2><table><tr><th>종목</th><th>진입</th><th>청산</th><th class=\"num\">수량</th><th class=\"num\">평균 매수가</th><th class=\"num\">평균 매도가</th><th class=\"num\">순손익</th><th class=\"num\">수익률</th><th>녹화</th></tr>");
+    for trip in &report.positions.round_trips {
+        let clips: Vec<String> = trip.clips.iter().map(|c| clip_link(report, c)).collect();
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num {}\">{}</td><td class=\"num\">{:+.2}%</td><td>{}</td></tr>",
+            escape(trip.instrument_name.as_deref().unwrap_or(&trip.ticker)),
+            kst_time(trip.opened_at),
+            kst_time(trip.closed_at),
+            trip.quantity,
+            format_won(trip.avg_entry_price),
+            format_won(trip.avg_exit_price),
+            pnl_class(trip.net_pnl),
+            format_won(trip.net_pnl),
+            trip.return_pct,
+            clips.join("<br>")
+        );
+    }
+    html.push_str("</table>");
+
+    if !report.positions.open_positions.is_empty() {
+        html.push_str("<h2>미청산 포지션</h2><table><tr><th>종목</th><th class=\"num\">수량</th><th class=\"num\">평균가</th></tr>");
+        for position in &report.positions.open_positions {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                escape(position.instrument_name.as_deref().unwrap_or(&position.ticker)),
+                position.quantity,
+                format_won(position.avg_price)
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    let _ = write!(
+        html,
+        "<p class=\"muted\">생성: {}</p></body></html>",
+        Utc::now().with_timezone(&kst()).format("%Y-%m-%d %H:%M:%S KST")
+    );
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TradeSource;
+    use crate::session::SessionManifest;
+    use crate::tracks::{FrameRecord, TrackWriter, FRAMES_TRACK};
+
+    #[test]
+    fn test_format_won() {
+        assert_eq!(format_won(1234567.4), "1,234,567");
+        assert_eq!(format_won(-1000.0), "-1,000");
+        assert_eq!(format_won(999.0), "999");
+    }
+
+    #[test]
+    fn test_daily_report_contents() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let output_dir = dir.to_str().unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let started = kst().with_ymd_and_hms(2026, 3, 9, 9, 0, 0).unwrap().with_timezone(&Utc);
+
+        let session_id = "recording_20260309_090000";
+        let session_dir = dir.join(session_id);
+        std::fs::create_dir_all(&session_dir).unwrap();
+        let mut manifest = SessionManifest::new(session_id, started);
+        manifest.ended_at = Some(started + Duration::minutes(30));
+        session::write_manifest(&session_dir, &manifest, None).unwrap();
+        TrackWriter::open(&session_dir.join(FRAMES_TRACK))
+            .unwrap()
+            .append(&FrameRecord {
+                index: 0,
+                file: "frame_000000.png".to_string(),
+                offset_ms: 60_000,
+                captured_at: started + Duration::minutes(1),
+                focused_window: None,
+            })
+            .unwrap();
+
+        let mut store = TradeStore::open(output_dir, None).unwrap();
+        let mut buy = TradeEvent::new(TradeAction::Buy, started + Duration::minutes(1), TradeSource::LogImport);
+        buy.ticker = Some("005930".to_string());
+        buy.instrument_name = Some("삼성<전자>".to_string());
+        buy.quantity = Some(10);
+        buy.price = Some(70_000.0);
+        buy.session = Some(SessionRef {
+            session_id: session_id.to_string(),
+            offset_ms: 60_000,
+            frame_index: Some(0),
+        });
+        buy.notes.push(crate::models::TradeNote {
+            id: "n1".to_string(),
+            text: "계획대로 진입".to_string(),
+            created_at: started,
+            updated_at: None,
+        });
+        // 저장된 값이 범위를 벗어나도 별점은 5개까지만
+        buy.rating = Some(9);
+        store.record(buy).unwrap();
+
+        let path = write_daily_report(output_dir, date, None, &FeeModel::default()).unwrap();
+        let html = std::fs::read_to_string(&path).unwrap();
+        assert!(html.contains("매매 리포트 2026-03-09"));
+        assert!(html.contains("삼성&lt;전자&gt;"));
+        assert!(html.contains("계획대로 진입"));
+        assert!(html.contains("href=\"../recording_20260309_090000/frame_000000.png\""));
+        assert!(html.contains("0:30:00"));
+        assert!(html.contains("★★★★★<"));
+
+        let close = Some(started + Duration::hours(9));
+        assert!(!report_due(output_dir, date, close, started + Duration::hours(10), false));
+        std::fs::remove_file(&path).unwrap();
+        assert!(report_due(output_dir, date, close, started + Duration::hours(10), false));
+        assert!(!report_due(output_dir, date, close, started + Duration::hours(10), true));
+        assert!(!report_due(output_dir, date, close, started + Duration::hours(8), false));
+
+        let cipher = Cipher::from_key(&[9; 32]);
+        let sealed = write_daily_report(output_dir, date, Some(&cipher), &FeeModel::default()).unwrap();
+        assert!(std::fs::read_to_string(&sealed).is_err());
+        assert!(storage::read_file(&path, Some(&cipher)).is_ok());
+        assert!(!report_due(output_dir, date, close, started + Duration::hours(10), false));
+    }
+}
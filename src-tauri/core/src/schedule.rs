@@ -0,0 +1,349 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+use crate::clock::Clock;
+
+/// KST (UTC+9, 서머타임 없음)
+const KST_OFFSET_SECS: i32 = 9 * 3600;
+
+/// 다음 개장 시각을 찾을 때 살펴볼 최대 일수 (연휴 포함)
+const NEXT_OPEN_SEARCH_DAYS: i64 = 30;
+
+/// 한국 표준시
+pub fn kst() -> FixedOffset {
+    FixedOffset::east_opt(KST_OFFSET_SECS).unwrap()
+}
+
+/// KRX 거래 시간대
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketSession {
+    /// 장 시작 동시호가 (08:30~09:00)
+    PreMarket,
+    /// 정규장 (09:00~15:30, 장 마감 동시호가 포함)
+    Regular,
+    /// 장후 시간외 종가 (15:40~16:00)
+    AfterHoursClosing,
+    /// 시간외 단일가 (16:00~18:00)
+    AfterHoursSinglePrice,
+}
+
+impl MarketSession {
+    pub const ALL: [MarketSession; 4] = [
+        MarketSession::PreMarket,
+        MarketSession::Regular,
+        MarketSession::AfterHoursClosing,
+        MarketSession::AfterHoursSinglePrice,
+    ];
+
+    /// KST 기준 시작/종료 시각 (종료 시각은 포함하지 않음)
+    pub fn hours(&self) -> (NaiveTime, NaiveTime) {
+        let hm = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        match self {
+            MarketSession::PreMarket => (hm(8, 30), hm(9, 0)),
+            MarketSession::Regular => (hm(9, 0), hm(15, 30)),
+            MarketSession::AfterHoursClosing => (hm(15, 40), hm(16, 0)),
+            MarketSession::AfterHoursSinglePrice => (hm(16, 0), hm(18, 0)),
+        }
+    }
+}
+
+/// 장 시간 기반 자동 녹화 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// 끄면 HTS 감지만으로 자동 녹화 (기존 동작)
+    pub enabled: bool,
+    /// 자동 녹화를 허용할 시간대
+    pub sessions: Vec<MarketSession>,
+    /// 시간대 시작 몇 분 전부터 녹화 허용
+    #[serde(default)]
+    pub lead_minutes: u32,
+    /// 시간대 종료 후 몇 분까지 녹화 유지
+    #[serde(default)]
+    pub lag_minutes: u32,
+    /// 휴장일 파일 (한 줄에 `YYYY-MM-DD`, `#` 뒤는 주석)
+    #[serde(default)]
+    pub holiday_file: Option<String>,
+}
+
+/// 기본 휴장일 파일 (저장소에 함께 있는 KRX 휴장일 목록)
+pub const DEFAULT_HOLIDAY_FILE: &str = "config/krx_holidays.txt";
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sessions: vec![MarketSession::PreMarket, MarketSession::Regular],
+            lead_minutes: 5,
+            lag_minutes: 5,
+            holiday_file: Some(DEFAULT_HOLIDAY_FILE.to_string()),
+        }
+    }
+}
+
+/// 휴장일 목록
+#[derive(Debug, Clone, Default)]
+pub struct HolidayCalendar {
+    dates: BTreeSet<NaiveDate>,
+}
+
+impl HolidayCalendar {
+    pub fn new(dates: impl IntoIterator<Item = NaiveDate>) -> Self {
+        Self {
+            dates: dates.into_iter().collect(),
+        }
+    }
+
+    /// 휴장일 파일 파싱
+    ///
+    /// ```text
+    /// # 2026년 KRX 휴장일
+    /// 2026-01-01 신정
+    /// 2026-02-16 설날
+    /// ```
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut dates = BTreeSet::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some(date) = line.split_whitespace().next() else {
+                continue;
+            };
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid holiday on line {}: {}", i + 1, e))?;
+            dates.insert(date);
+        }
+
+        Ok(Self { dates })
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read holiday file: {}", e))?;
+        Self::parse(&content)
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.dates.contains(&date)
+    }
+
+    pub fn len(&self) -> usize {
+        self.dates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dates.is_empty()
+    }
+}
+
+/// UI 표시용 스케줄 상태
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleStatus {
+    pub enabled: bool,
+    /// 지금 자동 녹화가 허용되는지 (스케줄이 꺼져 있으면 항상 true)
+    pub open: bool,
+    pub session: Option<MarketSession>,
+    pub next_open: Option<DateTime<Utc>>,
+}
+
+/// KRX 장 시간 스케줄
+pub struct MarketSchedule {
+    config: ScheduleConfig,
+    holidays: HolidayCalendar,
+    clock: Arc<dyn Clock>,
+}
+
+impl MarketSchedule {
+    pub fn new(config: ScheduleConfig, holidays: HolidayCalendar, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            config,
+            holidays,
+            clock,
+        }
+    }
+
+    /// 설정의 휴장일 파일까지 읽어서 생성
+    ///
+    /// 기본 휴장일 파일이 없으면 (다른 작업 디렉토리에서 실행) 경고만 하고 주말만 쉬는 것으로 본다.
+    pub fn load(config: &ScheduleConfig, clock: Arc<dyn Clock>) -> Result<Self, String> {
+        let holidays = match &config.holiday_file {
+            Some(path) if path == DEFAULT_HOLIDAY_FILE && !Path::new(path).exists() => {
+                eprintln!("Holiday file not found: {}", path);
+                HolidayCalendar::default()
+            }
+            Some(path) => HolidayCalendar::load(path)?,
+            None => HolidayCalendar::default(),
+        };
+        Ok(Self::new(config.clone(), holidays, clock))
+    }
+
+    pub fn config(&self) -> &ScheduleConfig {
+        &self.config
+    }
+
+    /// 거래일 여부 (주말, 휴장일 제외)
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(date)
+    }
+
+    /// 해당 날짜 시간대의 녹화 구간 (앞뒤 여유 포함, UTC)
+    fn window(&self, date: NaiveDate, session: MarketSession) -> (DateTime<Utc>, DateTime<Utc>) {
+        let (start, end) = session.hours();
+        let to_utc = |time: NaiveTime| {
+            kst()
+                .from_local_datetime(&date.and_time(time))
+                .unwrap()
+                .with_timezone(&Utc)
+        };
+
+        (
+            to_utc(start) - Duration::minutes(self.config.lead_minutes as i64),
+            to_utc(end) + Duration::minutes(self.config.lag_minutes as i64),
+        )
+    }
+
+    /// `at` 시각이 속한 설정된 시간대
+    pub fn session_at(&self, at: DateTime<Utc>) -> Option<MarketSession> {
+        let date = at.with_timezone(&kst()).date_naive();
+        if !self.is_trading_day(date) {
+            return None;
+        }
+
+        self.config.sessions.iter().copied().find(|&session| {
+            let (start, end) = self.window(date, session);
+            start <= at && at < end
+        })
+    }
+
+    /// 거래일의 마지막 설정 시간대가 끝나는 시각 (뒤 여유 포함, 거래일이 아니면 `None`)
+    pub fn day_close(&self, date: NaiveDate) -> Option<DateTime<Utc>> {
+        if !self.is_trading_day(date) {
+            return None;
+        }
+        self.config
+            .sessions
+            .iter()
+            .map(|&session| self.window(date, session).1)
+            .max()
+    }
+
+    pub fn current_session(&self) -> Option<MarketSession> {
+        self.session_at(self.clock.now())
+    }
+
+    /// 지금 자동 녹화가 허용되는지
+    pub fn is_open(&self) -> bool {
+        !self.config.enabled || self.current_session().is_some()
+    }
+
+    /// 다음 녹화 구간 시작 시각 (지금 열려 있으면 그 이후 구간)
+    pub fn next_open(&self) -> Option<DateTime<Utc>> {
+        let now = self.clock.now();
+        let today = now.with_timezone(&kst()).date_naive();
+
+        (0..NEXT_OPEN_SEARCH_DAYS)
+            .filter_map(|days| today.checked_add_signed(Duration::days(days)))
+            .filter(|&date| self.is_trading_day(date))
+            .flat_map(|date| {
+                self.config
+                    .sessions
+                    .iter()
+                    .map(move |&session| self.window(date, session).0)
+                    .collect::<Vec<_>>()
+            })
+            .filter(|&start| start > now)
+            .min()
+    }
+
+    pub fn status(&self) -> ScheduleStatus {
+        ScheduleStatus {
+            enabled: self.config.enabled,
+            open: self.is_open(),
+            session: self.current_session(),
+            next_open: if self.config.enabled { self.next_open() } else { None },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    fn kst_time(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        kst().with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn schedule_at(at: DateTime<Utc>, holidays: &str) -> MarketSchedule {
+        let config = ScheduleConfig {
+            enabled: true,
+            sessions: vec![MarketSession::Regular, MarketSession::AfterHoursSinglePrice],
+            lead_minutes: 5,
+            lag_minutes: 0,
+            holiday_file: None,
+        };
+        MarketSchedule::new(config, HolidayCalendar::parse(holidays).unwrap(), Arc::new(FixedClock(at)))
+    }
+
+    #[test]
+    fn test_session_windows() {
+        // 2026-10-19는 월요일
+        assert_eq!(schedule_at(kst_time(2026, 10, 19, 8, 54), "").current_session(), None);
+        assert_eq!(
+            schedule_at(kst_time(2026, 10, 19, 8, 55), "").current_session(),
+            Some(MarketSession::Regular)
+        );
+        assert_eq!(schedule_at(kst_time(2026, 10, 19, 15, 30), "").current_session(), None);
+        assert_eq!(
+            schedule_at(kst_time(2026, 10, 19, 17, 0), "").current_session(),
+            Some(MarketSession::AfterHoursSinglePrice)
+        );
+        assert!(!schedule_at(kst_time(2026, 10, 19, 20, 0), "").is_open());
+    }
+
+    #[test]
+    fn test_weekends_and_holidays_closed() {
+        assert!(!schedule_at(kst_time(2026, 10, 17, 10, 0), "").is_open());
+        assert!(!schedule_at(kst_time(2026, 10, 19, 10, 0), "2026-10-19 # 임시 휴장").is_open());
+    }
+
+    #[test]
+    fn test_next_open_skips_weekend_and_holiday() {
+        // 금요일 밤 → 월요일 휴장 → 화요일 08:55
+        let schedule = schedule_at(kst_time(2026, 10, 16, 20, 0), "2026-10-19");
+        assert_eq!(schedule.next_open(), Some(kst_time(2026, 10, 20, 8, 55)));
+    }
+
+    #[test]
+    fn test_day_close_is_last_session_end() {
+        let schedule = schedule_at(kst_time(2026, 10, 19, 10, 0), "");
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert_eq!(schedule.day_close(monday), Some(kst_time(2026, 10, 19, 18, 0)));
+        assert_eq!(schedule.day_close(monday - Duration::days(1)), None);
+    }
+
+    #[test]
+    fn test_disabled_schedule_always_open() {
+        let schedule = MarketSchedule::new(
+            ScheduleConfig::default(),
+            HolidayCalendar::default(),
+            Arc::new(FixedClock(kst_time(2026, 10, 18, 3, 0))),
+        );
+        assert!(schedule.is_open());
+    }
+
+    #[test]
+    fn test_invalid_holiday_line() {
+        assert!(HolidayCalendar::parse("2026-13-01").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use chrono::{Local, NaiveDateTime, TimeZone, DateTime, Utc};
+
+use crate::clock::ClockInfo;
+use crate::crypto::Cipher;
+use crate::integrity::IntegrityInfo;
+use crate::profiles::DetectedProfile;
+use crate::regions::RegionTrack;
+use crate::redaction::RedactionConfig;
+use crate::storage;
+use crate::tracks::{self, FrameRecord};
+
+/// 녹화 세션 디렉토리 접두어 (`recording_YYYYMMDD_HHMMSS`)
+pub const SESSION_DIR_PREFIX: &str = "recording_";
+
+/// 세션 디렉토리 안의 매니페스트 파일 이름
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// 녹화 종료 결과
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub path: String,
+    pub frame_count: usize,
+    pub duration_secs: u64,
+    pub bytes_written: u64,
+    /// 인코더가 밀려서 버린 프레임 수
+    pub dropped_frames: u64,
+}
+
+/// 세션 매니페스트 (`manifest.json`)
+///
+/// 녹화 시작 시 생성되고 녹화 종료 시 마무리된다. 녹화에 적용된 설정을
+/// 남겨두어 나중에 녹화본이 어떻게 만들어졌는지 확인할 수 있게 한다.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub version: u32,
+    pub id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub frame_count: usize,
+    /// 프레임에 적용된 가림 규칙 (비활성이면 None)
+    pub redaction: Option<RedactionConfig>,
+    /// 프레임/매니페스트가 암호화되어 저장됐는지
+    #[serde(default)]
+    pub encrypted: bool,
+    /// 해시 체인 요약 (녹화 종료 시 기록)
+    #[serde(default)]
+    pub integrity: Option<IntegrityInfo>,
+    /// 프레임 타임스탬프 기준점과 재설정 기록
+    #[serde(default)]
+    pub clock: Option<ClockInfo>,
+    /// 녹화 시작 시 감지된 HTS 프로필
+    #[serde(default)]
+    pub profile: Option<DetectedProfile>,
+    /// HTS 윈도우 영역별 프레임 트랙 (`regions/<id>/`)
+    #[serde(default)]
+    pub tracks: Vec<RegionTrack>,
+}
+
+impl SessionManifest {
+    pub fn new(id: &str, started_at: DateTime<Utc>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            id: id.to_string(),
+            started_at,
+            ended_at: None,
+            frame_count: 0,
+            redaction: None,
+            encrypted: false,
+            integrity: None,
+            clock: None,
+            profile: None,
+            tracks: Vec::new(),
+        }
+    }
+}
+
+pub fn write_manifest(
+    session_dir: &Path,
+    manifest: &SessionManifest,
+    cipher: Option<&Cipher>,
+) -> Result<(), String> {
+    let content = serde_json::to_vec_pretty(manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    storage::write_file(&session_dir.join(MANIFEST_FILE), &content, cipher)?;
+    Ok(())
+}
+
+pub fn read_manifest(session_dir: &Path, cipher: Option<&Cipher>) -> Result<SessionManifest, String> {
+    let content = storage::read_file(&session_dir.join(MANIFEST_FILE), cipher)?;
+    serde_json::from_slice(&content)
+        .map_err(|e| format!("Failed to parse manifest: {}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub path: String,
+    pub started_at: Option<DateTime<Local>>,
+    pub frame_count: usize,
+    pub total_bytes: u64,
+}
+
+/// 세션 디렉토리 이름에서 시작 시각 파싱
+fn parse_session_start(id: &str) -> Option<DateTime<Local>> {
+    let stamp = id.strip_prefix(SESSION_DIR_PREFIX)?;
+    let naive = NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S").ok()?;
+    Local.from_local_datetime(&naive).single()
+}
+
+/// 디렉토리 안 파일 크기 합 (하위 디렉토리 포함)
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            Some(if meta.is_dir() { dir_size(&entry.path()) } else { meta.len() })
+        })
+        .sum()
+}
+
+/// 세션 디렉토리 하나의 정보 읽기
+pub fn read_session(session_dir: &Path) -> Result<SessionInfo, String> {
+    let id = session_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid session path: {}", session_dir.display()))?;
+
+    let entries = std::fs::read_dir(session_dir)
+        .map_err(|e| format!("Failed to read session directory: {}", e))?;
+
+    let mut frame_count = 0;
+    let mut total_bytes = 0;
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Ok(meta) = entry.metadata() {
+            if meta.is_file() {
+                total_bytes += meta.len();
+            } else if meta.is_dir() {
+                // 영역 트랙 등 하위 디렉토리
+                total_bytes += dir_size(&entry.path());
+            }
+        }
+        if name.starts_with("frame_") {
+            frame_count += 1;
+        }
+    }
+
+    Ok(SessionInfo {
+        started_at: parse_session_start(&id),
+        path: session_dir.to_string_lossy().to_string(),
+        id,
+        frame_count,
+        total_bytes,
+    })
+}
+
+/// 출력 디렉토리의 녹화 세션 목록 (최신순)
+pub fn list_sessions(output_dir: &str) -> Result<Vec<SessionInfo>, String> {
+    let root = Path::new(output_dir);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = std::fs::read_dir(root)
+        .map_err(|e| format!("Failed to read output directory: {}", e))?;
+
+    let mut sessions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_session = path.is_dir()
+            && entry.file_name().to_string_lossy().starts_with(SESSION_DIR_PREFIX);
+        if is_session {
+            sessions.push(read_session(&path)?);
+        }
+    }
+
+    sessions.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(sessions)
+}
+
+/// 세션 ID로 세션 디렉토리 경로 찾기
+pub fn session_path(output_dir: &str, session_id: &str) -> Result<PathBuf, String> {
+    let path = Path::new(output_dir).join(session_id);
+    if !path.is_dir() {
+        return Err(format!("Session '{}' not found", session_id));
+    }
+    Ok(path)
+}
+
+/// 세션 타임라인 위의 위치
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRef {
+    pub session_id: String,
+    /// 세션 시작부터의 오프셋 (ms, 프레임 트랙과 같은 기준)
+    pub offset_ms: u64,
+    /// 가장 가까운 전체 화면 프레임 번호
+    pub frame_index: Option<usize>,
+}
+
+/// 종료 기록이 없는 세션은 마지막 프레임 뒤로 이만큼까지만 세션으로 봄
+const OPEN_SESSION_SLACK_SECS: i64 = 5;
+
+/// 세션 시작/종료와 프레임 캡처 시각 (외부 시각을 세션 위치로 바꿀 때 사용)
+#[derive(Debug, Clone)]
+pub struct SessionTimeline {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    /// 캡처 시각 순
+    pub frames: Vec<FrameRecord>,
+}
+
+impl SessionTimeline {
+    pub fn load(session_dir: &Path, cipher: Option<&Cipher>) -> Result<Self, String> {
+        let manifest = read_manifest(session_dir, cipher)?;
+        let mut frames: Vec<FrameRecord> = tracks::read_track(&session_dir.join(tracks::FRAMES_TRACK), cipher)?;
+        frames.sort_by_key(|f| f.captured_at);
+
+        Ok(Self {
+            session_id: manifest.id,
+            started_at: manifest.started_at,
+            ended_at: manifest.ended_at,
+            frames,
+        })
+    }
+
+    /// `at`이 세션 안이면 세션 오프셋과 가장 가까운 프레임
+    ///
+    /// 오프셋은 가장 가까운 프레임의 단조 시계 오프셋에서 계산하므로
+    /// 녹화 중 시스템 시계가 보정돼도 프레임 트랙과 어긋나지 않는다.
+    pub fn locate(&self, at: DateTime<Utc>) -> Option<SessionRef> {
+        let end = self.ended_at.or_else(|| {
+            self.frames
+                .last()
+                .map(|f| f.captured_at + chrono::Duration::seconds(OPEN_SESSION_SLACK_SECS))
+        });
+        if at < self.started_at || end.is_some_and(|end| at > end) {
+            return None;
+        }
+
+        let next = self.frames.partition_point(|f| f.captured_at <= at);
+        let nearest = [next.checked_sub(1), Some(next)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.frames.get(i))
+            .min_by_key(|f| (f.captured_at - at).num_milliseconds().abs());
+
+        let offset_ms = match nearest {
+            Some(frame) => {
+                let delta = (at - frame.captured_at).num_milliseconds();
+                (frame.offset_ms as i64 + delta).max(0) as u64
+            }
+            None => (at - self.started_at).num_milliseconds().max(0) as u64,
+        };
+
+        Some(SessionRef {
+            session_id: self.session_id.clone(),
+            offset_ms,
+            frame_index: nearest.map(|f| f.index),
+        })
+    }
+}
+
+/// 출력 디렉토리의 모든 세션 타임라인 (읽을 수 없는 세션은 건너뜀)
+pub fn load_timelines(output_dir: &str, cipher: Option<&Cipher>) -> Result<Vec<SessionTimeline>, String> {
+    let mut timelines = Vec::new();
+
+    for info in list_sessions(output_dir)? {
+        match SessionTimeline::load(Path::new(&info.path), cipher) {
+            Ok(timeline) => timelines.push(timeline),
+            Err(e) => eprintln!("Skipping session {}: {}", info.id, e),
+        }
+    }
+
+    Ok(timelines)
+}
+
+/// 여러 세션 중 `at`이 속한 세션 위치 찾기
+pub fn locate_in_sessions(timelines: &[SessionTimeline], at: DateTime<Utc>) -> Option<SessionRef> {
+    timelines.iter().find_map(|t| t.locate(at))
+}
+
+/// 세션 디렉토리를 통째로 다른 위치로 내보내기
+///
+/// 암호화된 파일은 `cipher`로 복호화해서 원래 이름으로 내보낸다.
+pub fn export_session(
+    output_dir: &str,
+    session_id: &str,
+    dest_dir: &str,
+    cipher: Option<&Cipher>,
+) -> Result<String, String> {
+    let source = session_path(output_dir, session_id)?;
+    let target = Path::new(dest_dir).join(session_id);
+
+    copy_decrypted(&source, &target, cipher)?;
+
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// 디렉토리를 복호화하면서 복사 (영역 트랙 하위 디렉토리 포함)
+fn copy_decrypted(source: &Path, target: &Path, cipher: Option<&Cipher>) -> Result<(), String> {
+    std::fs::create_dir_all(target)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let entries = std::fs::read_dir(source)
+        .map_err(|e| format!("Failed to read session directory: {}", e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            copy_decrypted(&path, &target.join(&name), cipher)?;
+        } else if path.is_file() {
+            let data = storage::read_file(&path, cipher)?;
+            std::fs::write(target.join(storage::plain_file_name(&name)), data)
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+        }
+    }
+
+    Ok(())
+}
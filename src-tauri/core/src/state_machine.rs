@@ -0,0 +1,386 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use chrono::{DateTime, Utc};
+
+/// 보관할 최근 전이 기록 수
+const HISTORY_LIMIT: usize = 100;
+
+/// 녹화 상태
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum RecorderState {
+    /// 자동 모드 꺼짐, 녹화 안 함
+    Idle,
+    /// 자동 모드 켜짐, HTS 감지 대기
+    Armed,
+    Recording,
+    Paused,
+    /// 녹화 중지 후 남은 프레임 기록/매니페스트 마무리 중
+    Finalizing,
+    Error { message: String },
+}
+
+/// 상태 전이 원인
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransitionReason {
+    Manual,
+    HtsDetected,
+    HtsExited,
+    Schedule,
+    DiskFull,
+}
+
+/// 현재 녹화를 누가 시작했는지
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingOrigin {
+    Manual,
+    Auto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateTransition {
+    pub from: RecorderState,
+    pub to: RecorderState,
+    pub reason: TransitionReason,
+    pub at: DateTime<Utc>,
+}
+
+/// 상태 머신 입력
+///
+/// HTS/스케줄 이벤트는 감시 주기마다 현재 상태를 그대로 넣어도 된다 (반복 입력은 무시됨).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecorderEvent {
+    ManualStart,
+    ManualStop,
+    ManualPause,
+    ManualResume,
+    SetAutoMode(bool),
+    HtsDetected,
+    HtsExited,
+    /// 장 시간 스케줄이 자동 녹화를 허용함
+    ScheduleOpened,
+    ScheduleClosed,
+    DiskFull,
+}
+
+/// 상태 머신이 요청하는 레코더 동작
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecorderAction {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+}
+
+/// 녹화 상태 머신
+///
+/// 수동/자동 제어 규칙:
+/// - 자동 녹화는 Armed 상태에서 HTS가 감지되고 스케줄이 열려 있을 때만 시작한다.
+/// - 자동 녹화는 HTS가 종료되거나 스케줄이 닫히면 멈춘다.
+/// - 수동으로 시작한 녹화는 HTS 종료/스케줄에 영향받지 않는다 (수동 중지 또는 디스크 부족만).
+/// - 자동 녹화를 수동으로 중지하면 HTS가 종료되거나 스케줄이 닫힐 때까지 자동 시작을 보류한다.
+/// - Error 상태에서는 자동으로 재시작하지 않는다 (수동 시작 또는 자동 모드 재설정 필요).
+pub struct RecordingStateMachine {
+    state: RecorderState,
+    origin: Option<RecordingOrigin>,
+    auto_mode: bool,
+    hts_running: bool,
+    /// 스케줄을 쓰지 않으면 항상 true
+    schedule_open: bool,
+    auto_suppressed: bool,
+    /// 진행 중인 시작/중지의 원인
+    pending_reason: TransitionReason,
+    pending: Vec<StateTransition>,
+    history: VecDeque<StateTransition>,
+}
+
+impl RecordingStateMachine {
+    pub fn new(auto_mode: bool) -> Self {
+        Self {
+            state: if auto_mode { RecorderState::Armed } else { RecorderState::Idle },
+            origin: None,
+            auto_mode,
+            hts_running: false,
+            schedule_open: true,
+            auto_suppressed: false,
+            pending_reason: TransitionReason::Manual,
+            pending: Vec::new(),
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn state(&self) -> &RecorderState {
+        &self.state
+    }
+
+    pub fn origin(&self) -> Option<RecordingOrigin> {
+        self.origin
+    }
+
+    pub fn auto_mode(&self) -> bool {
+        self.auto_mode
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, RecorderState::Recording | RecorderState::Paused)
+    }
+
+    pub fn history(&self) -> impl Iterator<Item = &StateTransition> {
+        self.history.iter()
+    }
+
+    /// 아직 UI로 보내지 않은 전이 기록 꺼내기
+    pub fn take_transitions(&mut self) -> Vec<StateTransition> {
+        std::mem::take(&mut self.pending)
+    }
+
+    fn transition(&mut self, to: RecorderState, reason: TransitionReason) {
+        if self.state == to {
+            return;
+        }
+
+        let transition = StateTransition {
+            from: self.state.clone(),
+            to: to.clone(),
+            reason,
+            at: Utc::now(),
+        };
+        self.state = to;
+
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(transition.clone());
+        self.pending.push(transition);
+    }
+
+    fn begin_recording(&mut self, origin: RecordingOrigin, reason: TransitionReason) -> RecorderAction {
+        self.origin = Some(origin);
+        self.pending_reason = reason;
+        self.transition(RecorderState::Recording, reason);
+        RecorderAction::Start
+    }
+
+    fn can_auto_start(&self) -> bool {
+        self.state == RecorderState::Armed && self.hts_running && self.schedule_open && !self.auto_suppressed
+    }
+
+    fn stop_auto_recording(&mut self, reason: TransitionReason) -> Option<RecorderAction> {
+        if self.is_active() && self.origin == Some(RecordingOrigin::Auto) {
+            Some(self.begin_stop(reason))
+        } else {
+            None
+        }
+    }
+
+    fn begin_stop(&mut self, reason: TransitionReason) -> RecorderAction {
+        self.pending_reason = reason;
+        self.transition(RecorderState::Finalizing, reason);
+        RecorderAction::Stop
+    }
+
+    /// 이벤트 처리. 레코더가 해야 할 동작이 있으면 돌려준다.
+    ///
+    /// 수동 명령이 현재 상태에서 불가능하면 Err.
+    pub fn handle(&mut self, event: RecorderEvent) -> Result<Option<RecorderAction>, String> {
+        use RecorderState as S;
+
+        let action = match event {
+            RecorderEvent::HtsDetected => {
+                self.hts_running = true;
+                self.can_auto_start()
+                    .then(|| self.begin_recording(RecordingOrigin::Auto, TransitionReason::HtsDetected))
+            }
+            RecorderEvent::HtsExited => {
+                self.hts_running = false;
+                self.auto_suppressed = false;
+                self.stop_auto_recording(TransitionReason::HtsExited)
+            }
+            RecorderEvent::ScheduleOpened => {
+                self.schedule_open = true;
+                self.can_auto_start()
+                    .then(|| self.begin_recording(RecordingOrigin::Auto, TransitionReason::Schedule))
+            }
+            RecorderEvent::ScheduleClosed => {
+                self.schedule_open = false;
+                self.auto_suppressed = false;
+                self.stop_auto_recording(TransitionReason::Schedule)
+            }
+            RecorderEvent::ManualStart => match self.state {
+                S::Idle | S::Armed | S::Error { .. } => {
+                    Some(self.begin_recording(RecordingOrigin::Manual, TransitionReason::Manual))
+                }
+                S::Recording | S::Paused => return Err("Already recording".to_string()),
+                S::Finalizing => return Err("Previous recording is still being finalized".to_string()),
+            },
+            RecorderEvent::ManualStop => {
+                if !self.is_active() {
+                    return Err("Not recording".to_string());
+                }
+                // 자동 녹화를 사용자가 끈 경우 HTS가 켜져 있는 동안 다시 시작하지 않음
+                if self.origin == Some(RecordingOrigin::Auto) && self.hts_running {
+                    self.auto_suppressed = true;
+                }
+                Some(self.begin_stop(TransitionReason::Manual))
+            }
+            RecorderEvent::ManualPause => match self.state {
+                S::Recording => {
+                    self.transition(S::Paused, TransitionReason::Manual);
+                    Some(RecorderAction::Pause)
+                }
+                _ => return Err("Not recording".to_string()),
+            },
+            RecorderEvent::ManualResume => match self.state {
+                S::Paused => {
+                    self.transition(S::Recording, TransitionReason::Manual);
+                    Some(RecorderAction::Resume)
+                }
+                _ => return Err("Not paused".to_string()),
+            },
+            RecorderEvent::SetAutoMode(enabled) => {
+                self.auto_mode = enabled;
+                match self.state {
+                    S::Idle | S::Error { .. } if enabled => self.transition(S::Armed, TransitionReason::Manual),
+                    S::Armed if !enabled => self.transition(S::Idle, TransitionReason::Manual),
+                    _ => {}
+                }
+                None
+            }
+            RecorderEvent::DiskFull => {
+                if self.is_active() {
+                    Some(self.begin_stop(TransitionReason::DiskFull))
+                } else {
+                    None
+                }
+            }
+        };
+
+        Ok(action)
+    }
+
+    /// 레코더 시작 실패 보고
+    pub fn start_failed(&mut self, message: &str) {
+        if self.state == RecorderState::Recording {
+            self.origin = None;
+            let reason = self.pending_reason;
+            self.transition(RecorderState::Error { message: message.to_string() }, reason);
+        }
+    }
+
+    /// 레코더 중지(마무리) 완료 보고
+    pub fn stop_completed(&mut self) {
+        if self.state != RecorderState::Finalizing {
+            return;
+        }
+
+        self.origin = None;
+        let reason = self.pending_reason;
+        let next = if reason == TransitionReason::DiskFull {
+            RecorderState::Error { message: "Disk full".to_string() }
+        } else if self.auto_mode {
+            RecorderState::Armed
+        } else {
+            RecorderState::Idle
+        };
+        self.transition(next, reason);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reasons(machine: &mut RecordingStateMachine) -> Vec<(RecorderState, TransitionReason)> {
+        machine
+            .take_transitions()
+            .into_iter()
+            .map(|t| (t.to, t.reason))
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_recording_follows_hts() {
+        let mut machine = RecordingStateMachine::new(true);
+
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), Some(RecorderAction::Start));
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), None);
+        assert_eq!(machine.handle(RecorderEvent::HtsExited).unwrap(), Some(RecorderAction::Stop));
+        machine.stop_completed();
+
+        assert_eq!(
+            reasons(&mut machine),
+            vec![
+                (RecorderState::Recording, TransitionReason::HtsDetected),
+                (RecorderState::Finalizing, TransitionReason::HtsExited),
+                (RecorderState::Armed, TransitionReason::HtsExited),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manual_recording_survives_hts_exit() {
+        let mut machine = RecordingStateMachine::new(true);
+
+        machine.handle(RecorderEvent::ManualStart).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), None);
+        assert_eq!(machine.handle(RecorderEvent::HtsExited).unwrap(), None);
+        assert_eq!(machine.state(), &RecorderState::Recording);
+    }
+
+    #[test]
+    fn test_manual_stop_suppresses_auto_restart_until_hts_exits() {
+        let mut machine = RecordingStateMachine::new(true);
+
+        machine.handle(RecorderEvent::HtsDetected).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::ManualStop).unwrap(), Some(RecorderAction::Stop));
+        machine.stop_completed();
+
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), None);
+        assert_eq!(machine.state(), &RecorderState::Armed);
+
+        machine.handle(RecorderEvent::HtsExited).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), Some(RecorderAction::Start));
+    }
+
+    #[test]
+    fn test_disk_full_ends_in_error_without_auto_restart() {
+        let mut machine = RecordingStateMachine::new(true);
+
+        machine.handle(RecorderEvent::HtsDetected).unwrap();
+        machine.handle(RecorderEvent::ManualPause).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::DiskFull).unwrap(), Some(RecorderAction::Stop));
+        machine.stop_completed();
+
+        assert!(matches!(machine.state(), RecorderState::Error { .. }));
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), None);
+        assert_eq!(machine.handle(RecorderEvent::ManualStart).unwrap(), Some(RecorderAction::Start));
+    }
+
+    #[test]
+    fn test_schedule_gates_auto_recording() {
+        let mut machine = RecordingStateMachine::new(true);
+
+        machine.handle(RecorderEvent::ScheduleClosed).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::HtsDetected).unwrap(), None);
+        assert_eq!(machine.handle(RecorderEvent::ScheduleOpened).unwrap(), Some(RecorderAction::Start));
+        assert_eq!(machine.handle(RecorderEvent::ScheduleClosed).unwrap(), Some(RecorderAction::Stop));
+        machine.stop_completed();
+
+        // 수동 녹화는 스케줄 밖에서도 유지
+        machine.handle(RecorderEvent::ManualStart).unwrap();
+        assert_eq!(machine.handle(RecorderEvent::ScheduleClosed).unwrap(), None);
+        assert_eq!(machine.state(), &RecorderState::Recording);
+    }
+
+    #[test]
+    fn test_invalid_manual_commands_rejected() {
+        let mut machine = RecordingStateMachine::new(false);
+
+        assert!(machine.handle(RecorderEvent::ManualStop).is_err());
+        assert!(machine.handle(RecorderEvent::ManualResume).is_err());
+        machine.handle(RecorderEvent::ManualStart).unwrap();
+        assert!(machine.handle(RecorderEvent::ManualStart).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use crate::crypto::{self, Cipher};
+use crate::integrity;
+use crate::session;
+
+/// 암호화된 파일에 붙는 확장자 (`frame_000001.png.enc`)
+pub const ENCRYPTED_EXT: &str = "enc";
+
+/// 암호화해서 저장할 때의 경로 (`<path>.enc`)
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ENCRYPTED_EXT);
+    PathBuf::from(name)
+}
+
+/// 디스크에 쓴 파일 정보
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// 디스크에 쓴 바이트(암호화된 경우 암호문)의 SHA-256
+    pub sha256: String,
+}
+
+/// 녹화 파일 쓰기
+///
+/// 암호화 키가 있으면 암호화해서 `<path>.enc`로 저장한다.
+pub fn write_file(path: &Path, data: &[u8], cipher: Option<&Cipher>) -> Result<StoredFile, String> {
+    let (target, bytes) = match cipher {
+        Some(cipher) => (encrypted_path(path), cipher.encrypt(data)?),
+        None => (path.to_path_buf(), data.to_vec()),
+    };
+
+    std::fs::write(&target, &bytes)
+        .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+    Ok(StoredFile {
+        path: target,
+        size: bytes.len() as u64,
+        sha256: integrity::sha256_hex(&bytes),
+    })
+}
+
+/// 녹화 파일 읽기
+///
+/// `path`가 없고 `<path>.enc`가 있으면 그쪽을 읽는다. 암호화된 내용이면 복호화한다.
+/// 키가 있는데 암호화된 세션 안의 파일이 평문이면 바꿔치기된 것으로 보고 거부한다.
+pub fn read_file(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<u8>, String> {
+    let source = if !path.exists() && encrypted_path(path).exists() {
+        encrypted_path(path)
+    } else {
+        path.to_path_buf()
+    };
+
+    let data = std::fs::read(&source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    if crypto::is_encrypted(&data) {
+        match cipher {
+            Some(cipher) => cipher.decrypt(&data),
+            None => Err("Recordings are encrypted: passphrase required".to_string()),
+        }
+    } else if cipher.is_some() && in_encrypted_session(path) {
+        Err(format!("Unencrypted file in encrypted session: {}", source.display()))
+    } else {
+        Ok(data)
+    }
+}
+
+/// `path`를 담고 있는 세션의 매니페스트가 암호화 세션이라고 하는지
+///
+/// 가장 가까운 상위 디렉토리의 매니페스트를 본다. 매니페스트가 암호화돼 있으면 암호화 세션이다.
+fn in_encrypted_session(path: &Path) -> bool {
+    for dir in path.ancestors().skip(1) {
+        let manifest = dir.join(session::MANIFEST_FILE);
+        if encrypted_path(&manifest).exists() {
+            return true;
+        }
+        if let Ok(content) = std::fs::read(&manifest) {
+            return serde_json::from_slice::<serde_json::Value>(&content)
+                .map(|value| value["encrypted"].as_bool().unwrap_or(false))
+                .unwrap_or(false);
+        }
+    }
+    false
+}
+
+/// 암호화 확장자를 뗀 원래 파일 이름
+pub fn plain_file_name(name: &str) -> &str {
+    name.strip_suffix(".enc").unwrap_or(name)
+}
+
+/// `path`가 있는 디스크의 남은 용량 (바이트). 디스크를 찾지 못하면 None.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+
+    // 가장 긴 마운트 경로가 실제로 파일이 있는 디스크
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plaintext_rejected_in_encrypted_session() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let cipher = Cipher::from_key(&[7; 32]);
+
+        let frame = dir.join("frame_000001.png");
+        std::fs::write(&frame, b"plain").unwrap();
+        // 세션 밖이거나 평문 세션이면 평문 파일도 읽음
+        assert_eq!(read_file(&frame, Some(&cipher)).unwrap(), b"plain");
+
+        write_file(&dir.join(session::MANIFEST_FILE), b"{}", Some(&cipher)).unwrap();
+        assert!(read_file(&frame, Some(&cipher)).is_err());
+        assert_eq!(read_file(&frame, None).unwrap(), b"plain");
+
+        write_file(&dir.join("frame_000002.png"), b"sealed", Some(&cipher)).unwrap();
+        assert_eq!(read_file(&dir.join("frame_000002.png"), Some(&cipher)).unwrap(), b"sealed");
+    }
+}
@@ -0,0 +1,356 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use xcap::image::{self, imageops, DynamicImage, RgbImage};
+
+use crate::crypto::Cipher;
+use crate::export::SCREEN_TRACK;
+use crate::session;
+use crate::storage;
+use crate::tracks::{self, FrameRecord};
+
+/// 트랙 디렉토리 안의 썸네일 폴더 (`thumbs/`)
+pub const THUMBS_DIR: &str = "thumbs";
+
+/// 스프라이트 시트 위치를 적은 WebVTT 파일 이름
+pub const VTT_FILE: &str = "thumbnails.vtt";
+
+/// 세션 디렉토리의 썸네일 목록 파일 (`thumbs/index.json`)
+pub const INDEX_FILE: &str = "index.json";
+
+/// 시트 한 장에 넣는 최대 행 수 (한 시간짜리 세션도 시트가 너무 커지지 않도록 나눔)
+const ROWS_PER_SHEET: u32 = 10;
+
+/// 스크러빙용 썸네일 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailConfig {
+    /// 녹화가 끝나면 썸네일/스프라이트 시트 자동 생성
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 썸네일 간격 (초)
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u32,
+    /// 썸네일 너비 (px, 높이는 첫 프레임 비율로 계산)
+    #[serde(default = "default_width")]
+    pub width: u32,
+    /// 스프라이트 시트 열 수
+    #[serde(default = "default_columns")]
+    pub columns: u32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval_secs() -> u32 {
+    10
+}
+
+fn default_width() -> u32 {
+    160
+}
+
+fn default_columns() -> u32 {
+    10
+}
+
+impl Default for ThumbnailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_secs: default_interval_secs(),
+            width: default_width(),
+            columns: default_columns(),
+        }
+    }
+}
+
+/// 트랙 하나의 썸네일 (타임라인 미리보기용)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailTrack {
+    /// 전체 화면은 `screen`, 영역 트랙은 영역 ID
+    pub track: String,
+    /// 세션 디렉토리 기준 WebVTT 경로 (`thumbs/thumbnails.vtt`, `regions/<id>/thumbs/thumbnails.vtt`)
+    pub vtt: String,
+    /// 세션 디렉토리 기준 스프라이트 시트 경로
+    pub sheets: Vec<String>,
+    pub count: usize,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub interval_ms: u64,
+}
+
+/// WebVTT 시각 (`HH:MM:SS.mmm`)
+fn vtt_time(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+fn sheet_name(index: usize) -> String {
+    format!("sprite_{:03}.jpg", index)
+}
+
+/// 채운 타일 수에 맞춰 시트 아래쪽 빈 행을 잘라서 JPEG로 저장
+fn write_sheet(
+    dir: &Path,
+    index: usize,
+    sheet: &RgbImage,
+    tiles: usize,
+    config: &ThumbnailConfig,
+    tile_height: u32,
+    cipher: Option<&Cipher>,
+) -> Result<(), String> {
+    let rows = (tiles as u32).div_ceil(config.columns);
+    let used = imageops::crop_imm(sheet, 0, 0, sheet.width(), rows * tile_height).to_image();
+
+    let mut buffer = Vec::new();
+    DynamicImage::ImageRgb8(used)
+        .write_to(&mut std::io::Cursor::new(&mut buffer), image::ImageFormat::Jpeg)
+        .map_err(|e| format!("Failed to encode sprite sheet: {}", e))?;
+    storage::write_file(&dir.join(sheet_name(index)), &buffer, cipher)?;
+    Ok(())
+}
+
+/// 트랙 하나의 썸네일, 스프라이트 시트, WebVTT 생성
+///
+/// `track_dir`는 세션 디렉토리 기준 트랙 디렉토리 (전체 화면은 빈 문자열).
+/// 읽을 수 없는 프레임은 건너뛰고 그 다음 프레임을 쓴다. 읽을 수 있는 프레임이 없으면 `None`.
+pub fn generate_track(
+    session_dir: &Path,
+    track: &str,
+    track_dir: &str,
+    frames: &[FrameRecord],
+    config: &ThumbnailConfig,
+    cipher: Option<&Cipher>,
+) -> Result<Option<ThumbnailTrack>, String> {
+    if config.interval_secs == 0 || config.width == 0 || config.columns == 0 {
+        return Err("Thumbnail interval, width and columns must be positive".to_string());
+    }
+    let interval_ms = config.interval_secs as u64 * 1000;
+    let relative = |name: &str| {
+        if track_dir.is_empty() {
+            format!("{}/{}", THUMBS_DIR, name)
+        } else {
+            format!("{}/{}/{}", track_dir, THUMBS_DIR, name)
+        }
+    };
+
+    // 이전 결과가 남아 있으면 시트 수가 달라질 수 있으므로 지우고 새로 만듦
+    let dir = session_dir.join(track_dir).join(THUMBS_DIR);
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear thumbnails: {}", e))?;
+    }
+
+    let tiles_per_sheet = (config.columns * ROWS_PER_SHEET) as usize;
+    let mut tile_height = 0;
+    let mut sheet: Option<RgbImage> = None;
+    let mut sheets = Vec::new();
+    // (시작 ms, 시트 번호, 시트 안 위치)
+    let mut cues: Vec<(u64, usize, u32, u32)> = Vec::new();
+    let mut last_offset = 0;
+
+    let mut frames: Vec<&FrameRecord> = frames.iter().collect();
+    frames.sort_by_key(|f| f.offset_ms);
+
+    // 간격마다 읽을 수 있는 첫 프레임 하나
+    let mut next_slot = 0;
+    for frame in frames {
+        if frame.offset_ms < next_slot {
+            continue;
+        }
+        let decoded = storage::read_file(&session_dir.join(&frame.file), cipher)
+            .and_then(|data| image::load_from_memory(&data).map_err(|e| format!("Failed to decode image: {}", e)));
+        let image = match decoded {
+            Ok(image) => image,
+            Err(e) => {
+                eprintln!("Thumbnail skipped ({}): {}", frame.file, e);
+                continue;
+            }
+        };
+        next_slot = (frame.offset_ms / interval_ms + 1) * interval_ms;
+
+        if tile_height == 0 {
+            let ratio = image.height() as f64 / image.width().max(1) as f64;
+            tile_height = ((config.width as f64 * ratio).round() as u32).max(1);
+            std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+        }
+
+        let slot = cues.len() % tiles_per_sheet;
+        let sheet_index = cues.len() / tiles_per_sheet;
+        if slot == 0 {
+            if let Some(full) = sheet.take() {
+                write_sheet(&dir, sheet_index - 1, &full, tiles_per_sheet, config, tile_height, cipher)?;
+            }
+            sheet = Some(RgbImage::new(config.width * config.columns, tile_height * ROWS_PER_SHEET));
+            sheets.push(relative(&sheet_name(sheet_index)));
+        }
+
+        // 창 크기가 바뀐 프레임은 비율을 유지해서 타일 가운데에 둠
+        let thumbnail = image.thumbnail(config.width, tile_height).to_rgb8();
+        let x = (slot as u32 % config.columns) * config.width;
+        let y = (slot as u32 / config.columns) * tile_height;
+        if let Some(sheet) = sheet.as_mut() {
+            let dx = (config.width - thumbnail.width()) / 2;
+            let dy = (tile_height - thumbnail.height()) / 2;
+            imageops::replace(sheet, &thumbnail, (x + dx) as i64, (y + dy) as i64);
+        }
+
+        let start = if cues.is_empty() { 0 } else { frame.offset_ms };
+        cues.push((start, sheet_index, x, y));
+        last_offset = frame.offset_ms;
+    }
+
+    if cues.is_empty() {
+        return Ok(None);
+    }
+    if let Some(last) = sheet.take() {
+        let tiles = (cues.len() - 1) % tiles_per_sheet + 1;
+        write_sheet(&dir, sheets.len() - 1, &last, tiles, config, tile_height, cipher)?;
+    }
+
+    // 각 구간은 다음 썸네일이 시작할 때까지 (마지막은 한 간격)
+    let mut vtt = String::from("WEBVTT\n");
+    for (i, &(start, sheet_index, x, y)) in cues.iter().enumerate() {
+        let end = cues.get(i + 1).map(|c| c.0).unwrap_or(last_offset + interval_ms).max(start + 1);
+        let _ = write!(
+            vtt,
+            "\n{} --> {}\n{}#xywh={},{},{},{}\n",
+            vtt_time(start),
+            vtt_time(end),
+            sheet_name(sheet_index),
+            x,
+            y,
+            config.width,
+            tile_height
+        );
+    }
+    storage::write_file(&dir.join(VTT_FILE), vtt.as_bytes(), cipher)?;
+
+    Ok(Some(ThumbnailTrack {
+        track: track.to_string(),
+        vtt: relative(VTT_FILE),
+        sheets,
+        count: cues.len(),
+        tile_width: config.width,
+        tile_height,
+        interval_ms,
+    }))
+}
+
+/// 세션의 전체 화면 트랙과 영역 트랙 썸네일을 만들고 목록을 `thumbs/index.json`에 저장
+pub fn generate_session(
+    session_dir: &Path,
+    config: &ThumbnailConfig,
+    cipher: Option<&Cipher>,
+) -> Result<Vec<ThumbnailTrack>, String> {
+    let manifest = session::read_manifest(session_dir, cipher)?;
+    let mut generated = Vec::new();
+
+    let frames: Vec<FrameRecord> = tracks::read_track(&session_dir.join(tracks::FRAMES_TRACK), cipher)?;
+    generated.extend(generate_track(session_dir, SCREEN_TRACK, "", &frames, config, cipher)?);
+
+    for track in &manifest.tracks {
+        let frames: Vec<FrameRecord> = tracks::read_track(&session_dir.join(&track.dir).join(tracks::FRAMES_TRACK), cipher)?;
+        generated.extend(generate_track(session_dir, &track.region.id, &track.dir, &frames, config, cipher)?);
+    }
+
+    // 전체 화면 프레임이 없어도 영역 트랙 목록은 남김
+    let dir = session_dir.join(THUMBS_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+    let content = serde_json::to_vec_pretty(&generated)
+        .map_err(|e| format!("Failed to serialize thumbnail index: {}", e))?;
+    storage::write_file(&dir.join(INDEX_FILE), &content, cipher)?;
+
+    Ok(generated)
+}
+
+/// 저장된 썸네일 목록 (아직 만들지 않았으면 `None`)
+pub fn read_index(session_dir: &Path, cipher: Option<&Cipher>) -> Result<Option<Vec<ThumbnailTrack>>, String> {
+    let path = session_dir.join(THUMBS_DIR).join(INDEX_FILE);
+    if !path.exists() && !path.with_extension(format!("json.{}", storage::ENCRYPTED_EXT)).exists() {
+        return Ok(None);
+    }
+    let content = storage::read_file(&path, cipher)?;
+    serde_json::from_slice(&content)
+        .map(Some)
+        .map_err(|e| format!("Failed to parse thumbnail index: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionManifest;
+    use crate::tracks::TrackWriter;
+    use chrono::Utc;
+
+    fn frame(index: usize, offset_ms: u64) -> FrameRecord {
+        FrameRecord {
+            index,
+            file: format!("frame_{:06}.png", index),
+            offset_ms,
+            captured_at: Utc::now(),
+            focused_window: None,
+        }
+    }
+
+    #[test]
+    fn test_vtt_time() {
+        assert_eq!(vtt_time(0), "00:00:00.000");
+        assert_eq!(vtt_time(3_723_004), "01:02:03.004");
+    }
+
+    #[test]
+    fn test_generate_sprite_sheets_and_vtt() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        session::write_manifest(dir, &SessionManifest::new("recording_20260309_090000", Utc::now()), None).unwrap();
+
+        let mut writer = TrackWriter::open(&dir.join(tracks::FRAMES_TRACK)).unwrap();
+        for i in 0..25 {
+            let record = frame(i, i as u64 * 5_000);
+            // 20초 프레임은 깨진 파일 → 25초 프레임이 대신 들어감
+            let data = if i == 4 {
+                b"broken".to_vec()
+            } else {
+                let mut png = Vec::new();
+                DynamicImage::ImageRgb8(RgbImage::from_pixel(320, 180, image::Rgb([i as u8 * 10, 0, 0])))
+                    .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+                    .unwrap();
+                png
+            };
+            std::fs::write(dir.join(&record.file), data).unwrap();
+            writer.append(&record).unwrap();
+        }
+
+        let config = ThumbnailConfig {
+            interval_secs: 10,
+            width: 32,
+            columns: 2,
+            ..Default::default()
+        };
+        let generated = generate_session(dir, &config, None).unwrap();
+        assert_eq!(generated.len(), 1);
+        let screen = &generated[0];
+        assert_eq!(screen.track, SCREEN_TRACK);
+        // 0, 10, 20(깨짐 → 25), 30, ... 120초 = 13장, 시트당 20장
+        assert_eq!(screen.count, 13);
+        assert_eq!(screen.tile_height, 18);
+        assert_eq!(screen.sheets, vec!["thumbs/sprite_000.jpg".to_string()]);
+
+        let sheet = image::open(dir.join(&screen.sheets[0])).unwrap();
+        assert_eq!((sheet.width(), sheet.height()), (64, 7 * 18));
+
+        let vtt = std::fs::read_to_string(dir.join(&screen.vtt)).unwrap();
+        assert!(vtt.starts_with("WEBVTT\n"));
+        assert!(vtt.contains("00:00:10.000 --> 00:00:25.000\nsprite_000.jpg#xywh=32,0,32,18"));
+        assert!(vtt.contains("00:00:25.000 --> 00:00:30.000\nsprite_000.jpg#xywh=0,18,32,18"));
+
+        let index = read_index(dir, None).unwrap().unwrap();
+        assert_eq!(index[0].count, 13);
+    }
+}
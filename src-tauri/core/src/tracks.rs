@@ -0,0 +1,156 @@
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+
+use crate::crypto::Cipher;
+
+/// 프레임 타임라인 트랙 파일
+pub const FRAMES_TRACK: &str = "frames.jsonl";
+
+/// 프레임 하나의 캡처 기록
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameRecord {
+    pub index: usize,
+    /// 세션 디렉토리 기준 파일 이름
+    pub file: String,
+    /// 세션 시작부터의 단조 시계 오프셋 (ms)
+    pub offset_ms: u64,
+    pub captured_at: DateTime<Utc>,
+    /// 캡처 시점의 맨 앞 윈도우 제목 (전체 화면 트랙만)
+    #[serde(default)]
+    pub focused_window: Option<String>,
+}
+
+/// 암호화한 트랙 기록 한 줄 (`sealed`는 암호화한 기록 JSON의 base64)
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct SealedRecord {
+    sealed: String,
+}
+
+impl SealedRecord {
+    pub(crate) fn seal<T: Serialize>(record: &T, cipher: &Cipher) -> Result<Self, String> {
+        let content = serde_json::to_vec(record)
+            .map_err(|e| format!("Failed to serialize track record: {}", e))?;
+        Ok(Self {
+            sealed: base64::engine::general_purpose::STANDARD.encode(cipher.encrypt(&content)?),
+        })
+    }
+
+    pub(crate) fn open<T: DeserializeOwned>(&self, cipher: &Cipher) -> Result<T, String> {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&self.sealed)
+            .map_err(|e| format!("Invalid sealed record: {}", e))?;
+        serde_json::from_slice(&cipher.decrypt(&data)?).map_err(|e| format!("Invalid sealed record: {}", e))
+    }
+}
+
+/// 세션 트랙용 JSONL 추가 기록기
+pub struct TrackWriter<T> {
+    file: File,
+    /// 있으면 기록마다 암호화해서 [`SealedRecord`]로 씀
+    cipher: Option<Cipher>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Serialize> TrackWriter<T> {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        Self::open_sealed(path, None)
+    }
+
+    /// 키가 있으면 기록을 암호화하는 기록기 (창 제목, 커서 위치처럼 민감한 트랙)
+    pub fn open_sealed(path: &Path, cipher: Option<&Cipher>) -> Result<Self, String> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("Failed to open track {}: {}", path.display(), e))?;
+
+        Ok(Self {
+            file,
+            cipher: cipher.cloned(),
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn append(&mut self, record: &T) -> Result<(), String> {
+        let line = match &self.cipher {
+            Some(cipher) => serde_json::to_string(&SealedRecord::seal(record, cipher)?),
+            None => serde_json::to_string(record),
+        }
+        .map_err(|e| format!("Failed to serialize track record: {}", e))?;
+        writeln!(self.file, "{}", line)
+            .map_err(|e| format!("Failed to append track record: {}", e))
+    }
+}
+
+/// JSONL 트랙 전체 읽기 (없으면 빈 목록)
+///
+/// 암호화된 기록은 `cipher`로 풀고, 키가 없으면 오류를 돌려준다.
+pub fn read_track<T: DeserializeOwned>(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<T>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = File::open(path)
+        .map_err(|e| format!("Failed to open track {}: {}", path.display(), e))?;
+
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|e| format!("Failed to read track: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = match serde_json::from_str::<SealedRecord>(&line) {
+            Ok(sealed) => match cipher {
+                Some(cipher) => sealed.open(cipher)?,
+                None => return Err(format!("Track {} is encrypted: passphrase required", path.display())),
+            },
+            Err(_) => serde_json::from_str(&line).map_err(|e| format!("Failed to parse track record: {}", e))?,
+        };
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(index: usize) -> FrameRecord {
+        FrameRecord {
+            index,
+            file: format!("frame_{:06}.png", index),
+            offset_ms: index as u64 * 1000,
+            captured_at: Utc::now(),
+            focused_window: Some("영웅문4 - 홍길동 5012-3456-78".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sealed_track_roundtrip() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(FRAMES_TRACK);
+        let cipher = Cipher::from_key(&[4; 32]);
+
+        let mut writer = TrackWriter::open_sealed(&path, Some(&cipher)).unwrap();
+        writer.append(&frame(1)).unwrap();
+        writer.append(&frame(2)).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("5012"));
+
+        let frames: Vec<FrameRecord> = read_track(&path, Some(&cipher)).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].focused_window, frame(2).focused_window);
+        assert!(read_track::<FrameRecord>(&path, None).is_err());
+
+        // 평문 트랙은 키가 있어도 그대로 읽음
+        let plain = temp.path().join("plain.jsonl");
+        TrackWriter::open(&plain).unwrap().append(&frame(3)).unwrap();
+        assert_eq!(read_track::<FrameRecord>(&plain, Some(&cipher)).unwrap()[0].index, 3);
+    }
+}
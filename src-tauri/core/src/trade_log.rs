@@ -0,0 +1,629 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use crate::models::{TradeAction, TradeEvent, TradeSource};
+use crate::schedule;
+use crate::session::{self, SessionTimeline};
+
+/// 로그 파일 문자 인코딩
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogEncoding {
+    #[default]
+    Utf8,
+    /// 대부분의 HTS가 쓰는 CP949/EUC-KR
+    EucKr,
+}
+
+impl LogEncoding {
+    fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            LogEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            LogEncoding::EucKr => encoding_rs::EUC_KR.decode(bytes).0.into_owned(),
+        }
+    }
+}
+
+/// CSV 열 (헤더 이름 또는 0부터 시작하는 번호)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ColumnRef {
+    Index(usize),
+    Name(String),
+}
+
+/// 거래 필드별 CSV 열
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnMap {
+    pub time: ColumnRef,
+    /// 날짜가 시각과 다른 열에 있을 때
+    #[serde(default)]
+    pub date: Option<ColumnRef>,
+    pub side: ColumnRef,
+    pub ticker: ColumnRef,
+    /// 종목명
+    #[serde(default)]
+    pub name: Option<ColumnRef>,
+    pub quantity: ColumnRef,
+    pub price: ColumnRef,
+    #[serde(default)]
+    pub order_id: Option<ColumnRef>,
+}
+
+fn default_delimiter() -> char {
+    ','
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// 로그 형식
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TradeLogFormat {
+    /// 체결내역 CSV 내보내기
+    Csv {
+        #[serde(default = "default_delimiter")]
+        delimiter: char,
+        #[serde(default = "default_true")]
+        has_header: bool,
+        columns: ColumnMap,
+        /// chrono 형식 (날짜 열이 있으면 `"<날짜> <시각>"`에 적용)
+        time_format: String,
+    },
+    /// 줄 단위 텍스트 로그
+    ///
+    /// 이름 있는 그룹 `time`, `side`, `ticker`, `quantity`, `price`
+    /// (선택: `date`, `name`, `order_id`)으로 필드를 뽑는다.
+    Regex { pattern: String, time_format: String },
+}
+
+/// 매수/매도 구분 값 (정확히 같거나 포함하면 일치, 대소문자 무시)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SideValues {
+    pub buy: Vec<String>,
+    pub sell: Vec<String>,
+}
+
+impl Default for SideValues {
+    fn default() -> Self {
+        Self {
+            buy: vec!["매수".to_string(), "buy".to_string()],
+            sell: vec!["매도".to_string(), "sell".to_string()],
+        }
+    }
+}
+
+impl SideValues {
+    pub fn classify(&self, value: &str) -> TradeAction {
+        let value = value.trim().to_lowercase();
+        let exact = |list: &[String]| list.iter().any(|v| v.to_lowercase() == value);
+        let partial = |list: &[String]| list.iter().any(|v| !v.is_empty() && value.contains(&v.to_lowercase()));
+
+        if exact(&self.buy) {
+            TradeAction::Buy
+        } else if exact(&self.sell) {
+            TradeAction::Sell
+        } else {
+            match (partial(&self.buy), partial(&self.sell)) {
+                (true, false) => TradeAction::Buy,
+                (false, true) => TradeAction::Sell,
+                _ => TradeAction::Unknown,
+            }
+        }
+    }
+}
+
+/// 주문/체결 로그 파일 설정
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeLogSource {
+    /// 고유 ID (예: "kiwoom-openapi")
+    pub id: String,
+    pub path: String,
+    pub format: TradeLogFormat,
+    #[serde(default)]
+    pub encoding: LogEncoding,
+    #[serde(default)]
+    pub side_values: SideValues,
+    /// 파일 끝에 추가되는 줄을 계속 읽음 (꺼져 있으면 가져오기 명령으로만 읽음)
+    #[serde(default)]
+    pub tail: bool,
+}
+
+/// 로그 줄 해석기
+///
+/// 설정으로 표현하기 어려운 형식은 이 트레이트를 직접 구현해서 `LogTailer`/`import_lines`에 넘긴다.
+pub trait TradeLogParser: Send {
+    /// 줄 하나 해석 (헤더/거래가 아닌 줄은 `Ok(None)`)
+    fn parse_line(&mut self, line: &str) -> Result<Option<TradeEvent>, String>;
+
+    /// 시각만 적힌 줄에 붙일 날짜 바꾸기
+    fn set_default_date(&mut self, date: NaiveDate);
+}
+
+/// 로그에서 뽑은 문자열 필드
+struct RawTrade<'a> {
+    date: Option<&'a str>,
+    time: &'a str,
+    side: &'a str,
+    ticker: &'a str,
+    name: Option<&'a str>,
+    quantity: &'a str,
+    price: &'a str,
+    order_id: Option<&'a str>,
+}
+
+/// 숫자 해석 (`+70,100`, `-1,000` 같은 HTS 표기 허용)
+fn parse_number(value: &str) -> Option<f64> {
+    let cleaned: String = value
+        .trim()
+        .trim_start_matches(['+', '-'])
+        .chars()
+        .filter(|c| *c != ',')
+        .collect();
+    cleaned.parse().ok()
+}
+
+/// KST 로그 시각을 UTC로 (시각만 있으면 `default_date` 사용)
+fn parse_timestamp(
+    date: Option<&str>,
+    time: &str,
+    time_format: &str,
+    default_date: NaiveDate,
+) -> Result<DateTime<Utc>, String> {
+    let text = match date {
+        Some(date) => format!("{} {}", date.trim(), time.trim()),
+        None => time.trim().to_string(),
+    };
+
+    let naive = NaiveDateTime::parse_from_str(&text, time_format)
+        .or_else(|_| NaiveTime::parse_from_str(&text, time_format).map(|t| default_date.and_time(t)))
+        .map_err(|e| format!("Invalid time '{}': {}", text, e))?;
+
+    schedule::kst()
+        .from_local_datetime(&naive)
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| format!("Invalid time '{}'", text))
+}
+
+impl RawTrade<'_> {
+    fn into_event(
+        self,
+        sides: &SideValues,
+        time_format: &str,
+        default_date: NaiveDate,
+    ) -> Result<TradeEvent, String> {
+        // HTS 표기 `A005930`의 `A`는 뒤가 6자리 숫자일 때만 뗌
+        let ticker = self.ticker.trim();
+        let ticker = ticker
+            .strip_prefix('A')
+            .filter(|code| code.len() == 6 && code.bytes().all(|b| b.is_ascii_digit()))
+            .unwrap_or(ticker);
+
+        let timestamp = parse_timestamp(self.date, self.time, time_format, default_date)?;
+        let mut event = TradeEvent::new(sides.classify(self.side), timestamp, TradeSource::LogImport);
+        event.ticker = (!ticker.is_empty()).then(|| ticker.to_string());
+        event.instrument_name = self.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        event.quantity = parse_number(self.quantity).map(|q| q as u64);
+        event.price = parse_number(self.price);
+        event.order_id = self.order_id.map(|o| o.trim().to_string()).filter(|o| !o.is_empty());
+
+        Ok(event)
+    }
+}
+
+struct CsvParser {
+    delimiter: u8,
+    has_header: bool,
+    columns: ColumnMap,
+    time_format: String,
+    sides: SideValues,
+    default_date: NaiveDate,
+    header: Option<Vec<String>>,
+}
+
+impl CsvParser {
+    fn column<'a>(&self, record: &'a csv::StringRecord, column: &ColumnRef) -> Result<&'a str, String> {
+        let index = match column {
+            ColumnRef::Index(i) => *i,
+            ColumnRef::Name(name) => self
+                .header
+                .as_ref()
+                .and_then(|h| h.iter().position(|c| c == name))
+                .ok_or_else(|| format!("Column '{}' not found in header", name))?,
+        };
+        record
+            .get(index)
+            .ok_or_else(|| format!("Column {} missing in row", index))
+    }
+}
+
+impl TradeLogParser for CsvParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<TradeEvent>, String> {
+        let line = line.trim_start_matches('\u{feff}');
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let record = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(line.as_bytes())
+            .records()
+            .next()
+            .transpose()
+            .map_err(|e| format!("Failed to parse CSV line: {}", e))?;
+        let Some(record) = record else {
+            return Ok(None);
+        };
+
+        if self.has_header && self.header.is_none() {
+            self.header = Some(record.iter().map(|c| c.trim().to_string()).collect());
+            return Ok(None);
+        }
+
+        let raw = RawTrade {
+            date: self.columns.date.as_ref().map(|c| self.column(&record, c)).transpose()?,
+            time: self.column(&record, &self.columns.time)?,
+            side: self.column(&record, &self.columns.side)?,
+            ticker: self.column(&record, &self.columns.ticker)?,
+            name: self.columns.name.as_ref().map(|c| self.column(&record, c)).transpose()?,
+            quantity: self.column(&record, &self.columns.quantity)?,
+            price: self.column(&record, &self.columns.price)?,
+            order_id: self.columns.order_id.as_ref().map(|c| self.column(&record, c)).transpose()?,
+        };
+        raw.into_event(&self.sides, &self.time_format, self.default_date).map(Some)
+    }
+
+    fn set_default_date(&mut self, date: NaiveDate) {
+        self.default_date = date;
+    }
+}
+
+struct RegexParser {
+    regex: Regex,
+    time_format: String,
+    sides: SideValues,
+    default_date: NaiveDate,
+}
+
+impl TradeLogParser for RegexParser {
+    fn parse_line(&mut self, line: &str) -> Result<Option<TradeEvent>, String> {
+        let Some(caps) = self.regex.captures(line) else {
+            return Ok(None);
+        };
+        let get = |name: &str| caps.name(name).map(|m| m.as_str());
+        let required = |name: &str| get(name).ok_or_else(|| format!("Pattern has no '{}' group", name));
+
+        let raw = RawTrade {
+            date: get("date"),
+            time: required("time")?,
+            side: required("side")?,
+            ticker: required("ticker")?,
+            name: get("name"),
+            quantity: required("quantity")?,
+            price: required("price")?,
+            order_id: get("order_id"),
+        };
+        raw.into_event(&self.sides, &self.time_format, self.default_date).map(Some)
+    }
+
+    fn set_default_date(&mut self, date: NaiveDate) {
+        self.default_date = date;
+    }
+}
+
+/// 설정에 맞는 해석기 (`default_date`는 시각만 적힌 로그의 날짜)
+pub fn parser_for(source: &TradeLogSource, default_date: NaiveDate) -> Result<Box<dyn TradeLogParser>, String> {
+    let sides = source.side_values.clone();
+
+    Ok(match &source.format {
+        TradeLogFormat::Csv {
+            delimiter,
+            has_header,
+            columns,
+            time_format,
+        } => {
+            if !delimiter.is_ascii() {
+                return Err(format!("Log '{}': CSV delimiter must be ASCII", source.id));
+            }
+            Box::new(CsvParser {
+                delimiter: *delimiter as u8,
+                has_header: *has_header,
+                columns: columns.clone(),
+                time_format: time_format.clone(),
+                sides,
+                default_date,
+                header: None,
+            })
+        }
+        TradeLogFormat::Regex { pattern, time_format } => Box::new(RegexParser {
+            regex: Regex::new(pattern).map_err(|e| format!("Log '{}': invalid pattern: {}", source.id, e))?,
+            time_format: time_format.clone(),
+            sides,
+            default_date,
+        }),
+    })
+}
+
+/// 로그 설정 검사 (ID 중복, 잘못된 패턴)
+pub fn validate_sources(sources: &[TradeLogSource]) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+
+    for source in sources {
+        if !seen.insert(source.id.as_str()) {
+            return Err(format!("Duplicate trade log id: {}", source.id));
+        }
+        parser_for(source, Local::now().date_naive())?;
+    }
+
+    Ok(())
+}
+
+/// 파일 수정 날짜 (KST, 시각만 있는 로그의 기본 날짜)
+fn file_date(path: &Path) -> NaiveDate {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|t| DateTime::<Utc>::from(t).with_timezone(&schedule::kst()).date_naive())
+        .unwrap_or_else(|_| Utc::now().with_timezone(&schedule::kst()).date_naive())
+}
+
+/// 줄 목록을 거래 이벤트로 (해석 못 한 줄은 건너뜀)
+pub fn import_lines<'a>(
+    parser: &mut dyn TradeLogParser,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<TradeEvent> {
+    let mut events = Vec::new();
+
+    for (number, line) in lines.into_iter().enumerate() {
+        match parser.parse_line(line) {
+            Ok(Some(event)) => events.push(event),
+            Ok(None) => {}
+            Err(e) => eprintln!("Skipping log line {}: {}", number + 1, e),
+        }
+    }
+
+    events
+}
+
+/// 로그 파일 전체 가져오기 (`date`가 없으면 파일 수정 날짜)
+pub fn import_file(source: &TradeLogSource, date: Option<NaiveDate>) -> Result<Vec<TradeEvent>, String> {
+    let path = Path::new(&source.path);
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read trade log: {}", e))?;
+    let content = source.encoding.decode(&bytes);
+
+    let mut parser = parser_for(source, date.unwrap_or_else(|| file_date(path)))?;
+    Ok(import_lines(parser.as_mut(), content.lines()))
+}
+
+/// 거래마다 해당 시각의 세션 위치 기록 (녹화 밖이면 None)
+pub fn align_trades(events: &mut [TradeEvent], timelines: &[SessionTimeline]) {
+    for event in events {
+        event.session = session::locate_in_sessions(timelines, event.timestamp);
+    }
+}
+
+/// `tail`이 켜진 로그마다 기록기 생성 (설정이 잘못된 로그는 건너뜀)
+pub fn open_tailers(sources: &[TradeLogSource]) -> Vec<LogTailer> {
+    sources
+        .iter()
+        .filter(|s| s.tail)
+        .filter_map(|s| match LogTailer::new(s.clone()) {
+            Ok(tailer) => Some(tailer),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 로그 파일 끝에 추가되는 거래를 읽는 기록기
+///
+/// 파일이 줄어들면 (HTS가 새 날짜 로그로 교체) 처음부터 다시 읽는다.
+pub struct LogTailer {
+    source: TradeLogSource,
+    path: PathBuf,
+    position: u64,
+    pending: Vec<u8>,
+    parser: Box<dyn TradeLogParser>,
+}
+
+impl LogTailer {
+    /// 이미 있는 줄은 거래로 내보내지 않고 건너뜀 (CSV 헤더는 읽어 둠)
+    pub fn new(source: TradeLogSource) -> Result<Self, String> {
+        let path = PathBuf::from(&source.path);
+        let parser = parser_for(&source, file_date(&path))?;
+        let mut tailer = Self {
+            source,
+            path,
+            position: 0,
+            pending: Vec::new(),
+            parser,
+        };
+        let skipped = tailer.read_new_lines()?;
+        if !skipped.is_empty() {
+            println!("Trade log {}: skipping {} existing trades", tailer.path.display(), skipped.len());
+        }
+        Ok(tailer)
+    }
+
+    pub fn source(&self) -> &TradeLogSource {
+        &self.source
+    }
+
+    /// 마지막 확인 이후 추가된 거래
+    pub fn poll(&mut self) -> Result<Vec<TradeEvent>, String> {
+        self.read_new_lines()
+    }
+
+    fn read_new_lines(&mut self) -> Result<Vec<TradeEvent>, String> {
+        // 아직 로그가 안 생겼으면 다음 확인 때 다시
+        let Ok(mut file) = std::fs::File::open(&self.path) else {
+            return Ok(Vec::new());
+        };
+        let len = file
+            .metadata()
+            .map_err(|e| format!("Failed to read trade log: {}", e))?
+            .len();
+
+        if len < self.position {
+            println!("Trade log {} was truncated, reading from start", self.path.display());
+            self.position = 0;
+            self.pending.clear();
+            self.parser = parser_for(&self.source, file_date(&self.path))?;
+        }
+        if len == self.position {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.position))
+            .map_err(|e| format!("Failed to read trade log: {}", e))?;
+        let mut chunk = Vec::new();
+        file.read_to_end(&mut chunk)
+            .map_err(|e| format!("Failed to read trade log: {}", e))?;
+        self.position += chunk.len() as u64;
+        self.pending.extend_from_slice(&chunk);
+
+        // 쓰는 중인 마지막 줄은 다음 확인 때 처리
+        let Some(end) = self.pending.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let complete: Vec<u8> = self.pending.drain(..=end).collect();
+        let content = self.source.encoding.decode(&complete);
+
+        // 시각만 적힌 로그는 방금 쓰인 날짜 (앱을 켠 날이 아니라)
+        self.parser.set_default_date(file_date(&self.path));
+
+        Ok(import_lines(self.parser.as_mut(), content.lines()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracks::FrameRecord;
+
+    fn kiwoom_csv() -> TradeLogSource {
+        TradeLogSource {
+            id: "kiwoom-csv".to_string(),
+            path: String::new(),
+            format: TradeLogFormat::Csv {
+                delimiter: ',',
+                has_header: true,
+                columns: ColumnMap {
+                    time: ColumnRef::Name("체결시간".to_string()),
+                    date: None,
+                    side: ColumnRef::Name("구분".to_string()),
+                    ticker: ColumnRef::Name("종목코드".to_string()),
+                    name: Some(ColumnRef::Name("종목명".to_string())),
+                    quantity: ColumnRef::Name("체결수량".to_string()),
+                    price: ColumnRef::Name("체결가".to_string()),
+                    order_id: Some(ColumnRef::Name("주문번호".to_string())),
+                },
+                time_format: "%H:%M:%S".to_string(),
+            },
+            encoding: LogEncoding::Utf8,
+            side_values: SideValues::default(),
+            tail: false,
+        }
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 9).unwrap()
+    }
+
+    #[test]
+    fn test_csv_with_header() {
+        let mut parser = parser_for(&kiwoom_csv(), date()).unwrap();
+        let lines = [
+            "주문번호,종목코드,종목명,구분,체결수량,체결가,체결시간",
+            "0012345,A005930,삼성전자,현금매수,10,\"+70,100\",09:01:02",
+            "0012346,005930,삼성전자,현금매도,10,\"-70,500\",09:15:00",
+            "0012347,AAPL,애플,현금매수,1,\"230\",22:31:00",
+        ];
+
+        let events = import_lines(parser.as_mut(), lines);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].action, TradeAction::Buy);
+        assert_eq!(events[0].ticker.as_deref(), Some("005930"));
+        assert_eq!(events[0].quantity, Some(10));
+        assert_eq!(events[0].price, Some(70100.0));
+        assert_eq!(events[0].order_id.as_deref(), Some("0012345"));
+        assert_eq!(events[0].instrument_name.as_deref(), Some("삼성전자"));
+        assert_eq!(events[0].source, TradeSource::LogImport);
+        // 09:01:02 KST = 00:01:02 UTC
+        assert_eq!(events[0].timestamp.to_rfc3339(), "2026-03-09T00:01:02+00:00");
+        assert_eq!(events[1].action, TradeAction::Sell);
+        assert_eq!(events[2].ticker.as_deref(), Some("AAPL"));
+    }
+
+    #[test]
+    fn test_regex_log_lines() {
+        let source = TradeLogSource {
+            format: TradeLogFormat::Regex {
+                pattern: r"^\[(?P<date>\d{8}) (?P<time>[\d:]{8})\] 체결 (?P<order_id>\d+) (?P<ticker>\d{6}) (?P<side>[12]) (?P<quantity>\d+)주 @(?P<price>[\d,]+)".to_string(),
+                time_format: "%Y%m%d %H:%M:%S".to_string(),
+            },
+            side_values: SideValues {
+                buy: vec!["2".to_string()],
+                sell: vec!["1".to_string()],
+            },
+            ..kiwoom_csv()
+        };
+        let mut parser = parser_for(&source, date()).unwrap();
+
+        let events = import_lines(
+            parser.as_mut(),
+            [
+                "[20260309 09:00:05] 접속 완료",
+                "[20260309 09:00:10] 체결 77 035720 1 5주 @52,300",
+            ],
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, TradeAction::Sell);
+        assert_eq!(events[0].price, Some(52300.0));
+    }
+
+    #[test]
+    fn test_align_to_nearest_frame() {
+        let start = Utc.with_ymd_and_hms(2026, 3, 9, 0, 0, 0).unwrap();
+        let frame = |index: usize, secs: i64| FrameRecord {
+            index,
+            file: format!("frame_{:06}.png", index),
+            offset_ms: (secs * 1000) as u64,
+            captured_at: start + chrono::Duration::seconds(secs),
+            focused_window: None,
+        };
+        let timeline = SessionTimeline {
+            session_id: "recording_20260309_090000".to_string(),
+            started_at: start,
+            ended_at: Some(start + chrono::Duration::seconds(60)),
+            frames: vec![frame(1, 0), frame(2, 1), frame(3, 2)],
+        };
+
+        let mut event = import_lines(
+            parser_for(&kiwoom_csv(), date()).unwrap().as_mut(),
+            ["주문번호,종목코드,종목명,구분,체결수량,체결가,체결시간", "1,005930,삼성전자,매수,1,70000,09:00:01"],
+        )
+        .remove(0);
+        event.timestamp += chrono::Duration::milliseconds(300);
+        let outside = TradeEvent {
+            timestamp: start + chrono::Duration::minutes(5),
+            ..event.clone()
+        };
+
+        let mut events = vec![event, outside];
+        align_trades(&mut events, &[timeline]);
+        let located = events[0].session.as_ref().unwrap();
+        assert_eq!(located.frame_index, Some(2));
+        assert_eq!(located.offset_ms, 1300);
+        assert!(events[1].session.is_none());
+    }
+}
@@ -0,0 +1,425 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::crypto::Cipher;
+use crate::journal::{self, JournalEdit};
+use crate::models::TradeEvent;
+use crate::tracks::{SealedRecord, TrackWriter};
+
+/// 세션 디렉토리 안의 거래 트랙
+pub const TRADES_TRACK: &str = "trades.jsonl";
+
+/// 출력 디렉토리의 전체 거래 색인 (세션 밖 거래 포함)
+pub const TRADE_INDEX_FILE: &str = "trades_index.jsonl";
+
+/// 시작 시 메모리에 올려 두는 기간
+const RECENT_DAYS: i64 = 30;
+
+/// 한 번에 돌려주는 최대 거래 수
+const MAX_PAGE_SIZE: usize = 500;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// 거래 내역 조회 조건 (최신순)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub offset: usize,
+    /// 없으면 100, 최대 500
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// 메모/태그/종목 검색어 (대소문자 무시)
+    #[serde(default)]
+    pub text: Option<String>,
+    /// 모든 태그가 붙은 거래만
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+}
+
+impl TradeQuery {
+    /// 기간 시작 말고는 조건이 없는지
+    fn is_unfiltered(&self) -> bool {
+        self.to.is_none() && self.text.is_none() && self.tags.is_empty() && self.min_rating.is_none()
+    }
+
+    fn matches(&self, event: &TradeEvent) -> bool {
+        self.from.is_none_or(|from| event.timestamp >= from)
+            && self.to.is_none_or(|to| event.timestamp < to)
+            && self.tags.iter().all(|tag| event.tags.contains(tag))
+            && self.min_rating.is_none_or(|min| event.rating.is_some_and(|r| r >= min))
+            && self.text.as_deref().is_none_or(|text| journal::matches_text(event, text))
+    }
+}
+
+/// 거래 내역 한 페이지
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradePage {
+    pub events: Vec<TradeEvent>,
+    /// 조건에 맞는 전체 거래 수
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// 거래 기록 한 줄 추가 (키가 있으면 암호화)
+fn append_record(path: &Path, event: &TradeEvent, cipher: Option<&Cipher>) -> Result<(), String> {
+    TrackWriter::open_sealed(path, cipher)?.append(event)
+}
+
+/// 거래 JSONL 읽기 (없으면 빈 목록)
+///
+/// 종료 중 끊긴 마지막 줄 같은 깨진 줄은 건너뛴다. 암호화된 줄은 키가 없으면 건너뛴다.
+pub fn read_trade_records(path: &Path, cipher: Option<&Cipher>) -> Result<Vec<TradeEvent>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read trades {}: {}", path.display(), e))?;
+
+    let mut events = Vec::new();
+    let mut locked = 0;
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = match serde_json::from_str::<SealedRecord>(line) {
+            Ok(sealed) => match cipher {
+                Some(cipher) => sealed.open(cipher),
+                None => {
+                    locked += 1;
+                    continue;
+                }
+            },
+            Err(_) => serde_json::from_str(line).map_err(|e| e.to_string()),
+        };
+        match parsed {
+            Ok(event) => events.push(event),
+            Err(e) => eprintln!("Skipping trade record {}:{}: {}", path.display(), number + 1, e),
+        }
+    }
+    if locked > 0 {
+        eprintln!("Skipping {} encrypted trade records in {}: passphrase required", locked, path.display());
+    }
+    Ok(events)
+}
+
+/// JSONL 기록을 ID별 마지막 버전으로 합쳐서 시각순 정렬
+///
+/// 거래 파일은 추가만 하므로 같은 ID가 여러 번 나오면 뒤의 줄이 최신이다.
+fn fold_latest(records: Vec<TradeEvent>) -> Vec<TradeEvent> {
+    let mut position: HashMap<String, usize> = HashMap::new();
+    let mut events: Vec<TradeEvent> = Vec::new();
+
+    for event in records {
+        match position.get(&event.id) {
+            Some(&i) => events[i] = event,
+            None => {
+                position.insert(event.id.clone(), events.len());
+                events.push(event);
+            }
+        }
+    }
+
+    events.sort_by_key(|e| e.timestamp);
+    events
+}
+
+/// 세션 거래 트랙 읽기 (같은 ID는 마지막 버전)
+pub fn read_session_trades(session_dir: &Path, cipher: Option<&Cipher>) -> Result<Vec<TradeEvent>, String> {
+    Ok(fold_latest(read_trade_records(&session_dir.join(TRADES_TRACK), cipher)?))
+}
+
+/// 거래 내역 저장소
+///
+/// 모든 거래는 출력 디렉토리의 색인에, 세션 안의 거래는 그 세션의 트랙에도
+/// 추가 기록한다. 최근 거래만 메모리에 두고 그보다 오래된 기간은 색인을 다시 읽는다.
+/// 키가 있으면 기록을 한 줄씩 암호화한다 (키 없이 연 저장소에는 암호화된 거래가 보이지 않음).
+pub struct TradeStore {
+    output_dir: PathBuf,
+    cipher: Option<Cipher>,
+    /// 이 시각 이후 거래는 모두 `recent`에 있음
+    horizon: DateTime<Utc>,
+    /// 시각순
+    recent: Vec<TradeEvent>,
+    /// `horizon` 전 거래 ID (기본 조회의 전체 개수용)
+    older: HashSet<String>,
+}
+
+impl TradeStore {
+    pub fn open(output_dir: &str, cipher: Option<&Cipher>) -> Result<Self, String> {
+        let output_dir = PathBuf::from(output_dir);
+        let horizon = Utc::now() - Duration::days(RECENT_DAYS);
+
+        let (recent, older): (Vec<TradeEvent>, Vec<TradeEvent>) =
+            fold_latest(read_trade_records(&output_dir.join(TRADE_INDEX_FILE), cipher)?)
+                .into_iter()
+                .partition(|e| e.timestamp >= horizon);
+
+        Ok(Self {
+            output_dir,
+            cipher: cipher.cloned(),
+            horizon,
+            recent,
+            older: older.into_iter().map(|e| e.id).collect(),
+        })
+    }
+
+    /// 메모리에 있는 최근 거래 수
+    pub fn recent_len(&self) -> usize {
+        self.recent.len()
+    }
+
+    /// 거래 기록 (같은 ID가 있으면 새 버전으로 교체)
+    pub fn record(&mut self, event: TradeEvent) -> Result<(), String> {
+        std::fs::create_dir_all(&self.output_dir)
+            .map_err(|e| format!("Failed to create output directory: {}", e))?;
+        let cipher = self.cipher.as_ref();
+        append_record(&self.output_dir.join(TRADE_INDEX_FILE), &event, cipher)?;
+
+        if let Some(session_dir) = self.session_dir(&event) {
+            append_record(&session_dir.join(TRADES_TRACK), &event, cipher)?;
+        }
+
+        if event.timestamp >= self.horizon {
+            self.recent.retain(|e| e.id != event.id);
+            let at = self.recent.partition_point(|e| e.timestamp <= event.timestamp);
+            self.recent.insert(at, event);
+        } else {
+            self.older.insert(event.id);
+        }
+        Ok(())
+    }
+
+    fn session_dir(&self, event: &TradeEvent) -> Option<PathBuf> {
+        let session = event.session.as_ref()?;
+        let dir = self.output_dir.join(&session.session_id);
+        dir.is_dir().then_some(dir)
+    }
+
+    /// ID로 거래 찾기 (최근 거래에 없으면 색인에서)
+    pub fn get(&self, id: &str) -> Result<Option<TradeEvent>, String> {
+        if let Some(event) = self.recent.iter().find(|e| e.id == id) {
+            return Ok(Some(event.clone()));
+        }
+        Ok(self.read_index()?.into_iter().find(|e| e.id == id))
+    }
+
+    /// 같은 주문번호/시각의 거래가 이미 있는지 (로그를 다시 가져올 때 중복 방지)
+    pub fn contains_order(&self, event: &TradeEvent) -> Result<bool, String> {
+        if event.order_id.is_none() {
+            return Ok(false);
+        }
+        let same = |e: &TradeEvent| e.order_id == event.order_id && e.timestamp == event.timestamp;
+
+        if event.timestamp >= self.horizon {
+            Ok(self.recent.iter().any(same))
+        } else {
+            Ok(self.read_index()?.iter().any(same))
+        }
+    }
+
+    /// 거래 저널 수정 후 새 버전으로 기록
+    ///
+    /// 예전 버전은 색인/세션 트랙에 그대로 남아 [`TradeStore::revisions`]로 볼 수 있다.
+    pub fn update(&mut self, id: &str, edit: &JournalEdit) -> Result<TradeEvent, String> {
+        let mut event = self.get(id)?.ok_or_else(|| format!("Trade not found: {}", id))?;
+        let now = Utc::now();
+        journal::apply(&mut event, edit, now)?;
+        event.revision += 1;
+        event.edited_at = Some(now);
+
+        self.record(event.clone())?;
+        Ok(event)
+    }
+
+    /// 거래의 모든 버전 (처음 기록부터 순서대로)
+    pub fn revisions(&self, id: &str) -> Result<Vec<TradeEvent>, String> {
+        let records = read_trade_records(&self.output_dir.join(TRADE_INDEX_FILE), self.cipher.as_ref())?;
+        Ok(records.into_iter().filter(|e| e.id == id).collect())
+    }
+
+    /// 조건에 맞는 거래 한 페이지 (최신순)
+    pub fn query(&self, query: &TradeQuery) -> Result<TradePage, String> {
+        let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+        // 조건 없는 기본 조회는 최근 거래로 페이지가 차면 색인을 읽지 않음
+        if query.from.is_none() && query.is_unfiltered() && query.offset + limit <= self.recent.len() {
+            return Ok(TradePage {
+                total: self.recent.len() + self.older.len(),
+                events: self.recent.iter().rev().skip(query.offset).take(limit).cloned().collect(),
+                offset: query.offset,
+                limit,
+            });
+        }
+
+        let source = self.events_since(query.from)?;
+
+        let matching: Vec<&TradeEvent> = source.iter().rev().filter(|e| query.matches(e)).collect();
+        Ok(TradePage {
+            total: matching.len(),
+            events: matching.into_iter().skip(query.offset).take(limit).cloned().collect(),
+            offset: query.offset,
+            limit,
+        })
+    }
+
+    /// 조건에 맞는 거래 전체 (시각순, 페이지 없이)
+    pub fn matching(&self, query: &TradeQuery) -> Result<Vec<TradeEvent>, String> {
+        let source = self.events_since(query.from)?;
+        Ok(source.iter().filter(|e| query.matches(e)).cloned().collect())
+    }
+
+    /// `from` 이후 거래를 모두 담은 목록 (최근 기간이면 메모리, 아니면 색인)
+    fn events_since(&self, from: Option<DateTime<Utc>>) -> Result<Cow<'_, [TradeEvent]>, String> {
+        if from.is_none_or(|from| from < self.horizon) {
+            Ok(Cow::Owned(self.read_index()?))
+        } else {
+            Ok(Cow::Borrowed(&self.recent))
+        }
+    }
+
+    /// 전체 색인 읽기 (시각순, ID별 마지막 버전)
+    pub fn read_index(&self) -> Result<Vec<TradeEvent>, String> {
+        Ok(fold_latest(read_trade_records(&self.output_dir.join(TRADE_INDEX_FILE), self.cipher.as_ref())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TradeAction, TradeSource};
+    use crate::session::SessionRef;
+
+    fn trade(minutes_ago: i64) -> TradeEvent {
+        TradeEvent::new(
+            TradeAction::Buy,
+            Utc::now() - Duration::minutes(minutes_ago),
+            TradeSource::Manual,
+        )
+    }
+
+    #[test]
+    fn test_persist_and_reload() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let session_dir = dir.join("recording_20260309_090000");
+        std::fs::create_dir_all(&session_dir).unwrap();
+
+        let mut store = TradeStore::open(dir.to_str().unwrap(), None).unwrap();
+        let mut in_session = trade(5);
+        in_session.session = Some(SessionRef {
+            session_id: "recording_20260309_090000".to_string(),
+            offset_ms: 1000,
+            frame_index: Some(1),
+        });
+        store.record(in_session.clone()).unwrap();
+        store.record(trade(1)).unwrap();
+        let old = trade(60 * 24 * 90);
+        store.record(old.clone()).unwrap();
+
+        // 같은 ID를 다시 기록하면 최신 버전만 남음
+        let mut edited = in_session.clone();
+        edited.tags.push("plan".to_string());
+        store.record(edited).unwrap();
+
+        let reopened = TradeStore::open(dir.to_str().unwrap(), None).unwrap();
+        assert_eq!(reopened.recent_len(), 2);
+        assert_eq!(reopened.get(&old.id).unwrap().map(|e| e.id), Some(old.id.clone()));
+
+        let session_trades = read_session_trades(&session_dir, None).unwrap();
+        assert_eq!(session_trades.len(), 1);
+        assert_eq!(session_trades[0].tags, vec!["plan".to_string()]);
+    }
+
+    #[test]
+    fn test_query_pages_newest_first() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut store = TradeStore::open(dir.to_str().unwrap(), None).unwrap();
+        let events: Vec<TradeEvent> = (0..5).map(|i| trade(i * 10)).collect();
+        for event in &events {
+            store.record(event.clone()).unwrap();
+        }
+
+        let page = store
+            .query(&TradeQuery {
+                from: Some(Utc::now() - Duration::minutes(35)),
+                to: None,
+                offset: 1,
+                limit: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(page.total, 4);
+        let ids: Vec<&str> = page.events.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec![events[1].id.as_str(), events[2].id.as_str()]);
+    }
+
+    #[test]
+    fn test_journal_edits_keep_history_and_search() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let mut store = TradeStore::open(dir.to_str().unwrap(), None).unwrap();
+        let event = trade(3);
+        store.record(event.clone()).unwrap();
+        store.record(trade(2)).unwrap();
+
+        store
+            .update(&event.id, &JournalEdit::AddNote { text: "계획대로 분할 매수".to_string() })
+            .unwrap();
+        store
+            .update(&event.id, &JournalEdit::AddTag { tag: "plan followed".to_string() })
+            .unwrap();
+        let latest = store.update(&event.id, &JournalEdit::SetRating { rating: Some(5) }).unwrap();
+        assert_eq!(latest.revision, 3);
+        assert!(store.update(&event.id, &JournalEdit::SetRating { rating: Some(0) }).is_err());
+
+        let revisions = store.revisions(&event.id).unwrap();
+        assert_eq!(revisions.iter().map(|e| e.revision).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        let reopened = TradeStore::open(dir.to_str().unwrap(), None).unwrap();
+        let found = reopened
+            .query(&TradeQuery {
+                text: Some("분할".to_string()),
+                tags: vec!["plan followed".to_string()],
+                min_rating: Some(4),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(found.total, 1);
+        assert_eq!(found.events[0].id, event.id);
+    }
+
+    #[test]
+    fn test_encrypted_records() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let cipher = Cipher::from_key(&[3; 32]);
+        let mut event = trade(1);
+        event.ticker = Some("005930".to_string());
+
+        let mut store = TradeStore::open(dir.to_str().unwrap(), Some(&cipher)).unwrap();
+        store.record(event.clone()).unwrap();
+        let index = std::fs::read_to_string(dir.join(TRADE_INDEX_FILE)).unwrap();
+        assert!(!index.contains("005930"));
+
+        let reopened = TradeStore::open(dir.to_str().unwrap(), Some(&cipher)).unwrap();
+        let page = reopened.query(&TradeQuery::default()).unwrap();
+        assert_eq!(page.events.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec![event.id.as_str()]);
+        assert_eq!(TradeStore::open(dir.to_str().unwrap(), None).unwrap().recent_len(), 0);
+
+        // 기본 조회는 색인을 다시 읽지 않음
+        std::fs::remove_file(dir.join(TRADE_INDEX_FILE)).unwrap();
+        let page = reopened.query(&TradeQuery { limit: Some(1), ..Default::default() }).unwrap();
+        assert_eq!((page.total, page.events.len()), (1, 1));
+    }
+}
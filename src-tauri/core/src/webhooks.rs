@@ -0,0 +1,763 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::api::ApiEvent;
+
+/// 출력 디렉토리 아래 아직 보내지 못한 웹훅을 쌓아 두는 폴더 (전송마다 JSON 파일 하나)
+pub const OUTBOX_DIR: &str = "webhooks/outbox";
+
+/// 웹훅으로 보낼 수 있는 이벤트 (엔드포인트 필터가 비어 있으면 전부)
+pub const WEBHOOK_EVENTS: &[&str] = &[
+    "recording-state",
+    "recording-started",
+    "recording-stopped",
+    "hts-detected",
+    "trade-detected",
+];
+
+/// 요청 하나의 제한 시간
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 대기열이 비어 있어도 이 간격마다 다시 확인
+const IDLE_POLL: Duration = Duration::from_secs(30);
+
+/// 웹훅 엔드포인트 하나
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    pub id: String,
+    pub url: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 보낼 이벤트 이름 (비어 있으면 [`WEBHOOK_EVENTS`] 전부)
+    #[serde(default)]
+    pub events: Vec<String>,
+    /// 본문 JSON 템플릿 (없으면 `{event, payload, timestamp, host}` 그대로)
+    ///
+    /// 문자열 안의 `{{payload.ticker}}` 같은 자리표시자를 값으로 바꾼다.
+    /// 문자열 전체가 자리표시자 하나면 숫자/객체도 원래 타입 그대로 들어간다.
+    #[serde(default)]
+    pub template: Option<Value>,
+    /// 추가 요청 헤더 (인증 토큰 등)
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl WebhookEndpoint {
+    fn accepts(&self, event: &str) -> bool {
+        self.enabled
+            && if self.events.is_empty() {
+                WEBHOOK_EVENTS.contains(&event)
+            } else {
+                self.events.iter().any(|e| e == event)
+            }
+    }
+}
+
+/// 웹훅 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+    /// 첫 재시도 대기 (초, 실패할 때마다 두 배)
+    #[serde(default = "default_retry_base_secs")]
+    pub retry_base_secs: u64,
+    /// 재시도 대기 상한 (초)
+    #[serde(default = "default_retry_max_secs")]
+    pub retry_max_secs: u64,
+    /// 이보다 오래 못 보낸 전송은 버림 (시간)
+    #[serde(default = "default_max_age_hours")]
+    pub max_age_hours: u64,
+}
+
+fn default_retry_base_secs() -> u64 {
+    5
+}
+
+fn default_retry_max_secs() -> u64 {
+    600
+}
+
+fn default_max_age_hours() -> u64 {
+    72
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            retry_base_secs: default_retry_base_secs(),
+            retry_max_secs: default_retry_max_secs(),
+            max_age_hours: default_max_age_hours(),
+        }
+    }
+}
+
+impl WebhookConfig {
+    pub fn find_endpoint(&self, id: &str) -> Option<&WebhookEndpoint> {
+        self.endpoints.iter().find(|e| e.id == id)
+    }
+
+    /// `attempts`번 실패한 뒤 다음 시도까지 대기 시간
+    fn backoff(&self, attempts: u32) -> chrono::Duration {
+        let factor = 1u64 << attempts.saturating_sub(1).min(20);
+        let secs = self.retry_base_secs.saturating_mul(factor).min(self.retry_max_secs);
+        chrono::Duration::seconds(secs as i64)
+    }
+}
+
+/// 엔드포인트 ID 중복, URL, 이벤트 이름, 헤더 검사
+pub fn validate_config(config: &WebhookConfig) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for endpoint in &config.endpoints {
+        if endpoint.id.trim().is_empty() {
+            return Err("Webhook id is empty".to_string());
+        }
+        if !ids.insert(endpoint.id.as_str()) {
+            return Err(format!("Duplicate webhook id: {}", endpoint.id));
+        }
+        if !endpoint.url.starts_with("http://") && !endpoint.url.starts_with("https://") {
+            return Err(format!("Webhook {} URL must start with http:// or https://", endpoint.id));
+        }
+        if let Some(event) = endpoint.events.iter().find(|e| !WEBHOOK_EVENTS.contains(&e.as_str())) {
+            return Err(format!("Webhook {} has unknown event: {}", endpoint.id, event));
+        }
+        if let Some(name) = endpoint.headers.keys().find(|n| n.is_empty() || !n.chars().all(|c| c.is_ascii_graphic() && c != ':')) {
+            return Err(format!("Webhook {} has invalid header name: {:?}", endpoint.id, name));
+        }
+    }
+    if config.retry_base_secs == 0 {
+        return Err("Webhook retry_base_secs must be at least 1".to_string());
+    }
+    Ok(())
+}
+
+/// 템플릿의 모든 문자열에 자리표시자 적용
+pub fn render_template(template: &Value, context: &Value) -> Value {
+    match template {
+        Value::String(text) => render_string(text, context),
+        Value::Array(items) => Value::Array(items.iter().map(|v| render_template(v, context)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, v)| (key.clone(), render_template(v, context)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn render_string(text: &str, context: &Value) -> Value {
+    // 문자열 전체가 자리표시자 하나면 원래 타입 그대로
+    if let Some(path) = text.strip_prefix("{{").and_then(|t| t.strip_suffix("}}")) {
+        if !path.contains("{{") && !path.contains("}}") {
+            return lookup(context, path.trim()).cloned().unwrap_or(Value::Null);
+        }
+    }
+
+    let mut rendered = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                rendered.push_str(&display(lookup(context, after[..end].trim())));
+                rest = &after[end + 2..];
+            }
+            None => {
+                rendered.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    rendered.push_str(rest);
+    Value::String(rendered)
+}
+
+/// `payload.session.offset_ms`, `payload.notes.0.text` 같은 경로
+fn lookup<'a>(context: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(context, |value, key| match value {
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+        _ => value.get(key),
+    })
+}
+
+/// 문자열 안에 넣을 값 (없거나 null이면 빈 문자열, 문자열은 따옴표 없이)
+fn display(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// 대기열에 있는 전송 하나 (본문은 넣을 때 만들어 둠)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub endpoint_id: String,
+    pub event: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    /// 실패한 횟수
+    #[serde(default)]
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// 보내지 못한 웹훅 대기열 (앱을 껐다 켜도 이어서 보냄)
+#[derive(Clone)]
+pub struct WebhookOutbox {
+    dir: PathBuf,
+}
+
+impl WebhookOutbox {
+    pub fn open(output_dir: &str) -> Result<Self, String> {
+        let dir = Path::new(output_dir).join(OUTBOX_DIR);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create webhook outbox: {}", e))?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 임시 파일에 쓰고 이름을 바꿔서 중간에 꺼져도 반쯤 쓴 파일이 남지 않게
+    pub fn save(&self, delivery: &WebhookDelivery) -> Result<(), String> {
+        let json = serde_json::to_string(delivery).map_err(|e| format!("Failed to serialize webhook: {}", e))?;
+        let path = self.dir.join(format!("{}.json", delivery.id));
+        let tmp = self.dir.join(format!("{}.tmp", delivery.id));
+        std::fs::write(&tmp, json).map_err(|e| format!("Failed to write webhook outbox: {}", e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write webhook outbox: {}", e))
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), String> {
+        match std::fs::remove_file(self.dir.join(format!("{}.json", id))) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove webhook from outbox: {}", e)),
+        }
+    }
+
+    /// 대기 중인 전송 (넣은 순서)
+    pub fn pending(&self) -> Result<Vec<WebhookDelivery>, String> {
+        let entries = std::fs::read_dir(&self.dir).map_err(|e| format!("Failed to read webhook outbox: {}", e))?;
+        let mut deliveries = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str::<WebhookDelivery>(&json).map_err(|e| e.to_string()));
+            match parsed {
+                Ok(delivery) => deliveries.push(delivery),
+                Err(e) => eprintln!("Skipping unreadable webhook {}: {}", path.display(), e),
+            }
+        }
+        deliveries.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+        Ok(deliveries)
+    }
+}
+
+/// 대기열 처리 결과
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryStats {
+    pub sent: usize,
+    /// 실패해서 다음에 다시 보낼 전송
+    pub retrying: usize,
+    /// 엔드포인트가 꺼졌거나, 4xx로 거절됐거나, 너무 오래된 전송
+    pub dropped: usize,
+    /// 남은 전송 중 가장 빠른 다음 시도 시각
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+fn earliest(current: Option<DateTime<Utc>>, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    Some(current.map_or(at, |current| current.min(at)))
+}
+
+enum SendError {
+    /// 네트워크 오류, 5xx, 408, 429
+    Retry(String),
+    /// 다시 보내도 안 되는 요청 (잘못된 URL/인증 등)
+    Permanent(String),
+}
+
+fn post(endpoint: &WebhookEndpoint, body: &str) -> Result<(), SendError> {
+    let mut request = ureq::post(&endpoint.url)
+        .timeout(REQUEST_TIMEOUT)
+        .set("Content-Type", "application/json");
+    for (name, value) in &endpoint.headers {
+        request = request.set(name, value);
+    }
+
+    match request.send_string(body) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) if (400..500).contains(&code) && code != 408 && code != 429 => {
+            Err(SendError::Permanent(format!("HTTP {}", code)))
+        }
+        Err(ureq::Error::Status(code, _)) => Err(SendError::Retry(format!("HTTP {}", code))),
+        Err(e) => Err(SendError::Retry(e.to_string())),
+    }
+}
+
+/// 설정된 웹훅에 앱 이벤트를 보내는 대기열
+///
+/// [`enqueue`](Self::enqueue)는 파일만 쓰고 바로 돌아오며, 실제 전송은 [`spawn_sender`](Self::spawn_sender)
+/// 스레드가 엔드포인트별로 넣은 순서대로 보낸다 (앞의 전송이 재시도 중이면 뒤는 기다림).
+pub struct Webhooks {
+    config: Mutex<WebhookConfig>,
+    outbox: Mutex<WebhookOutbox>,
+    host: String,
+    wake: Mutex<Option<Sender<()>>>,
+}
+
+impl Webhooks {
+    pub fn open(config: WebhookConfig, output_dir: &str) -> Result<Self, String> {
+        Ok(Self {
+            config: Mutex::new(config),
+            outbox: Mutex::new(WebhookOutbox::open(output_dir)?),
+            host: sysinfo::System::host_name().unwrap_or_default(),
+            wake: Mutex::new(None),
+        })
+    }
+
+    /// 설정 교체 (출력 디렉토리가 바뀌면 그쪽 대기열을 사용)
+    ///
+    /// 이전 설정에 있다가 빠진 엔드포인트로 가는 전송은 대기열에서 지운다.
+    /// 설정에 없는 엔드포인트의 전송이라도 여기서 지운 것이 아니면 보내는 쪽은 그대로 둔다.
+    pub fn set_config(&self, config: WebhookConfig, output_dir: &str) -> Result<(), String> {
+        let mut outbox = self.outbox.lock().unwrap();
+        if outbox.dir() != Path::new(output_dir).join(OUTBOX_DIR) {
+            *outbox = WebhookOutbox::open(output_dir)?;
+        }
+        let removed: HashSet<String> = {
+            let mut current = self.config.lock().unwrap();
+            let removed = current
+                .endpoints
+                .iter()
+                .filter(|old| config.find_endpoint(&old.id).is_none())
+                .map(|old| old.id.clone())
+                .collect();
+            *current = config;
+            removed
+        };
+        if !removed.is_empty() {
+            for delivery in outbox.pending()?.into_iter().filter(|d| removed.contains(&d.endpoint_id)) {
+                eprintln!("Webhook {} is removed, dropping {}", delivery.endpoint_id, delivery.event);
+                outbox.remove(&delivery.id)?;
+            }
+        }
+        drop(outbox);
+        self.notify();
+        Ok(())
+    }
+
+    /// 템플릿 자리표시자에 쓰는 값
+    fn context(&self, event: &ApiEvent) -> Value {
+        json!({
+            "event": event.event,
+            "payload": event.payload,
+            "timestamp": event.timestamp.to_rfc3339(),
+            "host": self.host,
+        })
+    }
+
+    fn render(&self, endpoint: &WebhookEndpoint, event: &ApiEvent) -> String {
+        let context = self.context(event);
+        match &endpoint.template {
+            Some(template) => render_template(template, &context).to_string(),
+            None => context.to_string(),
+        }
+    }
+
+    /// 이벤트를 받을 엔드포인트마다 대기열에 추가. 추가한 개수를 돌려준다.
+    pub fn enqueue(&self, event: &ApiEvent) -> Result<usize, String> {
+        let deliveries: Vec<WebhookDelivery> = self
+            .config
+            .lock()
+            .unwrap()
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.accepts(&event.event))
+            .map(|endpoint| WebhookDelivery {
+                id: uuid::Uuid::new_v4().simple().to_string(),
+                endpoint_id: endpoint.id.clone(),
+                event: event.event.clone(),
+                body: self.render(endpoint, event),
+                created_at: event.timestamp,
+                attempts: 0,
+                next_attempt_at: event.timestamp,
+                last_error: None,
+            })
+            .collect();
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let outbox = self.outbox.lock().unwrap();
+        for delivery in &deliveries {
+            outbox.save(delivery)?;
+        }
+        drop(outbox);
+        self.notify();
+        Ok(deliveries.len())
+    }
+
+    pub fn pending(&self) -> Result<Vec<WebhookDelivery>, String> {
+        self.outbox.lock().unwrap().pending()
+    }
+
+    /// 시도할 때가 된 전송을 보내고 결과에 따라 대기열 갱신
+    ///
+    /// 설정과 대기열 위치만 복사해 두고 락을 풀고 보내므로, 느린 엔드포인트가 있어도
+    /// [`enqueue`](Self::enqueue)나 설정 변경이 기다리지 않는다.
+    pub fn process_due(&self, now: DateTime<Utc>) -> Result<DeliveryStats, String> {
+        let config = self.config.lock().unwrap().clone();
+        let outbox = self.outbox.lock().unwrap().clone();
+        let max_age = chrono::Duration::hours(config.max_age_hours as i64);
+        let mut stats = DeliveryStats::default();
+        // 앞 전송이 아직 대기 중인 엔드포인트 (순서 유지)
+        let mut waiting: HashSet<String> = HashSet::new();
+
+        for mut delivery in outbox.pending()? {
+            if waiting.contains(&delivery.endpoint_id) {
+                continue;
+            }
+            if now - delivery.created_at > max_age {
+                eprintln!(
+                    "Webhook {} {} not delivered in {}h, dropping (last error: {})",
+                    delivery.endpoint_id,
+                    delivery.event,
+                    config.max_age_hours,
+                    delivery.last_error.as_deref().unwrap_or("-")
+                );
+                outbox.remove(&delivery.id)?;
+                stats.dropped += 1;
+                continue;
+            }
+            let endpoint = match config.find_endpoint(&delivery.endpoint_id) {
+                Some(endpoint) if endpoint.enabled => endpoint,
+                Some(_) => {
+                    eprintln!("Webhook {} is disabled, dropping {}", delivery.endpoint_id, delivery.event);
+                    outbox.remove(&delivery.id)?;
+                    stats.dropped += 1;
+                    continue;
+                }
+                // 아직 설정에 없는 엔드포인트 (설정을 읽기 전 등) → 지우지 않고 남겨 둠
+                None => {
+                    waiting.insert(delivery.endpoint_id.clone());
+                    continue;
+                }
+            };
+            if delivery.next_attempt_at > now {
+                waiting.insert(delivery.endpoint_id.clone());
+                stats.next_attempt_at = earliest(stats.next_attempt_at, delivery.next_attempt_at);
+                continue;
+            }
+
+            match post(endpoint, &delivery.body) {
+                Ok(()) => {
+                    outbox.remove(&delivery.id)?;
+                    stats.sent += 1;
+                }
+                Err(SendError::Permanent(e)) => {
+                    eprintln!("Webhook {} rejected {}: {}, dropping", delivery.endpoint_id, delivery.event, e);
+                    outbox.remove(&delivery.id)?;
+                    stats.dropped += 1;
+                }
+                Err(SendError::Retry(e)) => {
+                    delivery.attempts += 1;
+                    delivery.next_attempt_at = now + config.backoff(delivery.attempts);
+                    eprintln!(
+                        "Webhook {} failed ({}), retry #{} at {}",
+                        delivery.endpoint_id, e, delivery.attempts, delivery.next_attempt_at
+                    );
+                    delivery.last_error = Some(e);
+                    outbox.save(&delivery)?;
+                    waiting.insert(delivery.endpoint_id.clone());
+                    stats.retrying += 1;
+                    stats.next_attempt_at = earliest(stats.next_attempt_at, delivery.next_attempt_at);
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    /// 대기열을 거치지 않고 시험 이벤트를 바로 보냄 (설정 확인용)
+    pub fn send_test(&self, endpoint_id: &str) -> Result<(), String> {
+        let endpoint = self
+            .config
+            .lock()
+            .unwrap()
+            .find_endpoint(endpoint_id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown webhook: {}", endpoint_id))?;
+        let event = ApiEvent {
+            event: "test".to_string(),
+            payload: json!({ "message": "test-recorder webhook test" }),
+            timestamp: Utc::now(),
+        };
+        match post(&endpoint, &self.render(&endpoint, &event)) {
+            Ok(()) => Ok(()),
+            Err(SendError::Retry(e)) | Err(SendError::Permanent(e)) => {
+                Err(format!("Webhook {} test failed: {}", endpoint_id, e))
+            }
+        }
+    }
+
+    fn notify(&self) {
+        if let Some(wake) = self.wake.lock().unwrap().as_ref() {
+            let _ = wake.send(());
+        }
+    }
+
+    /// 대기열을 보내는 스레드 시작 (새 전송/설정 변경 시 바로 깨어남)
+    pub fn spawn_sender(self: &Arc<Self>) -> Result<(), String> {
+        let (wake, woken) = mpsc::channel();
+        *self.wake.lock().unwrap() = Some(wake);
+        let webhooks = Arc::clone(self);
+
+        thread::Builder::new()
+            .name("webhook-sender".to_string())
+            .spawn(move || loop {
+                let wait = match webhooks.process_due(Utc::now()) {
+                    Ok(stats) => stats
+                        .next_attempt_at
+                        .and_then(|at| (at - Utc::now()).to_std().ok())
+                        .map_or(IDLE_POLL, |wait| wait.min(IDLE_POLL)),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        IDLE_POLL
+                    }
+                };
+                match woken.recv_timeout(wait) {
+                    Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| format!("Failed to start webhook sender: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    /// 정해진 상태 코드를 차례로 응답하고 받은 본문을 모으는 로컬 HTTP 서버
+    fn stand_in(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&bodies);
+
+        thread::spawn(move || {
+            for (stream, status) in listener.incoming().zip(statuses) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                received.lock().unwrap().push(String::from_utf8(body).unwrap());
+                write!(stream, "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+            }
+        });
+        (url, bodies)
+    }
+
+    fn event(name: &str, payload: Value) -> ApiEvent {
+        ApiEvent {
+            event: name.to_string(),
+            payload,
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_template() {
+        let context = json!({
+            "event": "trade-detected",
+            "host": "desk-1",
+            "payload": { "action": "buy", "ticker": "005930", "price": 71200.0, "notes": [{ "text": "돌파" }] },
+        });
+        let template = json!({
+            "text": "{{host}} {{ payload.action }} {{payload.ticker}} @{{payload.price}} ({{payload.notes.0.text}}){{payload.missing}}",
+            "price": "{{payload.price}}",
+            "fields": ["{{event}}", 1, "{{payload.unclosed"],
+        });
+
+        let rendered = render_template(&template, &context);
+        assert_eq!(rendered["text"], "desk-1 buy 005930 @71200.0 (돌파)");
+        assert_eq!(rendered["price"], json!(71200.0));
+        assert_eq!(rendered["fields"], json!(["trade-detected", 1, "{{payload.unclosed"]));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let endpoint = WebhookEndpoint {
+            id: "chat".to_string(),
+            url: "https://chat.example.com/hook".to_string(),
+            enabled: true,
+            events: vec!["trade-detected".to_string()],
+            template: None,
+            headers: BTreeMap::new(),
+        };
+        let mut config = WebhookConfig {
+            endpoints: vec![endpoint.clone()],
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_ok());
+
+        config.endpoints.push(endpoint.clone());
+        assert!(validate_config(&config).is_err());
+        config.endpoints[1].id = "other".to_string();
+        config.endpoints[1].url = "ftp://example.com".to_string();
+        assert!(validate_config(&config).is_err());
+        config.endpoints[1].url = endpoint.url.clone();
+        config.endpoints[1].events = vec!["recording-duration".to_string()];
+        assert!(validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_delivery_retries_in_order() {
+        let (url, bodies) = stand_in(vec![503, 200, 200]);
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let config = WebhookConfig {
+            endpoints: vec![WebhookEndpoint {
+                id: "chat".to_string(),
+                url,
+                enabled: true,
+                events: vec!["recording-started".to_string(), "trade-detected".to_string()],
+                template: Some(json!({ "text": "{{event}} {{payload.ticker}}" })),
+                headers: BTreeMap::new(),
+            }],
+            ..Default::default()
+        };
+        let webhooks = Webhooks::open(config, dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(webhooks.enqueue(&event("recording-started", json!({ "session_id": "s" }))).unwrap(), 1);
+        assert_eq!(webhooks.enqueue(&event("recording-duration", json!(12))).unwrap(), 0);
+        assert_eq!(webhooks.enqueue(&event("trade-detected", json!({ "ticker": "005930" }))).unwrap(), 1);
+
+        // 첫 전송이 503이면 두 번째도 기다림
+        let now = Utc::now();
+        let stats = webhooks.process_due(now).unwrap();
+        assert_eq!((stats.sent, stats.retrying), (0, 1));
+        assert_eq!(stats.next_attempt_at, Some(now + chrono::Duration::seconds(5)));
+        let pending = webhooks.pending().unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].last_error.as_deref(), Some("HTTP 503"));
+
+        // 대기열은 파일이라 다시 열어도 남아 있음
+        let reopened = Webhooks::open(webhooks.config.lock().unwrap().clone(), dir.to_str().unwrap()).unwrap();
+        assert_eq!(reopened.process_due(now + chrono::Duration::seconds(1)).unwrap().sent, 0);
+        let stats = reopened.process_due(now + chrono::Duration::seconds(6)).unwrap();
+        assert_eq!((stats.sent, stats.retrying), (2, 0));
+        assert!(reopened.pending().unwrap().is_empty());
+
+        let bodies = bodies.lock().unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert_eq!(bodies[1], r#"{"text":"recording-started "}"#);
+        assert_eq!(bodies[2], r#"{"text":"trade-detected 005930"}"#);
+    }
+
+    #[test]
+    fn test_rejected_and_stale_deliveries_dropped() {
+        let (url, bodies) = stand_in(vec![404]);
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let config = WebhookConfig {
+            endpoints: vec![WebhookEndpoint {
+                id: "chat".to_string(),
+                url,
+                enabled: true,
+                events: Vec::new(),
+                template: None,
+                headers: BTreeMap::new(),
+            }],
+            max_age_hours: 1,
+            ..Default::default()
+        };
+        let webhooks = Webhooks::open(config, dir.to_str().unwrap()).unwrap();
+
+        let mut stale = event("hts-detected", json!(true));
+        stale.timestamp = Utc::now() - chrono::Duration::hours(2);
+        webhooks.enqueue(&stale).unwrap();
+        webhooks.enqueue(&event("hts-detected", json!(false))).unwrap();
+
+        let stats = webhooks.process_due(Utc::now()).unwrap();
+        assert_eq!((stats.sent, stats.dropped), (0, 2));
+        assert!(webhooks.pending().unwrap().is_empty());
+
+        let bodies = bodies.lock().unwrap();
+        let body: Value = serde_json::from_str(&bodies[0]).unwrap();
+        assert_eq!(body["event"], "hts-detected");
+        assert_eq!(body["payload"], json!(false));
+    }
+
+    #[test]
+    fn test_unknown_endpoints_kept_until_removed() {
+        let temp = tempfile::tempdir().unwrap();
+        let output_dir = temp.path().to_str().unwrap();
+        let endpoint = |id: &str| WebhookEndpoint {
+            id: id.to_string(),
+            url: "http://127.0.0.1:9/hook".to_string(),
+            enabled: true,
+            events: Vec::new(),
+            template: None,
+            headers: BTreeMap::new(),
+        };
+        let config = WebhookConfig {
+            endpoints: vec![endpoint("chat"), endpoint("desk")],
+            ..Default::default()
+        };
+        Webhooks::open(config.clone(), output_dir)
+            .unwrap()
+            .enqueue(&event("hts-detected", json!(true)))
+            .unwrap();
+
+        // 엔드포인트를 모르는 설정으로 열어도 지우지 않음
+        let webhooks = Webhooks::open(WebhookConfig::default(), output_dir).unwrap();
+        let stats = webhooks.process_due(Utc::now()).unwrap();
+        assert_eq!((stats.sent, stats.dropped), (0, 0));
+        assert_eq!(webhooks.pending().unwrap().len(), 2);
+
+        // 설정에서 빠진 엔드포인트의 전송만 지움
+        webhooks.set_config(config, output_dir).unwrap();
+        let without_chat = WebhookConfig {
+            endpoints: vec![endpoint("desk")],
+            ..Default::default()
+        };
+        webhooks.set_config(without_chat, output_dir).unwrap();
+        let pending = webhooks.pending().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].endpoint_id, "desk");
+    }
+}
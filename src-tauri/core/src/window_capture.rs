@@ -1,7 +1,35 @@
 use xcap::Window;
+use xcap::image::RgbaImage;
 use std::path::Path;
 use chrono::Local;
 
+use crate::crypto::Cipher;
+use crate::matcher::PatternMatcher;
+use crate::models::Rect;
+use crate::redaction::Redactor;
+use crate::storage;
+
+/// RGBA 이미지를 PNG로 인코딩
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut buffer = Vec::new();
+    let mut cursor = std::io::Cursor::new(&mut buffer);
+
+    // DynamicImage로 변환 후 PNG 저장
+    use xcap::image::DynamicImage;
+    let dynamic = DynamicImage::ImageRgba8(image.clone());
+    dynamic
+        .write_to(&mut cursor, xcap::image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+
+    Ok(buffer)
+}
+
+/// screenshots 캡처 결과를 RGBA 이미지로 변환
+pub fn screen_image_to_rgba(image: &screenshots::Image) -> Result<RgbaImage, String> {
+    RgbaImage::from_raw(image.width(), image.height(), image.rgba().clone())
+        .ok_or_else(|| "Invalid screen image buffer".to_string())
+}
+
 pub struct WindowCapture {
     target_window: Option<Window>,
 }
@@ -47,17 +75,7 @@ impl WindowCapture {
                 .map_err(|e| format!("Failed to capture: {}", e))?;
             
             // xcap의 ImageBuffer를 PNG로 인코딩
-            let mut buffer = Vec::new();
-            let cursor = std::io::Cursor::new(&mut buffer);
-            
-            // DynamicImage로 변환 후 PNG 저장
-            use xcap::image::DynamicImage;
-            let dynamic = DynamicImage::ImageRgba8(image);
-            dynamic
-                .write_to(cursor, xcap::image::ImageFormat::Png)
-                .map_err(|e| format!("Failed to encode PNG: {}", e))?;
-            
-            Ok(buffer)
+            encode_png(&image)
         } else {
             Err("No target window set".to_string())
         }
@@ -99,9 +117,69 @@ impl WindowCapture {
         }
     }
 
+    /// 제목이 패턴과 일치하는 첫 (최소화되지 않은) 윈도우
+    pub fn find_window(title_matcher: &PatternMatcher) -> Option<Window> {
+        let windows = Window::all().ok()?;
+
+        windows
+            .into_iter()
+            .filter(|w| !w.is_minimized())
+            .find(|w| title_matcher.matches_text(w.title()))
+    }
+
+    /// 맨 앞에 있는 윈도우 제목
+    ///
+    /// 윈도우 목록은 위에 있는 것부터 오므로 최소화되지 않고 제목이 있는 첫 윈도우를 포커스 윈도우로 본다.
+    pub fn focused_window_title() -> Option<String> {
+        let windows = Window::all().ok()?;
+
+        windows
+            .into_iter()
+            .filter(|w| !w.is_minimized() && w.width() > 0 && w.height() > 0)
+            .map(|w| w.title().to_string())
+            .find(|title| !title.trim().is_empty())
+    }
+
+    /// 제목이 패턴과 일치하는 첫 윈도우의 화면 좌표
+    pub fn find_window_rect(title_matcher: &PatternMatcher) -> Option<Rect> {
+        Self::find_window(title_matcher).map(|w| Rect {
+            x: w.x(),
+            y: w.y(),
+            width: w.width(),
+            height: w.height(),
+        })
+    }
+
     /// 전체 화면을 파일로 저장
-    pub fn capture_screen_and_save(output_dir: &str, prefix: &str) -> Result<String, String> {
-        let image_data = Self::capture_primary_screen()?;
+    ///
+    /// `redactor`가 있으면 HTS 윈도우 기준 가림 영역을 적용하고,
+    /// `cipher`가 있으면 암호화해서 저장한다.
+    pub fn capture_screen_and_save(
+        output_dir: &str,
+        prefix: &str,
+        redactor: Option<(&Redactor, &PatternMatcher)>,
+        cipher: Option<&Cipher>,
+    ) -> Result<String, String> {
+        let image_data = match redactor {
+            Some((redactor, window_matcher)) if redactor.is_active() => {
+                let screens = screenshots::Screen::all()
+                    .map_err(|e| format!("Failed to get screens: {}", e))?;
+                let screen = screens.first().ok_or_else(|| "No screen found".to_string())?;
+                let captured = screen
+                    .capture()
+                    .map_err(|e| format!("Failed to capture screen: {}", e))?;
+
+                let mut image = screen_image_to_rgba(&captured)?;
+                let window = Self::find_window_rect(window_matcher).map(|r| Rect {
+                    x: r.x - screen.display_info.x,
+                    y: r.y - screen.display_info.y,
+                    ..r
+                });
+                redactor.apply(&mut image, window);
+                encode_png(&image)?
+            }
+            _ => Self::capture_primary_screen()?,
+        };
         
         let timestamp = Local::now().format("%Y%m%d_%H%M%S");
         let filename = format!("{}_{}.png", prefix, timestamp);
@@ -110,10 +188,9 @@ impl WindowCapture {
         std::fs::create_dir_all(output_dir)
             .map_err(|e| format!("Failed to create directory: {}", e))?;
         
-        std::fs::write(&filepath, image_data)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
+        let written = storage::write_file(&filepath, &image_data, cipher)?;
         
-        Ok(filepath.to_string_lossy().to_string())
+        Ok(written.path.to_string_lossy().to_string())
     }
 
     /// 윈도우 정보 가져오기
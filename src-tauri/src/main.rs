@@ -1,80 +1,364 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use tauri::{Manager, State, AppHandle};
+use std::path::Path;
 use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
 use tokio::time::{interval, Duration};
+use tokio_util::sync::CancellationToken;
 
-mod models;
-mod process_monitor;
-mod recorder;
-mod window_capture;
+mod playback;
 
-use models::*;
-use process_monitor::ProcessMonitor;
-use recorder::ScreenRecorder;
-use window_capture::WindowCapture;
+use test_recorder_core::activity::{self, ActivityTimeline};
+use test_recorder_core::api::{self, ApiCommand, ApiConfig, ApiEvent, ApiRequest, ApiServer, EventBus};
+use test_recorder_core::clips::{self, ClipExport};
+use test_recorder_core::clock::SystemClock;
+use test_recorder_core::controller::{ControllerOutcome, RecordingController};
+use test_recorder_core::crypto::{self, Cipher};
+use test_recorder_core::export::{self, ExportFilter, ExportFormat, ExportedFile};
+use test_recorder_core::integrity::{self, VerifyReport};
+use test_recorder_core::journal::{self, JournalEdit};
+use test_recorder_core::models::*;
+use test_recorder_core::order_detector::{self, OrderDetection, OrderDetector};
+use test_recorder_core::positions::{self, PositionReport};
+use test_recorder_core::process_monitor::ProcessMonitor;
+use test_recorder_core::profiles::{self, BrokerProfile};
+use test_recorder_core::recorder::ScreenRecorder;
+use test_recorder_core::redaction::{self, Redactor};
+use test_recorder_core::regions;
+use test_recorder_core::report;
+use test_recorder_core::schedule::{self, MarketSchedule, ScheduleStatus};
+use test_recorder_core::session::{self, SessionInfo, SessionSummary};
+use test_recorder_core::state_machine::{RecorderEvent, RecorderState, RecordingOrigin, StateTransition};
+use test_recorder_core::thumbnails::{self, ThumbnailTrack};
+use test_recorder_core::trade_log::{self, LogTailer};
+use test_recorder_core::trade_store::{TradePage, TradeQuery, TradeStore};
+use test_recorder_core::webhooks::{self, Webhooks};
+use test_recorder_core::window_capture::WindowCapture;
 
 // 전역 상태
 #[derive(Clone)]
 struct AppState {
-    recorder: Arc<Mutex<ScreenRecorder>>,
+    controller: Arc<Mutex<RecordingController>>,
     monitor: Arc<Mutex<ProcessMonitor>>,
     capture: Arc<Mutex<WindowCapture>>,
     config: Arc<Mutex<AppConfig>>,
-    trade_history: Arc<Mutex<Vec<TradeEvent>>>,
+    schedule: Arc<Mutex<MarketSchedule>>,
+    order_detector: Arc<Mutex<OrderDetector>>,
+    log_tailers: Arc<Mutex<Vec<LogTailer>>>,
+    trade_store: Arc<Mutex<TradeStore>>,
+    cipher: Arc<Mutex<Option<Cipher>>>,
+    /// 프론트엔드로 보내는 이벤트를 로컬 API 구독자에게도 전달
+    events: EventBus,
+    api_requests: mpsc::Sender<ApiRequest>,
+    /// 실행 중인 로컬 API 서버 (재시작 시 이전 서버가 멈출 때까지 대기하므로 tokio Mutex)
+    api_server: Arc<tokio::sync::Mutex<Option<ApiServer>>>,
+    /// 녹화/거래 이벤트 웹훅 대기열
+    webhooks: Arc<Webhooks>,
 }
 
-// Tauri 명령어들
+/// 상태 머신 조회 결과
+#[derive(Debug, Clone, serde::Serialize)]
+struct RecorderStateInfo {
+    state: RecorderState,
+    origin: Option<RecordingOrigin>,
+    auto_mode: bool,
+    history: Vec<StateTransition>,
+}
 
-#[tauri::command]
-async fn start_monitoring(state: State<'_, AppState>) -> Result<String, String> {
+/// 녹화 시작 알림
+#[derive(Debug, Clone, serde::Serialize)]
+struct RecordingStarted {
+    session_id: String,
+    path: String,
+}
+
+/// 썸네일 생성 완료 알림
+#[derive(Debug, Clone, serde::Serialize)]
+struct ThumbnailsReady {
+    session_id: String,
+    tracks: Vec<ThumbnailTrack>,
+}
+
+/// 프론트엔드, 로컬 API 이벤트 스트림, 웹훅 대기열에 같은 이벤트 전송
+fn emit_event<S: serde::Serialize + Clone>(app_handle: &AppHandle, state: &AppState, event: &str, payload: S) {
+    match ApiEvent::new(event, &payload) {
+        Ok(api_event) => {
+            if let Err(e) = state.webhooks.enqueue(&api_event) {
+                eprintln!("{}", e);
+            }
+            state.events.send(api_event);
+        }
+        Err(e) => eprintln!("{}", e),
+    }
+    let _ = app_handle.emit_all(event, payload);
+}
+
+/// 녹화 상태 머신에 이벤트를 넣고 전이를 프론트엔드에 알림
+///
+/// 녹화 중지는 파이프라인 flush를 기다리므로 블로킹 스레드에서 처리하고,
+/// 그동안 컨트롤러 락은 풀어 둔다 (상태 조회, 거래 기록이 막히지 않도록).
+async fn dispatch_event(
+    app_handle: &AppHandle,
+    state: &AppState,
+    event: RecorderEvent,
+) -> Result<ControllerOutcome, String> {
+    let controller = Arc::clone(&state.controller);
     let config = state.config.lock().unwrap().clone();
-    let recorder = Arc::clone(&state.recorder);
     
-    let output_dir = config.output_dir.clone();
-    let result = recorder.lock().unwrap().start_recording(&output_dir)?;
+    let (result, transitions) = tauri::async_runtime::spawn_blocking(move || {
+        let (result, mut transitions) = {
+            let mut controller = controller.lock().unwrap();
+            let result = controller.begin(event, &config);
+            (result, controller.take_transitions())
+        };
+        
+        let result = result.and_then(|mut outcome| {
+            if let Some(stopping) = outcome.stopping.take() {
+                let finished = stopping.finish();
+                let mut controller = controller.lock().unwrap();
+                let finished = controller.finish_stop(finished);
+                transitions.extend(controller.take_transitions());
+                outcome.summary = Some(finished?);
+            }
+            Ok(outcome)
+        });
+        (result, transitions)
+    })
+    .await
+    .map_err(|e| format!("Failed to handle recorder event: {}", e))?;
     
-    Ok(format!("Monitoring started. Recording to: {}", result))
+    // 실패해도 일어난 전이(예: Error)는 모두 전송
+    for transition in &transitions {
+        emit_event(app_handle, state, "recording-state", transition);
+    }
+    if let Ok(outcome) = &result {
+        if let Some(path) = &outcome.started {
+            let session_id = Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            emit_event(app_handle, state, "recording-started", RecordingStarted { session_id, path: path.clone() });
+        }
+        if let Some(summary) = &outcome.summary {
+            emit_event(app_handle, state, "recording-stopped", Some(summary));
+            spawn_thumbnail_job(app_handle, state, summary);
+        }
+    }
+    
+    result
 }
 
-#[tauri::command]
-async fn stop_monitoring(state: State<'_, AppState>) -> Result<String, String> {
-    let recorder = Arc::clone(&state.recorder);
+/// 녹화가 끝난 세션의 스크러빙용 썸네일을 백그라운드에서 생성
+fn spawn_thumbnail_job(app_handle: &AppHandle, state: &AppState, summary: &SessionSummary) {
+    let config = state.config.lock().unwrap().thumbnails.clone();
+    if !config.enabled {
+        return;
+    }
+    let cipher = state.cipher.lock().unwrap().clone();
+    let app_handle = app_handle.clone();
+    let state = state.clone();
+    let session_id = summary.session_id.clone();
+    let session_dir = summary.path.clone();
+    
+    tauri::async_runtime::spawn_blocking(move || {
+        match thumbnails::generate_session(Path::new(&session_dir), &config, cipher.as_ref()) {
+            Ok(tracks) => {
+                println!("Thumbnails generated for {}", session_id);
+                emit_event(&app_handle, &state, "thumbnails-ready", ThumbnailsReady { session_id, tracks });
+            }
+            Err(e) => eprintln!("Failed to generate thumbnails for {}: {}", session_id, e),
+        }
+    });
+}
+
+/// 감지된 주문창을 저장하고 거래 내역/세션 해시 체인에 추가
+///
+/// 녹화 중이면 세션 디렉토리에, 아니면 출력 디렉토리에 저장한다.
+fn record_order_detection(app_handle: &AppHandle, state: &AppState, detection: OrderDetection) {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    if config.encryption.enabled && cipher.is_none() {
+        eprintln!("Order dialog detected but recordings are locked: screenshot not saved");
+        return;
+    }
     
-    let result = {
-        let rec = recorder.lock().unwrap();
-        rec.stop_recording()?
+    let (dir, detected) = {
+        let controller = state.controller.lock().unwrap();
+        (controller.recorder().get_output_path(), controller.detected_profile().cloned())
+    };
+    let dir = dir.unwrap_or_else(|| config.output_dir.clone());
+    // 주문창에도 계좌번호가 보이므로 녹화 프레임과 같은 가림 적용
+    let redactor = match Redactor::new(config.redaction_for(detected.as_ref())) {
+        Ok(redactor) => redactor,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+    let hts_window = WindowCapture::find_window_rect(&config.hts.window_matcher_for(detected.as_ref()));
+    let event = match order_detector::save_detection(&detection, Path::new(&dir), &redactor, hts_window, cipher.as_ref()) {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
     };
     
-    match result {
-        Some(path) => Ok(format!("Recording saved to: {}", path)),
-        None => Ok("Monitoring stopped".to_string()),
+    println!("Order dialog detected: {:?} ({:.2})", event.action, detection.confidence);
+    record_trade_event(app_handle, state, event);
+}
+
+/// 거래 이벤트를 녹화 중인 세션 해시 체인과 거래 내역에 추가하고 알림
+///
+/// 세션 위치를 채운 이벤트를 돌려준다.
+fn record_trade_event(app_handle: &AppHandle, state: &AppState, mut event: TradeEvent) -> TradeEvent {
+    {
+        let controller = state.controller.lock().unwrap();
+        let recorder = controller.recorder();
+        let current = recorder.session_position(event.timestamp);
+        // 가져온 로그는 이미 지난 세션에 맞춰져 있을 수 있음
+        if event.session.is_none() {
+            event.session = current.clone();
+        }
+        let in_current = match (&event.session, &current) {
+            (Some(session), Some(current)) => session.session_id == current.session_id,
+            _ => false,
+        };
+        if in_current {
+            if let Err(e) = recorder.append_trade_event(&event) {
+                eprintln!("{}", e);
+            }
+        }
+    }
+    
+    let locked = state.config.lock().unwrap().encryption.enabled && state.cipher.lock().unwrap().is_none();
+    if locked {
+        eprintln!("Trade not saved: recordings are locked");
+    } else if let Err(e) = state.trade_store.lock().unwrap().record(event.clone()) {
+        eprintln!("Failed to save trade: {}", e);
     }
+    emit_event(app_handle, state, "trade-detected", &event);
+    event
+}
+
+// Tauri 명령어들
+
+#[tauri::command]
+async fn start_monitoring(app_handle: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let outcome = dispatch_event(&app_handle, &state, RecorderEvent::ManualStart).await?;
+    let result = outcome.started.unwrap_or_default();
+    
+    Ok(format!("Monitoring started. Recording to: {}", result))
+}
+
+#[tauri::command]
+async fn stop_monitoring(app_handle: AppHandle, state: State<'_, AppState>) -> Result<SessionSummary, String> {
+    let outcome = dispatch_event(&app_handle, &state, RecorderEvent::ManualStop).await?;
+    outcome.summary.ok_or_else(|| "Not recording".to_string())
+}
+
+#[tauri::command]
+async fn pause_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    dispatch_event(&app_handle, &state, RecorderEvent::ManualPause).await?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    dispatch_event(&app_handle, &state, RecorderEvent::ManualResume).await?;
+    Ok(())
+}
+
+/// HTS 감지 시 자동 녹화 켜기/끄기
+#[tauri::command]
+async fn set_auto_mode(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    dispatch_event(&app_handle, &state, RecorderEvent::SetAutoMode(enabled)).await?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_recorder_state(state: State<'_, AppState>) -> Result<RecorderStateInfo, String> {
+    let controller = state.controller.lock().unwrap();
+    
+    Ok(RecorderStateInfo {
+        state: controller.state().clone(),
+        origin: controller.origin(),
+        auto_mode: controller.auto_mode(),
+        history: controller.history(),
+    })
+}
+
+/// 장 시간 스케줄 상태 (현재 시간대, 다음 개장)
+#[tauri::command]
+async fn get_schedule_status(state: State<'_, AppState>) -> Result<ScheduleStatus, String> {
+    Ok(state.schedule.lock().unwrap().status())
 }
 
 #[tauri::command]
 async fn get_recording_status(state: State<'_, AppState>) -> Result<RecordingStatus, String> {
-    let recorder = state.recorder.lock().unwrap();
+    Ok(recording_status(&state))
+}
+
+fn recording_status(state: &AppState) -> RecordingStatus {
+    let controller = state.controller.lock().unwrap();
     let monitor = state.monitor.lock().unwrap();
+    let recorder = controller.recorder();
     
     let is_recording = recorder.is_recording();
-    let hts_detected_name = monitor.is_target_running();
+    let detected = monitor.detect();
     let recording_duration = recorder.get_recording_duration();
+    let pipeline = recorder.get_pipeline_stats();
     
-    Ok(RecordingStatus {
+    RecordingStatus {
         is_recording,
-        hts_detected: hts_detected_name.is_some(),
-        hts_name: hts_detected_name,
+        hts_detected: detected.is_some(),
+        hts_name: detected.as_ref().map(|d| d.process_name.clone()),
         recording_duration,
-    })
+        pipeline,
+        state: Some(controller.state().clone()),
+        profile: detected,
+    }
 }
 
 #[tauri::command]
 async fn capture_screenshot(state: State<'_, AppState>) -> Result<String, String> {
-    let config = state.config.lock().unwrap();
-    let output_dir = config.output_dir.clone();
+    let config = state.config.lock().unwrap().clone();
+    let redactor = Redactor::new(config.redaction.clone())?;
+    let cipher = state.cipher.lock().unwrap().clone();
+    if config.encryption.enabled && cipher.is_none() {
+        return Err("Recordings are encrypted: unlock with passphrase first".to_string());
+    }
+    
+    WindowCapture::capture_screen_and_save(
+        &config.output_dir,
+        "screenshot",
+        Some((&redactor, &config.hts.window_matcher())),
+        cipher.as_ref(),
+    )
+}
+
+/// 암호화된 녹화 잠금 해제 (패스프레이즈 방식이면 passphrase 필요)
+#[tauri::command]
+async fn unlock_recordings(
+    state: State<'_, AppState>,
+    passphrase: Option<String>,
+) -> Result<bool, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = crypto::open_cipher(&config.encryption, &config.output_dir, passphrase.as_deref())?;
+    let unlocked = cipher.is_some();
+    
+    // 잠겨 있는 동안 보이지 않던 암호화된 거래 내역까지 다시 읽음
+    let trade_store = TradeStore::open(&config.output_dir, cipher.as_ref())?;
+    *state.trade_store.lock().unwrap() = trade_store;
+    state.controller.lock().unwrap().recorder().set_cipher(cipher.clone());
+    *state.cipher.lock().unwrap() = cipher;
     
-    WindowCapture::capture_screen_and_save(&output_dir, "screenshot")
+    Ok(unlocked)
 }
 
 #[tauri::command]
@@ -83,9 +367,261 @@ async fn list_windows() -> Result<Vec<String>, String> {
 }
 
 #[tauri::command]
-async fn get_trade_history(state: State<'_, AppState>) -> Result<Vec<TradeEvent>, String> {
-    let history = state.trade_history.lock().unwrap();
-    Ok(history.clone())
+async fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, String> {
+    let output_dir = state.config.lock().unwrap().output_dir.clone();
+    session::list_sessions(&output_dir)
+}
+
+/// 세션 해시 체인 검증
+#[tauri::command]
+async fn verify_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<VerifyReport, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    let session_dir = session::session_path(&config.output_dir, &session_id)?;
+    let trusted = integrity::trusted_key(&config.integrity, &config.output_dir)?;
+    
+    integrity::verify_session(&session_dir, cipher.as_ref(), trusted.as_ref())
+}
+
+/// 거래 내역 (최신순, `from` 이상 `to` 미만)
+///
+/// `query`가 없으면 최근 100개. 메모/태그/종목 검색어, 태그, 실행 평가 하한으로 거를 수 있다.
+#[tauri::command]
+async fn get_trade_history(state: State<'_, AppState>, query: Option<TradeQuery>) -> Result<TradePage, String> {
+    state.trade_store.lock().unwrap().query(&query.unwrap_or_default())
+}
+
+/// 왕복 매매별 실현 손익 (선입선출, 설정의 수수료/거래세 적용)
+///
+/// 조회 기간 전에 산 종목을 판 거래는 `unmatched_sells`로 따로 돌려준다.
+#[tauri::command]
+async fn get_positions(state: State<'_, AppState>, query: Option<TradeQuery>) -> Result<PositionReport, String> {
+    let fees = state.config.lock().unwrap().fees.clone();
+    let events = state.trade_store.lock().unwrap().matching(&query.unwrap_or_default())?;
+    
+    Ok(positions::compute_positions(&events, &fees))
+}
+
+/// 일일 리포트 HTML 생성 (`date`는 YYYY-MM-DD, 없으면 오늘 KST)
+///
+/// 저장한 파일 경로를 돌려준다.
+#[tauri::command]
+async fn export_daily_report(state: State<'_, AppState>, date: Option<String>) -> Result<String, String> {
+    let date = match date {
+        Some(date) => chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| format!("Invalid date: {}", e))?,
+        None => chrono::Utc::now().with_timezone(&schedule::kst()).date_naive(),
+    };
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    
+    let path = tokio::task::spawn_blocking(move || {
+        report::write_daily_report(&config.output_dir, date, cipher.as_ref(), &config.fees)
+    })
+    .await
+    .map_err(|e| format!("Report task failed: {}", e))??;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// 세션 썸네일 목록 (`recording://` 경로로 WebVTT/스프라이트 시트를 불러옴)
+///
+/// 아직 없거나 `regenerate`면 새로 만든다.
+#[tauri::command]
+async fn get_thumbnails(
+    state: State<'_, AppState>,
+    session_id: String,
+    regenerate: Option<bool>,
+) -> Result<Vec<ThumbnailTrack>, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    let session_dir = session::session_path(&config.output_dir, &session_id)?;
+    
+    tokio::task::spawn_blocking(move || {
+        if !regenerate.unwrap_or(false) {
+            if let Some(tracks) = thumbnails::read_index(&session_dir, cipher.as_ref())? {
+                return Ok(tracks);
+            }
+        }
+        thumbnails::generate_session(&session_dir, &config.thumbnails, cipher.as_ref())
+    })
+    .await
+    .map_err(|e| format!("Thumbnail task failed: {}", e))?
+}
+
+/// 세션 구간을 프레임 파일로 내보내기 (`render_input`이 없으면 설정의 `render_in_clips`)
+#[tauri::command]
+async fn export_clip(
+    state: State<'_, AppState>,
+    session_id: String,
+    start_ms: u64,
+    end_ms: u64,
+    dest_dir: String,
+    render_input: Option<bool>,
+) -> Result<ClipExport, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    let render_input = render_input.unwrap_or(config.input_tracking.render_in_clips);
+    
+    tokio::task::spawn_blocking(move || {
+        clips::export_clip(&config.output_dir, &session_id, start_ms, end_ms, &dest_dir, cipher.as_ref(), render_input)
+    })
+    .await
+    .map_err(|e| format!("Clip export task failed: {}", e))?
+}
+
+/// 세션 화면 활동 곡선과 활동이 많았던 구간 (`points`: 곡선 점 개수)
+#[tauri::command]
+async fn get_activity_timeline(
+    state: State<'_, AppState>,
+    session_id: String,
+    points: Option<usize>,
+) -> Result<ActivityTimeline, String> {
+    let output_dir = state.config.lock().unwrap().output_dir.clone();
+    let session_dir = session::session_path(&output_dir, &session_id)?;
+    
+    tokio::task::spawn_blocking(move || {
+        activity::load_timeline(&session_dir, points.unwrap_or(activity::DEFAULT_POINTS))
+    })
+    .await
+    .map_err(|e| format!("Activity task failed: {}", e))?
+}
+
+/// 거래, 왕복 매매, 세션, 프레임 트랙을 CSV/Parquet로 내보내기
+#[tauri::command]
+async fn export_data(
+    state: State<'_, AppState>,
+    dest_dir: String,
+    format: ExportFormat,
+    filter: Option<ExportFilter>,
+) -> Result<Vec<ExportedFile>, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    let filter = filter.unwrap_or_default();
+    
+    tokio::task::spawn_blocking(move || {
+        export::export_data(&config.output_dir, &dest_dir, format, &filter, cipher.as_ref(), &config.fees)
+    })
+    .await
+    .map_err(|e| format!("Export task failed: {}", e))?
+}
+
+/// 장 마감 후 자동 일일 리포트 (녹화도 거래도 없는 날은 건너뜀)
+async fn auto_generate_report(app_handle: &AppHandle, state: &AppState, date: chrono::NaiveDate) {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    
+    let result = tokio::task::spawn_blocking(move || {
+        let daily = report::collect(&config.output_dir, date, cipher.as_ref(), &config.fees)?;
+        if daily.is_empty() {
+            return Ok(None);
+        }
+        report::write_report(&config.output_dir, &daily, cipher.as_ref()).map(Some)
+    })
+    .await
+    .map_err(|e| format!("Report task failed: {}", e))
+    .and_then(|result| result);
+    
+    match result {
+        Ok(Some(path)) => {
+            println!("Daily report written: {}", path.display());
+            emit_event(app_handle, state, "report-generated", path.to_string_lossy().to_string());
+        }
+        Ok(None) => println!("No recordings or trades on {}, skipping daily report", date),
+        Err(e) => eprintln!("Failed to generate daily report: {}", e),
+    }
+}
+
+/// 거래 저널 수정 (메모, 태그, 실행 평가, 스크린샷)
+///
+/// 수정할 때마다 새 버전이 색인/세션 트랙에 추가되고, 수정된 거래를 "trade-updated"로 알린다.
+#[tauri::command]
+async fn edit_trade_journal(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    trade_id: String,
+    edit: JournalEdit,
+) -> Result<TradeEvent, String> {
+    let event = state.trade_store.lock().unwrap().update(&trade_id, &edit)?;
+    emit_event(&app_handle, &state, "trade-updated", &event);
+    Ok(event)
+}
+
+/// 파일을 출력 디렉토리로 복사해서 거래에 스크린샷으로 첨부
+#[tauri::command]
+async fn attach_trade_screenshot(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    trade_id: String,
+    path: String,
+    caption: Option<String>,
+) -> Result<TradeEvent, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    if config.encryption.enabled && cipher.is_none() {
+        return Err("Recordings are encrypted: unlock with passphrase first".to_string());
+    }
+    // 없는 거래면 파일을 복사하기 전에 거부
+    if state.trade_store.lock().unwrap().get(&trade_id)?.is_none() {
+        return Err(format!("Trade not found: {}", trade_id));
+    }
+
+    let copied = journal::import_screenshot(&config.output_dir, &trade_id, Path::new(&path), cipher.as_ref())?;
+    let edit = JournalEdit::AddScreenshot { path: copied, caption };
+    
+    let event = state.trade_store.lock().unwrap().update(&trade_id, &edit)?;
+    emit_event(&app_handle, &state, "trade-updated", &event);
+    Ok(event)
+}
+
+/// 거래의 수정 이력 (처음 기록부터 순서대로)
+#[tauri::command]
+async fn get_trade_revisions(state: State<'_, AppState>, trade_id: String) -> Result<Vec<TradeEvent>, String> {
+    state.trade_store.lock().unwrap().revisions(&trade_id)
+}
+
+/// 설정된 주문/체결 로그를 가져와서 녹화 세션 타임라인에 맞춤
+///
+/// `date`(YYYY-MM-DD)는 시각만 적힌 로그의 날짜 (없으면 파일 수정 날짜).
+/// 이미 거래 내역에 있는 주문번호/시각은 다시 추가하지 않는다.
+#[tauri::command]
+async fn import_trade_log(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    source_id: String,
+    date: Option<String>,
+) -> Result<Vec<TradeEvent>, String> {
+    let config = state.config.lock().unwrap().clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+    if config.encryption.enabled && cipher.is_none() {
+        return Err("Recordings are encrypted: unlock with passphrase first".to_string());
+    }
+    let source = config
+        .trade_logs
+        .iter()
+        .find(|s| s.id == source_id)
+        .ok_or_else(|| format!("Trade log '{}' not found", source_id))?;
+    let date = date
+        .map(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d"))
+        .transpose()
+        .map_err(|e| format!("Invalid date: {}", e))?;
+    
+    let mut events = trade_log::import_file(source, date)?;
+    let timelines = session::load_timelines(&config.output_dir, cipher.as_ref())?;
+    trade_log::align_trades(&mut events, &timelines);
+    
+    // 새 거래만 감지된 거래처럼 기록 (해시 체인, 웹훅, 프론트엔드 알림)
+    let mut imported = Vec::with_capacity(events.len());
+    for event in events {
+        if state.trade_store.lock().unwrap().contains_order(&event)? {
+            imported.push(event);
+        } else {
+            imported.push(record_trade_event(&app_handle, &state, event));
+        }
+    }
+    
+    Ok(imported)
 }
 
 #[tauri::command]
@@ -99,87 +635,412 @@ async fn update_config(
     state: State<'_, AppState>,
     new_config: AppConfig,
 ) -> Result<(), String> {
+    // 모두 검사하고 새로 만들 것을 준비한 뒤에 적용 (하나라도 잘못되면 아무것도 바꾸지 않음)
+    let schedule = MarketSchedule::load(&new_config.schedule, Arc::new(SystemClock))?;
+    profiles::validate_profiles(&new_config.hts.profiles)?;
+    regions::validate_regions(&new_config.regions)?;
+    redaction::validate_config(&new_config.redaction)?;
+    trade_log::validate_sources(&new_config.trade_logs)?;
+    webhooks::validate_config(&new_config.webhooks)?;
+    api::validate_config(&new_config.api)?;
+    let detector = OrderDetector::new(new_config.order_detection.clone(), &new_config.hts.profiles)?;
+    let (previous_api, output_changed) = {
+        let config = state.config.lock().unwrap();
+        (config.api.clone(), config.output_dir != new_config.output_dir)
+    };
+    let trade_store = if output_changed {
+        let cipher = state.cipher.lock().unwrap().clone();
+        Some(TradeStore::open(&new_config.output_dir, cipher.as_ref())?)
+    } else {
+        None
+    };
+    
+    // 실패할 수 있는 적용 단계: API 서버를 먼저 다시 열고, 웹훅 설정이 실패하면 되돌림
+    apply_api_config(&state, &new_config.api).await?;
+    if let Err(e) = state.webhooks.set_config(new_config.webhooks.clone(), &new_config.output_dir) {
+        if let Err(restore) = apply_api_config(&state, &previous_api).await {
+            eprintln!("Failed to restore API server: {}", restore);
+        }
+        return Err(e);
+    }
+    
+    *state.schedule.lock().unwrap() = schedule;
+    *state.log_tailers.lock().unwrap() = trade_log::open_tailers(&new_config.trade_logs);
+    *state.order_detector.lock().unwrap() = detector;
+    state.monitor.lock().unwrap().set_profiles(new_config.hts.profiles.clone());
+    if let Some(trade_store) = trade_store {
+        *state.trade_store.lock().unwrap() = trade_store;
+    }
+    
+    // 적용은 끝났으므로 저장에 실패해도 이번 실행 동안은 새 설정을 씀
+    let saved = new_config.save_to_file(&config_path());
+    *state.config.lock().unwrap() = new_config;
+    saved.map_err(|e| format!("Settings applied but not saved: {}", e))
+}
+
+#[tauri::command]
+async fn list_profiles(state: State<'_, AppState>) -> Result<Vec<BrokerProfile>, String> {
+    Ok(state.config.lock().unwrap().hts.profiles.clone())
+}
+
+/// 프로필 파일 가져오기 (같은 ID는 교체). 가져온 프로필 수를 돌려준다.
+#[tauri::command]
+async fn import_profiles(state: State<'_, AppState>, path: String) -> Result<usize, String> {
+    let imported = profiles::import_profiles(&path)?;
+    let count = imported.len();
+    
     let mut config = state.config.lock().unwrap();
-    *config = new_config;
+    let mut merged = config.hts.profiles.clone();
+    profiles::merge_profiles(&mut merged, imported);
+    
+    // 주문창 기준 이미지를 못 읽으면 가져오지 않음
+    let detector = OrderDetector::new(config.order_detection.clone(), &merged)?;
+    *state.order_detector.lock().unwrap() = detector;
+    state.monitor.lock().unwrap().set_profiles(merged.clone());
+    config.hts.profiles = merged;
+    config
+        .save_to_file(&config_path())
+        .map_err(|e| format!("Profiles imported but not saved: {}", e))?;
+    
+    Ok(count)
+}
+
+/// 프로필 내보내기 (`ids`가 없으면 전체)
+#[tauri::command]
+async fn export_profiles(
+    state: State<'_, AppState>,
+    path: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    let selected: Vec<BrokerProfile> = state
+        .config
+        .lock()
+        .unwrap()
+        .hts
+        .profiles
+        .iter()
+        .filter(|p| ids.as_ref().is_none_or(|ids| ids.contains(&p.id)))
+        .cloned()
+        .collect();
+    
+    profiles::export_profiles(&path, &selected)?;
+    Ok(selected.len())
+}
+
+/// 웹훅 엔드포인트에 시험 이벤트를 바로 보냄 (대기열/재시도 없음)
+#[tauri::command]
+async fn test_webhook(state: State<'_, AppState>, endpoint_id: String) -> Result<(), String> {
+    let webhooks = Arc::clone(&state.webhooks);
+    tokio::task::spawn_blocking(move || webhooks.send_test(&endpoint_id))
+        .await
+        .map_err(|e| format!("Webhook task failed: {}", e))?
+}
+
+/// 로컬 API용 새 토큰 (설정에 넣어 저장해야 적용됨)
+#[tauri::command]
+async fn generate_api_token() -> Result<String, String> {
+    Ok(api::generate_token())
+}
+
+/// 설정에 맞게 로컬 API 서버 시작/재시작/중지 (설정이 같으면 그대로)
+async fn apply_api_config(state: &AppState, config: &ApiConfig) -> Result<(), String> {
+    api::validate_config(config)?;
+    let mut server = state.api_server.lock().await;
+    if server.as_ref().map(ApiServer::config) == Some(config) {
+        return Ok(());
+    }
+    
+    // 같은 포트로 다시 열 수 있도록 이전 서버가 멈출 때까지 대기
+    if let Some(running) = server.take() {
+        running.stop().await;
+    }
+    if config.enabled {
+        let started = ApiServer::start(config, state.api_requests.clone(), state.events.clone()).await?;
+        *server = Some(started);
+    }
     Ok(())
 }
 
+/// 로컬 API 명령 처리 (UI 명령과 같은 경로라 상태 전이/이벤트도 똑같이 나감)
+async fn api_request_task(app_handle: AppHandle, state: Arc<AppState>, mut requests: mpsc::Receiver<ApiRequest>) {
+    while let Some(request) = requests.recv().await {
+        let reply = request.reply;
+        match request.command {
+            ApiCommand::Status => reply.send(Ok(recording_status(&state))),
+            ApiCommand::Start => {
+                let result = dispatch_event(&app_handle, &state, RecorderEvent::ManualStart).await;
+                reply.send(result.map(|outcome| outcome.started));
+            }
+            ApiCommand::Stop => {
+                let result = dispatch_event(&app_handle, &state, RecorderEvent::ManualStop).await;
+                reply.send(result.and_then(|outcome| outcome.summary.ok_or_else(|| "Not recording".to_string())));
+            }
+            ApiCommand::Pause => {
+                let result = dispatch_event(&app_handle, &state, RecorderEvent::ManualPause).await;
+                reply.send(result.map(|_| ()));
+            }
+            ApiCommand::Resume => {
+                let result = dispatch_event(&app_handle, &state, RecorderEvent::ManualResume).await;
+                reply.send(result.map(|_| ()));
+            }
+            ApiCommand::AddMarker(marker) => {
+                let result = marker.into_event(chrono::Utc::now());
+                reply.send(result.map(|event| record_trade_event(&app_handle, &state, event)));
+            }
+            ApiCommand::ListSessions => {
+                let output_dir = state.config.lock().unwrap().output_dir.clone();
+                reply.send(session::list_sessions(&output_dir));
+            }
+        }
+    }
+}
+
 // 백그라운드 모니터링 태스크
 async fn background_monitoring_task(app_handle: AppHandle, state: Arc<AppState>) {
     let mut check_interval = interval(Duration::from_secs(1));
     let mut was_hts_running = false;
+    // 자동 리포트를 이미 처리한 날짜 (KST)
+    let mut report_checked: Option<chrono::NaiveDate> = None;
     
     loop {
         check_interval.tick().await;
         
-        let monitor = state.monitor.lock().unwrap();
-        let is_hts_running = monitor.is_target_running().is_some();
-        drop(monitor);
+        let detected = state.monitor.lock().unwrap().detect();
+        let is_hts_running = detected.is_some();
         
-        // HTS 상태 변경 감지
-        if is_hts_running && !was_hts_running {
-            println!("HTS detected! Starting recording...");
-            
-            let recorder = state.recorder.lock().unwrap();
-            let config = state.config.lock().unwrap();
-            let _ = recorder.start_recording(&config.output_dir);
-            
-            // 프론트엔드에 알림
-            let _ = app_handle.emit_all("hts-detected", true);
-            let _ = app_handle.emit_all("recording-started", ());
-            
-        } else if !is_hts_running && was_hts_running {
-            println!("HTS closed! Stopping recording...");
-            
-            let recorder = state.recorder.lock().unwrap();
-            let _ = recorder.stop_recording();
-            
-            // 프론트엔드에 알림
-            let _ = app_handle.emit_all("hts-detected", false);
-            let _ = app_handle.emit_all("recording-stopped", ());
-        }
+        // 다음 녹화 매니페스트에 남길 프로필
+        state.controller.lock().unwrap().set_detected_profile(detected);
         
+        // HTS 상태 변경 알림
+        if is_hts_running != was_hts_running {
+            println!("HTS {}", if is_hts_running { "detected" } else { "closed" });
+            emit_event(&app_handle, &state, "hts-detected", is_hts_running);
+        }
         was_hts_running = is_hts_running;
         
+        // 시작/중지 여부는 상태 머신이 결정 (수동 녹화 중이면 HTS 종료에도 계속 녹화)
+        let schedule_event = if state.schedule.lock().unwrap().is_open() {
+            RecorderEvent::ScheduleOpened
+        } else {
+            RecorderEvent::ScheduleClosed
+        };
+        if let Err(e) = dispatch_event(&app_handle, &state, schedule_event).await {
+            eprintln!("{}", e);
+        }
+        
+        let event = if is_hts_running {
+            RecorderEvent::HtsDetected
+        } else {
+            RecorderEvent::HtsExited
+        };
+        if let Err(e) = dispatch_event(&app_handle, &state, event).await {
+            eprintln!("{}", e);
+        }
+        
+        let disk_full = state.controller.lock().unwrap().recorder().is_disk_full();
+        if disk_full {
+            if let Err(e) = dispatch_event(&app_handle, &state, RecorderEvent::DiskFull).await {
+                eprintln!("{}", e);
+            }
+        }
+        
+        // 주문/체결 로그에 새로 추가된 거래
+        let trades: Vec<TradeEvent> = state
+            .log_tailers
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .flat_map(|tailer| match tailer.poll() {
+                Ok(events) => events,
+                Err(e) => {
+                    eprintln!("Trade log {}: {}", tailer.source().id, e);
+                    Vec::new()
+                }
+            })
+            .collect();
+        for event in trades {
+            record_trade_event(&app_handle, &state, event);
+        }
+        
+        // 그날 마지막 장 시간대가 끝나고 녹화가 멈추면 일일 리포트
+        let now = chrono::Utc::now();
+        let today = now.with_timezone(&schedule::kst()).date_naive();
+        let (auto_report, output_dir) = {
+            let config = state.config.lock().unwrap();
+            (config.report.auto_generate, config.output_dir.clone())
+        };
+        if auto_report && report_checked != Some(today) {
+            let day_close = state.schedule.lock().unwrap().day_close(today);
+            let recording = state.controller.lock().unwrap().recorder().is_recording();
+            if report::report_due(&output_dir, today, day_close, now, recording) {
+                report_checked = Some(today);
+                auto_generate_report(&app_handle, &state, today).await;
+            }
+        }
+        
         // 주기적으로 상태 전송
-        if is_hts_running {
-            let recorder = state.recorder.lock().unwrap();
-            let duration = recorder.get_recording_duration();
-            let _ = app_handle.emit_all("recording-duration", duration);
+        let duration = state.controller.lock().unwrap().recorder().get_recording_duration();
+        if duration.is_some() {
+            emit_event(&app_handle, &state, "recording-duration", duration);
         }
     }
 }
 
+/// 앱 설정 파일을 바꾸는 환경 변수
+const CONFIG_PATH_ENV: &str = "TEST_RECORDER_CONFIG";
+
+/// 앱 설정 파일 경로 (CLI `--config`와 같은 형식)
+fn config_path() -> String {
+    std::env::var(CONFIG_PATH_ENV).unwrap_or_else(|_| "config.json".to_string())
+}
+
+/// 저장된 설정 읽기 (없거나 못 읽으면 기본 설정)
+fn load_config() -> AppConfig {
+    let path = config_path();
+    if !Path::new(&path).exists() {
+        return AppConfig::default();
+    }
+    AppConfig::load_from_file(&path).unwrap_or_else(|e| {
+        eprintln!("{}: using default settings", e);
+        AppConfig::default()
+    })
+}
+
 fn main() {
     // 설정 로드
-    let config = AppConfig::default();
+    let config = load_config();
+    
+    // 키체인/환경 변수로 열 수 있으면 바로 잠금 해제
+    let cipher = match crypto::open_cipher(&config.encryption, &config.output_dir, None) {
+        Ok(cipher) => cipher,
+        Err(e) => {
+            println!("Recordings locked: {}", e);
+            None
+        }
+    };
+    let recorder = ScreenRecorder::new();
+    recorder.set_cipher(cipher.clone());
+    
+    let schedule = MarketSchedule::load(&config.schedule, Arc::new(SystemClock)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        let mut fallback = config.schedule.clone();
+        fallback.holiday_file = None;
+        MarketSchedule::new(fallback, Default::default(), Arc::new(SystemClock))
+    });
+    
+    let order_detector = OrderDetector::new(config.order_detection.clone(), &config.hts.profiles)
+        .unwrap_or_else(|e| {
+            // 기준 이미지를 못 읽어도 제목 기반 감지는 계속
+            eprintln!("{}", e);
+            let mut profiles = config.hts.profiles.clone();
+            for dialog in profiles.iter_mut().flat_map(|p| p.order_dialogs.iter_mut()) {
+                dialog.templates.clear();
+            }
+            OrderDetector::new(config.order_detection.clone(), &profiles)
+                .expect("order detector without templates")
+        });
+    
+    let log_tailers = trade_log::open_tailers(&config.trade_logs);
+    
+    // 지난 거래 내역 (최근 기간만 메모리에)
+    let trade_store = TradeStore::open(&config.output_dir, cipher.as_ref()).expect("failed to open trade history");
+    println!("Loaded {} recent trades", trade_store.recent_len());
+    
+    // 보내지 못한 웹훅은 출력 디렉토리에 남아 있다가 다시 보냄
+    let webhooks = Arc::new(
+        Webhooks::open(config.webhooks.clone(), &config.output_dir).expect("failed to open webhook outbox"),
+    );
+    if let Err(e) = webhooks.spawn_sender() {
+        eprintln!("{}", e);
+    }
+    
+    // 로컬 API 요청은 앱 쪽 태스크 하나가 순서대로 처리
+    let (api_requests, api_receiver) = mpsc::channel(32);
     
     // 상태 초기화
     let app_state = Arc::new(AppState {
-        recorder: Arc::new(Mutex::new(ScreenRecorder::new())),
+        // 기존 동작대로 HTS 감지 시 자동 녹화
+        controller: Arc::new(Mutex::new(RecordingController::new(recorder, true))),
         monitor: Arc::new(Mutex::new(ProcessMonitor::new(
-            config.hts.process_names.clone(),
+            config.hts.profiles.clone(),
         ))),
         capture: Arc::new(Mutex::new(WindowCapture::new())),
         config: Arc::new(Mutex::new(config)),
-        trade_history: Arc::new(Mutex::new(Vec::new())),
+        schedule: Arc::new(Mutex::new(schedule)),
+        order_detector: Arc::new(Mutex::new(order_detector)),
+        log_tailers: Arc::new(Mutex::new(log_tailers)),
+        trade_store: Arc::new(Mutex::new(trade_store)),
+        cipher: Arc::new(Mutex::new(cipher)),
+        events: EventBus::new(),
+        api_requests,
+        api_server: Arc::new(tokio::sync::Mutex::new(None)),
+        webhooks,
     });
+    let protocol_state = Arc::clone(&app_state);
     
     tauri::Builder::default()
         .manage(app_state.clone())
+        .register_uri_scheme_protocol(playback::SCHEME, move |_app, request| {
+            playback::handle(&protocol_state, request)
+        })
         .invoke_handler(tauri::generate_handler![
             start_monitoring,
             stop_monitoring,
+            pause_recording,
+            resume_recording,
+            set_auto_mode,
+            get_recorder_state,
             get_recording_status,
+            get_schedule_status,
             capture_screenshot,
+            unlock_recordings,
             list_windows,
+            list_sessions,
+            verify_session,
             get_trade_history,
+            import_trade_log,
+            edit_trade_journal,
+            attach_trade_screenshot,
+            get_trade_revisions,
+            get_positions,
+            export_daily_report,
+            export_data,
+            get_thumbnails,
+            get_activity_timeline,
+            export_clip,
             get_config,
             update_config,
+            list_profiles,
+            import_profiles,
+            export_profiles,
+            generate_api_token,
+            test_webhook,
         ])
         .setup(move |app| {
             let app_handle = app.handle();
             
+            // 주문창 감지 (윈도우 목록/화면 캡처가 블로킹이라 전용 스레드)
+            let detector_handle = app_handle.clone();
+            let detector_state = Arc::clone(&app_state);
+            std::thread::spawn(move || {
+                OrderDetector::run(
+                    Arc::clone(&detector_state.order_detector),
+                    CancellationToken::new(),
+                    |detection| record_order_detection(&detector_handle, &detector_state, detection),
+                );
+            });
+            
+            // 로컬 API (설정에서 켠 경우만 서버를 엶)
+            let api_handle = app_handle.clone();
+            let api_state = Arc::clone(&app_state);
+            tauri::async_runtime::spawn(async move {
+                let config = api_state.config.lock().unwrap().api.clone();
+                if let Err(e) = apply_api_config(&api_state, &config).await {
+                    eprintln!("{}", e);
+                }
+                api_request_task(api_handle, api_state, api_receiver).await;
+            });
+            
             // Tokio 런타임에서 백그라운드 태스크 실행
             tauri::async_runtime::spawn(async move {
                 background_monitoring_task(app_handle, app_state).await;
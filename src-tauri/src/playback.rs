@@ -0,0 +1,58 @@
+use std::path::{Component, Path};
+use tauri::http::{Request, Response, ResponseBuilder};
+
+use test_recorder_core::storage;
+
+use crate::AppState;
+
+/// 재생용 URI 스킴 이름 (`recording://localhost/<session_id>/<file>`)
+pub const SCHEME: &str = "recording";
+
+fn mime_type(file: &str) -> &'static str {
+    match Path::new(storage::plain_file_name(file))
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("json") => "application/json",
+        Some("vtt") => "text/vtt",
+        _ => "application/octet-stream",
+    }
+}
+
+/// URI에서 `<session_id>/<file>` 부분만 추출
+fn request_path(uri: &str) -> &str {
+    let rest = uri.split_once("://").map(|(_, rest)| rest).unwrap_or(uri);
+    let path = rest.split_once('/').map(|(_, path)| path).unwrap_or("");
+    path.split(['?', '#']).next().unwrap_or("")
+}
+
+/// 녹화 파일 요청 처리
+///
+/// 암호화된 세션 파일은 여기서 복호화해서 돌려주므로 프론트엔드는
+/// 암호화 여부를 신경 쓰지 않아도 된다.
+pub fn handle(state: &AppState, request: &Request) -> Result<Response, Box<dyn std::error::Error>> {
+    let path = request_path(request.uri());
+    let relative = Path::new(path);
+
+    // 출력 디렉토리 밖으로 나가는 경로 차단
+    let is_safe = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if path.is_empty() || !is_safe {
+        return ResponseBuilder::new().status(400).body(Vec::new());
+    }
+
+    let output_dir = state.config.lock().unwrap().output_dir.clone();
+    let cipher = state.cipher.lock().unwrap().clone();
+
+    match storage::read_file(&Path::new(&output_dir).join(relative), cipher.as_ref()) {
+        Ok(data) => ResponseBuilder::new()
+            .mimetype(mime_type(path))
+            .status(200)
+            .body(data),
+        Err(e) => {
+            eprintln!("Playback request failed ({}): {}", path, e);
+            ResponseBuilder::new().status(404).body(Vec::new())
+        }
+    }
+}
@@ -44,7 +44,7 @@ export const TradeHistory: React.FC<TradeHistoryProps> = ({ trades, onRefresh })
     <div className="trade-history">
       <div className="history-header">
         <h2>📋 거래 내역</h2>
-        <button onClick={onRefresh} className="btn btn-sm">
+        <button onClick={() => onRefresh()} className="btn btn-sm">
           🔄 새로고침
         </button>
       </div>
@@ -56,21 +56,43 @@ export const TradeHistory: React.FC<TradeHistoryProps> = ({ trades, onRefresh })
         </div>
       ) : (
         <div className="trade-list">
-          {trades.map((trade, index) => (
-            <div key={index} className="trade-item">
+          {trades.map((trade) => (
+            <div key={trade.id} className="trade-item">
               <div className="trade-icon">{getActionIcon(trade.action)}</div>
               <div className="trade-details">
                 <div className="trade-action">
                   <strong>{getActionText(trade.action)}</strong>
+                  {trade.ticker && (
+                    <span className="trade-instrument">
+                      {' '}{trade.instrument_name ?? trade.ticker}
+                      {trade.quantity != null && ` ${trade.quantity}주`}
+                      {trade.price != null && ` @${trade.price.toLocaleString('ko-KR')}`}
+                    </span>
+                  )}
                 </div>
                 <div className="trade-time">{formatTime(trade.timestamp)}</div>
-                <div className="trade-window">{trade.window_title}</div>
-              </div>
-              <div className="trade-screenshot">
-                <span className="screenshot-path" title={trade.screenshot_path}>
-                  📁 {trade.screenshot_path.split('/').pop()}
-                </span>
+                {trade.window_title && <div className="trade-window">{trade.window_title}</div>}
+                {(trade.rating != null || trade.tags.length > 0) && (
+                  <div className="trade-journal">
+                    {trade.rating != null && (
+                      <span className="trade-rating">{'★'.repeat(trade.rating)}{'☆'.repeat(5 - trade.rating)}</span>
+                    )}
+                    {trade.tags.map((tag) => (
+                      <span key={tag} className="trade-tag">#{tag}</span>
+                    ))}
+                  </div>
+                )}
+                {trade.notes.map((note) => (
+                  <div key={note.id} className="trade-note">📝 {note.text}</div>
+                ))}
               </div>
+              {trade.screenshot_path && (
+                <div className="trade-screenshot">
+                  <span className="screenshot-path" title={trade.screenshot_path}>
+                    📁 {trade.screenshot_path.split('/').pop()}
+                  </span>
+                </div>
+              )}
             </div>
           ))}
         </div>
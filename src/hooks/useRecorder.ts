@@ -2,18 +2,274 @@ import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
 
+export interface PipelineStats {
+  captured: number;
+  encoded: number;
+  written: number;
+  dropped: number;
+  bytes_written: number;
+  errors: number;
+  backlog: number;
+}
+
+export type RecorderState =
+  | { state: 'idle' }
+  | { state: 'armed' }
+  | { state: 'recording' }
+  | { state: 'paused' }
+  | { state: 'finalizing' }
+  | { state: 'error'; message: string };
+
+export type TransitionReason = 'manual' | 'hts_detected' | 'hts_exited' | 'schedule' | 'disk_full';
+
+export interface StateTransition {
+  from: RecorderState;
+  to: RecorderState;
+  reason: TransitionReason;
+  at: string;
+}
+
+export type MarketSession = 'pre_market' | 'regular' | 'after_hours_closing' | 'after_hours_single_price';
+
+export interface ScheduleStatus {
+  enabled: boolean;
+  open: boolean;
+  session: MarketSession | null;
+  next_open: string | null;
+}
+
+export interface DetectedProfile {
+  profile_id: string;
+  profile_name: string;
+  process_name: string;
+}
+
 export interface RecordingStatus {
   is_recording: boolean;
   hts_detected: boolean;
   hts_name: string | null;
   recording_duration: number | null;
+  pipeline?: PipelineStats | null;
+  state?: RecorderState | null;
+  profile?: DetectedProfile | null;
 }
 
+export interface SessionSummary {
+  session_id: string;
+  path: string;
+  frame_count: number;
+  duration_secs: number;
+  bytes_written: number;
+  dropped_frames: number;
+}
+
+export type TradeSource = 'manual' | 'ocr' | 'dialog' | 'log_import';
+
+export type Market = 'kospi' | 'kosdaq' | 'konex' | 'derivatives' | 'overseas' | 'other';
+
+export type OrderType =
+  | 'limit'
+  | 'market'
+  | 'conditional_limit'
+  | 'best_limit'
+  | 'priority_limit'
+  | 'other';
+
+export type FillStatus = 'submitted' | 'partially_filled' | 'filled' | 'cancelled' | 'rejected';
+
+export interface SessionRef {
+  session_id: string;
+  offset_ms: number;
+  frame_index: number | null;
+}
+
+export interface TradeNote {
+  id: string;
+  text: string;
+  created_at: string;
+  updated_at: string | null;
+}
+
+export interface TradeAttachment {
+  path: string;
+  caption: string | null;
+  added_at: string;
+}
+
+// 거래 저널 수정
+export type JournalEdit =
+  | { op: 'add_note'; text: string }
+  | { op: 'edit_note'; note_id: string; text: string }
+  | { op: 'delete_note'; note_id: string }
+  | { op: 'add_tag'; tag: string }
+  | { op: 'remove_tag'; tag: string }
+  | { op: 'set_tags'; tags: string[] }
+  | { op: 'set_rating'; rating: number | null }
+  | { op: 'remove_screenshot'; path: string };
+
 export interface TradeEvent {
+  version: number;
+  id: string;
   action: 'buy' | 'sell' | 'unknown';
   timestamp: string;
-  screenshot_path: string;
-  window_title: string;
+  source: TradeSource;
+  ticker: string | null;
+  instrument_name: string | null;
+  market: Market | null;
+  quantity: number | null;
+  price: number | null;
+  order_type: OrderType | null;
+  order_id: string | null;
+  fill_status: FillStatus | null;
+  confidence: number | null;
+  screenshot_path: string | null;
+  window_title: string | null;
+  session: SessionRef | null;
+  notes: TradeNote[];
+  tags: string[];
+  rating: number | null;
+  attachments: TradeAttachment[];
+  revision: number;
+  edited_at: string | null;
+}
+
+export interface TradePage {
+  events: TradeEvent[];
+  total: number;
+  offset: number;
+  limit: number;
+}
+
+export interface TradeHistoryQuery {
+  from?: string;
+  to?: string;
+  offset?: number;
+  limit?: number;
+  text?: string;
+  tags?: string[];
+  min_rating?: number;
+}
+
+export interface ClipLink {
+  session_id: string;
+  start_ms: number;
+  end_ms: number;
+  start_frame: number | null;
+  end_frame: number | null;
+}
+
+// 포지션이 0에서 다시 0이 될 때까지의 왕복 매매
+export interface RoundTrip {
+  id: string;
+  ticker: string;
+  instrument_name: string | null;
+  market: Market | null;
+  opened_at: string;
+  closed_at: string;
+  quantity: number;
+  avg_entry_price: number;
+  avg_exit_price: number;
+  gross_pnl: number;
+  fees: number;
+  taxes: number;
+  net_pnl: number;
+  return_pct: number;
+  holding_secs: number;
+  avg_lot_holding_secs: number;
+  entry_trade_ids: string[];
+  exit_trade_ids: string[];
+  clips: ClipLink[];
+}
+
+export interface OpenPosition {
+  ticker: string;
+  instrument_name: string | null;
+  quantity: number;
+  avg_price: number;
+  opened_at: string;
+  fees: number;
+  taxes: number;
+  realized_pnl: number;
+  trade_ids: string[];
+}
+
+export interface PositionReport {
+  round_trips: RoundTrip[];
+  open_positions: OpenPosition[];
+  summary: {
+    round_trips: number;
+    wins: number;
+    losses: number;
+    gross_pnl: number;
+    fees: number;
+    taxes: number;
+    net_pnl: number;
+  };
+  unmatched_sells: string[];
+  skipped: number;
+}
+
+// 세션 썸네일 트랙 (경로는 세션 디렉토리 기준)
+export interface ThumbnailTrack {
+  track: string;
+  vtt: string;
+  sheets: string[];
+  count: number;
+  tile_width: number;
+  tile_height: number;
+  interval_ms: number;
+}
+
+// 재생 스킴으로 세션 파일 URL 만들기 (Windows 웹뷰는 https://recording.localhost/ 형식)
+export function recordingUrl(sessionId: string, file: string): string {
+  const path = `${sessionId}/${file}`;
+  return navigator.userAgent.includes('Windows')
+    ? `https://recording.localhost/${path}`
+    : `recording://localhost/${path}`;
+}
+
+export interface ActivityPoint {
+  offset_ms: number;
+  max: number;
+  mean: number;
+}
+
+export interface ActivityBurst {
+  start_ms: number;
+  end_ms: number;
+  peak_score: number;
+  peak_offset_ms: number;
+  peak_frame: number;
+  total: number;
+}
+
+export interface ActivityTimeline {
+  session_id: string;
+  duration_ms: number;
+  bucket_ms: number;
+  points: ActivityPoint[];
+  bursts: ActivityBurst[];
+  burst_threshold: number;
+}
+
+export interface ClipExport {
+  path: string;
+  frames: number;
+  input_rendered: boolean;
+}
+
+export type ExportFormat = 'csv' | 'parquet';
+
+export interface ExportFilter {
+  from?: string;
+  to?: string;
+  tickers?: string[];
+}
+
+export interface ExportedFile {
+  dataset: 'trades' | 'round_trips' | 'sessions' | 'frames';
+  path: string;
+  rows: number;
 }
 
 export function useRecorder() {
@@ -44,6 +300,9 @@ export function useRecorder() {
     let unlistenRecordingStarted: UnlistenFn;
     let unlistenRecordingStopped: UnlistenFn;
     let unlistenDuration: UnlistenFn;
+    let unlistenState: UnlistenFn;
+    let unlistenTrade: UnlistenFn;
+    let unlistenTradeUpdated: UnlistenFn;
 
     const setupListeners = async () => {
       // HTS 감지 이벤트
@@ -71,6 +330,24 @@ export function useRecorder() {
         const duration = event.payload as number | null;
         setStatus(prev => ({ ...prev, recording_duration: duration }));
       });
+
+      // 녹화 상태 전이 이벤트
+      unlistenState = await listen('recording-state', (event) => {
+        const transition = event.payload as StateTransition;
+        setStatus(prev => ({ ...prev, state: transition.to }));
+      });
+
+      // 주문창 감지 이벤트
+      unlistenTrade = await listen('trade-detected', (event) => {
+        const trade = event.payload as TradeEvent;
+        setTradeHistory(prev => [trade, ...prev.filter(t => t.id !== trade.id)]);
+      });
+
+      // 거래 저널 수정 이벤트
+      unlistenTradeUpdated = await listen('trade-updated', (event) => {
+        const trade = event.payload as TradeEvent;
+        setTradeHistory(prev => prev.map(t => (t.id === trade.id ? trade : t)));
+      });
     };
 
     setupListeners();
@@ -84,6 +361,9 @@ export function useRecorder() {
       unlistenRecordingStarted?.();
       unlistenRecordingStopped?.();
       unlistenDuration?.();
+      unlistenState?.();
+      unlistenTrade?.();
+      unlistenTradeUpdated?.();
     };
   }, [updateStatus]);
 
@@ -103,7 +383,7 @@ export function useRecorder() {
   // 모니터링 중지
   const stopMonitoring = useCallback(async () => {
     try {
-      const result = await invoke<string>('stop_monitoring');
+      const result = await invoke<SessionSummary>('stop_monitoring');
       console.log(result);
       await updateStatus();
       setError(null);
@@ -113,6 +393,40 @@ export function useRecorder() {
     }
   }, [updateStatus]);
 
+  // 녹화 일시정지/재개
+  const pauseRecording = useCallback(async () => {
+    try {
+      await invoke('pause_recording');
+      await updateStatus();
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, [updateStatus]);
+
+  const resumeRecording = useCallback(async () => {
+    try {
+      await invoke('resume_recording');
+      await updateStatus();
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, [updateStatus]);
+
+  // HTS 감지 시 자동 녹화 켜기/끄기
+  const setAutoMode = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke('set_auto_mode', { enabled });
+      setError(null);
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
   // 스크린샷 캡처
   const captureScreenshot = useCallback(async () => {
     try {
@@ -125,17 +439,139 @@ export function useRecorder() {
     }
   }, []);
 
-  // 거래 내역 가져오기
-  const fetchTradeHistory = useCallback(async () => {
+  // 거래 내역 가져오기 (최신순)
+  const fetchTradeHistory = useCallback(async (query: TradeHistoryQuery = {}) => {
     try {
-      const history = await invoke<TradeEvent[]>('get_trade_history');
-      setTradeHistory(history);
+      const page = await invoke<TradePage>('get_trade_history', { query });
+      setTradeHistory(page.events);
       setError(null);
     } catch (err) {
       setError(String(err));
     }
   }, []);
 
+  // 주문/체결 로그 가져오기 (date: YYYY-MM-DD)
+  const importTradeLog = useCallback(async (sourceId: string, date?: string) => {
+    try {
+      const trades = await invoke<TradeEvent[]>('import_trade_log', { sourceId, date });
+      await fetchTradeHistory();
+      return trades;
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, [fetchTradeHistory]);
+
+  // 거래 저널 수정 (메모, 태그, 실행 평가)
+  const editTradeJournal = useCallback(async (tradeId: string, edit: JournalEdit) => {
+    try {
+      return await invoke<TradeEvent>('edit_trade_journal', { tradeId, edit });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 스크린샷 첨부 (파일은 출력 디렉토리로 복사됨)
+  const attachTradeScreenshot = useCallback(async (tradeId: string, path: string, caption?: string) => {
+    try {
+      return await invoke<TradeEvent>('attach_trade_screenshot', { tradeId, path, caption });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 거래 수정 이력
+  const getTradeRevisions = useCallback(async (tradeId: string) => {
+    try {
+      return await invoke<TradeEvent[]>('get_trade_revisions', { tradeId });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 왕복 매매별 실현 손익
+  const getPositions = useCallback(async (query: TradeHistoryQuery = {}) => {
+    try {
+      return await invoke<PositionReport>('get_positions', { query });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 일일 리포트 HTML 생성 (date: YYYY-MM-DD, 없으면 오늘), 저장 경로 반환
+  const exportDailyReport = useCallback(async (date?: string) => {
+    try {
+      return await invoke<string>('export_daily_report', { date });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 타임라인 미리보기 썸네일 (없으면 생성)
+  const getThumbnails = useCallback(async (sessionId: string, regenerate = false) => {
+    try {
+      return await invoke<ThumbnailTrack[]>('get_thumbnails', { sessionId, regenerate });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 화면 활동 곡선과 활동 구간
+  const getActivityTimeline = useCallback(async (sessionId: string, points?: number) => {
+    try {
+      return await invoke<ActivityTimeline>('get_activity_timeline', { sessionId, points });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 세션 구간을 프레임 파일로 내보내기 (renderInput: 커서/클릭 표시, 없으면 설정값)
+  const exportClip = useCallback(async (sessionId: string, startMs: number, endMs: number, destDir: string, renderInput?: boolean) => {
+    try {
+      return await invoke<ClipExport>('export_clip', { sessionId, startMs, endMs, destDir, renderInput });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 분석용 데이터셋 내보내기 (거래, 왕복 매매, 세션, 프레임 트랙)
+  const exportData = useCallback(async (destDir: string, format: ExportFormat, filter?: ExportFilter) => {
+    try {
+      return await invoke<ExportedFile[]>('export_data', { destDir, format, filter });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 로컬 API용 새 토큰 (설정의 api.token에 넣어 저장)
+  const generateApiToken = useCallback(async () => {
+    try {
+      return await invoke<string>('generate_api_token');
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
+  // 웹훅 엔드포인트에 시험 이벤트 전송
+  const testWebhook = useCallback(async (endpointId: string) => {
+    try {
+      await invoke('test_webhook', { endpointId });
+    } catch (err) {
+      setError(String(err));
+      throw err;
+    }
+  }, []);
+
   // 윈도우 리스트 가져오기
   const listWindows = useCallback(async () => {
     try {
@@ -153,8 +589,23 @@ export function useRecorder() {
     error,
     startMonitoring,
     stopMonitoring,
+    pauseRecording,
+    resumeRecording,
+    setAutoMode,
     captureScreenshot,
     fetchTradeHistory,
+    importTradeLog,
+    editTradeJournal,
+    attachTradeScreenshot,
+    getTradeRevisions,
+    getPositions,
+    exportDailyReport,
+    exportData,
+    getThumbnails,
+    getActivityTimeline,
+    exportClip,
+    generateApiToken,
+    testWebhook,
     listWindows,
   };
 }